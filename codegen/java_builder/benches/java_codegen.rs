@@ -49,7 +49,9 @@ fn generate_100_projects(c: &mut Criterion) {
                 let example = sample_class(&pom_xml);
                 let mut mvn_code = MavenCodebase::new(pom_xml, &top_folder);
                 mvn_code = mvn_code.add_entity(example.clone());
-                mvn_code.generate_code();
+                mvn_code
+                    .generate_code()
+                    .expect("Generated code has syntax errors");
                 cleanup_folder(&top_folder);
             }
         });
@@ -0,0 +1,341 @@
+//offline knowledge about the artifacts the generator uses: which ones the spring boot bom
+//manages, which versions work with which spring boot version and which ones clash
+use std::{cmp::Ordering, collections::HashMap, path::Path, sync::OnceLock};
+
+use serde::Deserialize;
+
+use super::{
+    error::CodegenError,
+    pom_xml::{Library, PomXml},
+};
+use crate::java_structs::diagnostics::Diagnostic;
+
+const BUNDLED_CATALOG: &str = include_str!("../../resources/dependency-catalog.json");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Catalog {
+    pub spring_boot: SpringBootInfo,
+    pub artifacts: Vec<ArtifactInfo>,
+    #[serde(default)]
+    pub conflicts: Vec<Conflict>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpringBootInfo {
+    pub latest: String,
+    pub versions: Vec<String>,
+    //every artifact of these groups gets its version from spring-boot-dependencies
+    pub managed_groups: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactInfo {
+    pub group_id: String,
+    pub artifact_id: String,
+    //the spring boot versions it works with, any when missing
+    pub boot: Option<String>,
+    //only needed for artifacts the bom does not manage
+    #[serde(default)]
+    pub versions: Vec<VersionForBoot>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionForBoot {
+    //a maven version range over spring boot versions e.g. [3.4,3.5)
+    pub boot: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Conflict {
+    //groupId:artifactId
+    pub artifacts: Vec<String>,
+    pub message: String,
+}
+
+//the catalog shipped with the crate
+pub fn bundled() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        Catalog::from_json(BUNDLED_CATALOG).expect("The bundled dependency catalog is invalid")
+    })
+}
+
+fn coordinates(lib: &Library) -> String {
+    format!("{}:{}", lib.group_id(), lib.artifact_id)
+}
+
+impl Catalog {
+    pub fn from_json(json: &str) -> Result<Catalog, CodegenError> {
+        serde_json::from_str(json).map_err(|e| CodegenError::Parse(e.to_string()))
+    }
+
+    //a catalog kept up to date outside of the crate
+    pub fn load(path: &Path) -> Result<Catalog, CodegenError> {
+        let json = std::fs::read_to_string(path).map_err(|e| CodegenError::io(path, e))?;
+        Self::from_json(&json)
+    }
+
+    pub fn latest_spring_boot(&self) -> &str {
+        &self.spring_boot.latest
+    }
+
+    pub fn find(&self, group_id: &str, artifact_id: &str) -> Option<&ArtifactInfo> {
+        self.artifacts
+            .iter()
+            .find(|a| a.group_id == group_id && a.artifact_id == artifact_id)
+    }
+
+    pub fn is_managed_by_spring_boot(&self, lib: &Library) -> bool {
+        self.spring_boot
+            .managed_groups
+            .iter()
+            .any(|g| g == lib.group_id())
+    }
+
+    //the version to use for a library the bom does not manage
+    pub fn version_for(
+        &self,
+        group_id: &str,
+        artifact_id: &str,
+        boot_version: &str,
+    ) -> Option<&str> {
+        self.find(group_id, artifact_id)?
+            .versions
+            .iter()
+            .find(|v| VersionRange::parse(&v.boot).contains(boot_version))
+            .map(|v| v.version.as_str())
+    }
+
+    fn boot_version_of<'a>(&'a self, pom_xml: &'a PomXml) -> &'a str {
+        pom_xml
+            .spring_boot_version()
+            .unwrap_or(self.latest_spring_boot())
+    }
+
+    //where the build gets the version from when the pom does not declare one
+    fn is_managed(&self, pom_xml: &PomXml, lib: &Library) -> bool {
+        pom_xml.is_managed(lib)
+            || (pom_xml.spring_boot_version().is_some() && self.is_managed_by_spring_boot(lib))
+    }
+
+    //fills in the versions maven or gradle would not be able to find
+    pub fn resolve_missing_versions(&self, mut pom_xml: PomXml) -> PomXml {
+        let boot_version = self.boot_version_of(&pom_xml).to_owned();
+        let mut resolved = vec![];
+        for lib in pom_xml.dependencies.iter() {
+            if lib.version().is_some() || self.is_managed(&pom_xml, lib) {
+                resolved.push(lib.clone());
+                continue;
+            }
+            match self.version_for(lib.group_id(), &lib.artifact_id, &boot_version) {
+                Some(version) => resolved.push(lib.clone().with_version(version.to_owned())),
+                None => resolved.push(lib.clone()),
+            }
+        }
+        pom_xml.dependencies = resolved;
+        pom_xml
+    }
+
+    pub fn check(&self, pom_xml: &PomXml) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let boot_version = self.boot_version_of(pom_xml);
+        if let Some(v) = pom_xml.spring_boot_version() {
+            if !self.spring_boot.versions.iter().any(|known| known == v) {
+                diagnostics.push(Diagnostic::warning(
+                    "unknown-spring-boot-version",
+                    "spring-boot-starter-parent".to_owned(),
+                    format!(
+                        "Spring Boot {} is not in the catalog, versions might not fit",
+                        v
+                    ),
+                ));
+            }
+        }
+
+        let mut seen: HashMap<String, &Library> = HashMap::new();
+        for lib in pom_xml.dependencies.iter() {
+            let element = coordinates(lib);
+            if let Some(first) = seen.get(&element) {
+                if first.version() == lib.version() && first.scope == lib.scope {
+                    diagnostics.push(Diagnostic::warning(
+                        "duplicate-dependency",
+                        element.clone(),
+                        "Declared more than once".to_owned(),
+                    ));
+                } else {
+                    diagnostics.push(Diagnostic::error(
+                        "conflicting-dependency",
+                        element.clone(),
+                        "Declared more than once with a different version or scope".to_owned(),
+                    ));
+                }
+                continue;
+            }
+            seen.insert(element.clone(), lib);
+            self.check_library(pom_xml, lib, boot_version, &mut diagnostics);
+        }
+
+        for conflict in self.conflicts.iter() {
+            if conflict.artifacts.iter().all(|a| seen.contains_key(a)) {
+                diagnostics.push(Diagnostic::warning(
+                    "conflicting-libraries",
+                    conflict.artifacts.join(", "),
+                    conflict.message.clone(),
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    fn check_library(
+        &self,
+        pom_xml: &PomXml,
+        lib: &Library,
+        boot_version: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let element = coordinates(lib);
+        let info = self.find(lib.group_id(), &lib.artifact_id);
+        let managed = self.is_managed(pom_xml, lib);
+        if info.is_none() && !self.is_managed_by_spring_boot(lib) {
+            diagnostics.push(Diagnostic::warning(
+                "unknown-artifact",
+                element.clone(),
+                "Not in the dependency catalog, its version cannot be checked".to_owned(),
+            ));
+        }
+        if let Some(range) = info.and_then(|i| i.boot.as_ref()) {
+            if !VersionRange::parse(range).contains(boot_version) {
+                diagnostics.push(Diagnostic::error(
+                    "incompatible-version",
+                    element.clone(),
+                    format!(
+                        "Needs Spring Boot {}, the project uses {}",
+                        range, boot_version
+                    ),
+                ));
+            }
+        }
+        match lib.version() {
+            None if managed => {}
+            None => match self.version_for(lib.group_id(), &lib.artifact_id, boot_version) {
+                Some(v) => diagnostics.push(Diagnostic::warning(
+                    "unmanaged-version",
+                    element,
+                    format!(
+                        "Not managed by the parent bom, version {} from the catalog will be used",
+                        v
+                    ),
+                )),
+                None => diagnostics.push(Diagnostic::error(
+                    "missing-version",
+                    element,
+                    "Not managed by the parent bom and no version is known".to_owned(),
+                )),
+            },
+            Some(v) => {
+                let expected = self.version_for(lib.group_id(), &lib.artifact_id, boot_version);
+                if let Some(expected) = expected.filter(|e| *e != v && !v.starts_with("${")) {
+                    diagnostics.push(Diagnostic::warning(
+                        "incompatible-version",
+                        element,
+                        format!(
+                            "Version {} is not the one known to work with Spring Boot {} ({})",
+                            v, boot_version, expected
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+//a maven version range like [3.4,3.5), (,3.0] or [3.1,), a bare version only matches itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRange {
+    lower: Option<(String, bool)>,
+    upper: Option<(String, bool)>,
+}
+
+impl VersionRange {
+    pub fn parse(range: &str) -> Self {
+        let range = range.trim();
+        let (Some(first), Some(last)) = (range.chars().next(), range.chars().last()) else {
+            return Self {
+                lower: None,
+                upper: None,
+            };
+        };
+        if !matches!(first, '[' | '(') {
+            let exact = Some((range.to_owned(), true));
+            return Self {
+                lower: exact.clone(),
+                upper: exact,
+            };
+        }
+        //an unbalanced "[" or "[1.0" has no closing bracket to strip
+        let rest = &range[first.len_utf8()..];
+        let inner = rest.strip_suffix([']', ')']).unwrap_or(rest);
+        let bound = |s: &str, inclusive: bool| {
+            let s = s.trim();
+            (!s.is_empty()).then(|| (s.to_owned(), inclusive))
+        };
+        match inner.split_once(',') {
+            Some((lower, upper)) => Self {
+                lower: bound(lower, first == '['),
+                upper: bound(upper, last == ']'),
+            },
+            //[1.0] is an exact version
+            None => Self {
+                lower: bound(inner, true),
+                upper: bound(inner, true),
+            },
+        }
+    }
+
+    pub fn contains(&self, version: &str) -> bool {
+        let above_lower = match self.lower {
+            Some((ref v, inclusive)) => match compare_versions(version, v) {
+                Ordering::Greater => true,
+                Ordering::Equal => inclusive,
+                Ordering::Less => false,
+            },
+            None => true,
+        };
+        let below_upper = match self.upper {
+            Some((ref v, inclusive)) => match compare_versions(version, v) {
+                Ordering::Less => true,
+                Ordering::Equal => inclusive,
+                Ordering::Greater => false,
+            },
+            None => true,
+        };
+        above_lower && below_upper
+    }
+}
+
+//numeric segments are compared as numbers, missing ones count as 0 so 3.4 == 3.4.0
+//a qualifier like -M1 or -SNAPSHOT comes before the release
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| {
+        let (numbers, qualifier) = v.split_once('-').unwrap_or((v, ""));
+        let numbers: Vec<u64> = numbers.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+        (numbers, qualifier.to_owned())
+    };
+    let (a_numbers, a_qualifier) = split(a);
+    let (b_numbers, b_qualifier) = split(b);
+    for i in 0..a_numbers.len().max(b_numbers.len()) {
+        let x = a_numbers.get(i).copied().unwrap_or(0);
+        let y = b_numbers.get(i).copied().unwrap_or(0);
+        if x != y {
+            return x.cmp(&y);
+        }
+    }
+    match (a_qualifier.is_empty(), b_qualifier.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a_qualifier.cmp(&b_qualifier),
+    }
+}
@@ -0,0 +1,353 @@
+//pipelines for the generated project: build and test with the project's build system and,
+//when a Dockerfile is generated, build the image and push it to a container registry
+use super::{
+    docker::{ContainerTarget, DockerConfig},
+    maven_builder::BuildSystem,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CiProvider {
+    //.github/workflows/build.yml
+    GitHubActions,
+    //.gitlab-ci.yml
+    GitLab,
+}
+
+#[derive(Clone, Debug)]
+pub struct CiConfig {
+    pub providers: Vec<CiProvider>,
+    //pushes and image builds only happen here, pull requests are only tested
+    pub default_branch: String,
+    //ghcr.io on github and the project registry on gitlab when None
+    pub registry: Option<String>,
+    //build the image without pushing it
+    pub push_image: bool,
+}
+
+impl Default for CiConfig {
+    fn default() -> Self {
+        Self {
+            providers: vec![CiProvider::GitHubActions],
+            default_branch: "main".to_owned(),
+            registry: None,
+            push_image: true,
+        }
+    }
+}
+
+fn uses_testcontainers(target: &ContainerTarget) -> bool {
+    target
+        .pom_xml
+        .has_dependency_that(|d| d.group_id() == "org.testcontainers")
+}
+
+fn java_version(target: &ContainerTarget) -> String {
+    match target.pom_xml.java.as_str() {
+        "" => "17".to_owned(),
+        v => v.to_owned(),
+    }
+}
+
+fn image_repository(target: &ContainerTarget) -> String {
+    target.pom_xml.project_info.artifact_id.to_ascii_lowercase()
+}
+
+impl CiConfig {
+    pub fn new(providers: Vec<CiProvider>) -> Self {
+        Self {
+            providers,
+            ..Default::default()
+        }
+    }
+
+    pub fn default_branch(mut self, branch: String) -> Self {
+        self.default_branch = branch;
+        self
+    }
+
+    pub fn registry(mut self, registry: String) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    pub fn push_image(mut self, push: bool) -> Self {
+        self.push_image = push;
+        self
+    }
+
+    //relative path and content of every pipeline file
+    pub fn files(
+        &self,
+        target: &ContainerTarget,
+        docker: Option<&DockerConfig>,
+    ) -> Vec<(String, String)> {
+        self.providers
+            .iter()
+            .map(|provider| match provider {
+                CiProvider::GitHubActions => (
+                    ".github/workflows/build.yml".to_owned(),
+                    self.github_workflow(target, docker),
+                ),
+                CiProvider::GitLab => (".gitlab-ci.yml".to_owned(), self.gitlab_ci(target, docker)),
+            })
+            .collect()
+    }
+
+    pub fn github_workflow(
+        &self,
+        target: &ContainerTarget,
+        docker: Option<&DockerConfig>,
+    ) -> String {
+        let java = java_version(target);
+        let mut workflow = format!(
+            "name: {name}
+
+on:
+  push:
+    branches: [{branch}]
+  pull_request:
+
+jobs:
+  build:
+    runs-on: ubuntu-latest
+",
+            name = target.pom_xml.project_info.name,
+            branch = self.default_branch
+        );
+        //testcontainers starts its own database with the docker of the runner
+        if let (Some(db), Some(service), false) = (
+            target.db_info,
+            target.database_service(docker),
+            uses_testcontainers(target),
+        ) {
+            workflow.push_str(&format!(
+                "    services:
+      db:
+        image: {image}
+        env:
+{env}        ports:
+          - {db_port}:{port}
+        options: >-
+          --health-cmd \"{healthcheck}\"
+          --health-interval 5s
+          --health-timeout 5s
+          --health-retries 10
+",
+                image = service.image,
+                env = service
+                    .environment
+                    .iter()
+                    .map(|(k, v)| format!("          {}: {}\n", k, v))
+                    .collect::<String>(),
+                db_port = db.db_port,
+                port = service.port,
+                healthcheck = service.healthcheck
+            ));
+        }
+        workflow.push_str(&format!(
+            "    steps:
+      - uses: actions/checkout@v4
+      - uses: actions/setup-java@v4
+        with:
+          distribution: temurin
+          java-version: '{java}'
+"
+        ));
+        match target.build_system {
+            BuildSystem::Maven => workflow.push_str(
+                "          cache: maven
+      - name: Build and test
+        run: mvn -B verify
+",
+            ),
+            BuildSystem::Gradle(gradle) => workflow.push_str(&format!(
+                "      - uses: gradle/actions/setup-gradle@v4
+        with:
+          gradle-version: '{}'
+      - name: Build and test
+        run: gradle build --no-daemon
+",
+                gradle.gradle_version
+            )),
+        }
+
+        if docker.is_none() {
+            return workflow;
+        }
+        let registry = self.registry.clone().unwrap_or("ghcr.io".to_owned());
+        let image = match self.registry {
+            Some(_) => format!("{}/{}", registry, image_repository(target)),
+            None => format!(
+                "ghcr.io/${{{{ github.repository_owner }}}}/{}",
+                image_repository(target)
+            ),
+        };
+        let push = match self.push_image {
+            true => "${{ github.event_name == 'push' }}",
+            false => "false",
+        };
+        workflow.push_str(
+            "
+  image:
+    needs: build
+    runs-on: ubuntu-latest
+    permissions:
+      contents: read
+      packages: write
+    steps:
+      - uses: actions/checkout@v4
+      - uses: docker/setup-buildx-action@v3
+",
+        );
+        if self.push_image {
+            //ghcr.io takes the token of the workflow, other registries need secrets
+            let (username, password) = match self.registry {
+                Some(_) => ("secrets.REGISTRY_USER", "secrets.REGISTRY_PASSWORD"),
+                None => ("github.actor", "secrets.GITHUB_TOKEN"),
+            };
+            workflow.push_str(&format!(
+                "      - uses: docker/login-action@v3
+        if: github.event_name == 'push'
+        with:
+          registry: {registry}
+          username: ${{{{ {username} }}}}
+          password: ${{{{ {password} }}}}
+"
+            ));
+        }
+        workflow.push_str(&format!(
+            "      - uses: docker/build-push-action@v6
+        with:
+          context: .
+          push: {push}
+          tags: |
+            {image}:latest
+            {image}:${{{{ github.sha }}}}
+"
+        ));
+        workflow
+    }
+
+    pub fn gitlab_ci(&self, target: &ContainerTarget, docker: Option<&DockerConfig>) -> String {
+        let java = java_version(target);
+        let mut pipeline = "stages:\n  - test\n".to_owned();
+        if docker.is_some() {
+            pipeline.push_str("  - package\n");
+        }
+        let (image, cache, script, reports) = match target.build_system {
+            BuildSystem::Maven => (
+                format!("maven:3.9-eclipse-temurin-{}", java),
+                "variables:
+  MAVEN_OPTS: \"-Dmaven.repo.local=$CI_PROJECT_DIR/.m2/repository\"
+
+cache:
+  paths:
+    - .m2/repository
+",
+                "mvn -B verify",
+                "**/target/surefire-reports/TEST-*.xml",
+            ),
+            BuildSystem::Gradle(gradle) => (
+                format!("gradle:{}-jdk{}", gradle.gradle_version, java),
+                "variables:
+  GRADLE_USER_HOME: \"$CI_PROJECT_DIR/.gradle\"
+
+cache:
+  paths:
+    - .gradle/caches
+    - .gradle/wrapper
+",
+                "gradle build --no-daemon",
+                "**/build/test-results/test/TEST-*.xml",
+            ),
+        };
+        pipeline.push('\n');
+        pipeline.push_str(cache);
+        pipeline.push_str(&format!(
+            "
+test:
+  stage: test
+  image: {image}
+"
+        ));
+        if uses_testcontainers(target) {
+            //testcontainers talks to a docker in docker service
+            pipeline.push_str(
+                "  services:
+    - name: docker:27-dind
+      alias: docker
+  variables:
+    DOCKER_HOST: tcp://docker:2375
+    DOCKER_TLS_CERTDIR: \"\"
+    TESTCONTAINERS_HOST_OVERRIDE: docker
+",
+            );
+        } else if let (Some(db), Some(service)) = (target.db_info, target.database_service(docker))
+        {
+            pipeline.push_str(&format!(
+                "  services:
+    - name: {image}
+      alias: db
+  variables:
+{env}    SPRING_DATASOURCE_URL: {url}
+",
+                image = service.image,
+                env = service
+                    .environment
+                    .iter()
+                    .map(|(k, v)| format!("    {}: {}\n", k, v))
+                    .collect::<String>(),
+                url = db.kind.jdbc_url("db", service.port, &db.db)
+            ));
+        }
+        pipeline.push_str(&format!(
+            "  script:
+    - {script}
+  artifacts:
+    when: always
+    reports:
+      junit: \"{reports}\"
+"
+        ));
+
+        if docker.is_none() {
+            return pipeline;
+        }
+        let (login, image) = match self.registry {
+            Some(ref registry) => (
+                format!(
+                    "echo \"$REGISTRY_PASSWORD\" | docker login -u \"$REGISTRY_USER\" --password-stdin {}",
+                    registry
+                ),
+                format!("{}/{}", registry, image_repository(target)),
+            ),
+            None => (
+                "echo \"$CI_REGISTRY_PASSWORD\" | docker login -u \"$CI_REGISTRY_USER\" --password-stdin \"$CI_REGISTRY\"".to_owned(),
+                "$CI_REGISTRY_IMAGE".to_owned(),
+            ),
+        };
+        pipeline.push_str(&format!(
+            "
+package-image:
+  stage: package
+  image: docker:27
+  services:
+    - docker:27-dind
+  rules:
+    - if: $CI_COMMIT_BRANCH == \"{branch}\"
+  script:
+",
+            branch = self.default_branch
+        ));
+        if self.push_image {
+            pipeline.push_str(&format!("    - {}\n", login));
+        }
+        pipeline.push_str(&format!(
+            "    - docker build -t \"{image}:$CI_COMMIT_SHORT_SHA\" -t \"{image}:latest\" .\n"
+        ));
+        if self.push_image {
+            pipeline.push_str(&format!("    - docker push --all-tags \"{image}\"\n"));
+        }
+        pipeline
+    }
+}
@@ -0,0 +1,196 @@
+//the spring boot configuration of a generated project, rendered to application.properties
+//or application.yml. keys are kept sorted so the files come out the same every time
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[default]
+    Properties,
+    Yaml,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpringProfile {
+    //the local database, the one compose.yaml starts
+    Dev,
+    //an in memory h2 database
+    Test,
+    //credentials come from the environment
+    Prod,
+}
+
+impl SpringProfile {
+    pub const ALL: [SpringProfile; 3] =
+        [SpringProfile::Dev, SpringProfile::Test, SpringProfile::Prod];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpringProfile::Dev => "dev",
+            SpringProfile::Test => "test",
+            SpringProfile::Prod => "prod",
+        }
+    }
+}
+
+impl ConfigFormat {
+    //application.yml, application-test.yml and so on
+    pub fn file_name(&self, profile: Option<SpringProfile>) -> String {
+        let extension = match self {
+            ConfigFormat::Properties => "properties",
+            ConfigFormat::Yaml => "yml",
+        };
+        match profile {
+            Some(p) => format!("application-{}.{}", p.name(), extension),
+            None => format!("application.{}", extension),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AppConfig {
+    entries: BTreeMap<String, String>,
+}
+
+//yaml reads these as something else than a string when they are not quoted
+fn yaml_value(value: &str) -> String {
+    let special_start = value.starts_with([
+        '*', '&', '!', '@', '`', '%', '[', ']', '{', '}', '"', '\'', '|', '>', '#', '-', '?', ',',
+    ]);
+    if value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || special_start
+        || changes_when_resolved(value)
+    {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+//yaml 1.1 (snakeyaml) resolves these to booleans, null or numbers that do not read back
+//as the same string, e.g. yes becomes true and 1.10 becomes 1.1
+fn changes_when_resolved(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let plain_integer = value == "0"
+        || (digits.starts_with(|c: char| matches!(c, '1'..='9'))
+            && digits.chars().all(|c| c.is_ascii_digit()));
+    if plain_integer {
+        return false;
+    }
+    let lower = value.to_ascii_lowercase();
+    let boolean_or_null = matches!(lower.as_str(), "yes" | "no" | "on" | "off" | "null" | "~")
+        || (matches!(lower.as_str(), "true" | "false") && value != lower);
+    let numeric_start = value.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c));
+    //floats, octal 010, hex 0x1f, 1_000 and sexagesimal 1:30
+    let number = numeric_start
+        && (value.replace('_', "").parse::<f64>().is_ok()
+            || lower.starts_with("0x")
+            || lower.starts_with("0b")
+            || value
+                .split(':')
+                .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit() || c == '_')));
+    boolean_or_null || number || matches!(lower.as_str(), ".inf" | "-.inf" | "+.inf" | ".nan")
+}
+
+impl AppConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //replaces the value a key already has
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.entries.insert(key.to_owned(), value.to_owned());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|v| v.as_str())
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+
+    //the keys of other win
+    pub fn merge(&mut self, other: &AppConfig) {
+        for (key, value) in other.entries.iter() {
+            self.set(key, value);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    //keys without a value are left out, spring would read them as an empty string
+    fn entries(&self) -> Vec<(&str, &str)> {
+        self.iter().filter(|(_, v)| !v.is_empty()).collect()
+    }
+
+    pub fn render(&self, format: ConfigFormat) -> String {
+        match format {
+            ConfigFormat::Properties => self.to_properties(),
+            ConfigFormat::Yaml => self.to_yaml(),
+        }
+    }
+
+    pub fn to_properties(&self) -> String {
+        self.entries()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect()
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let entries: Vec<(Vec<&str>, &str)> = self
+            .entries()
+            .into_iter()
+            .map(|(key, value)| (key.split('.').collect(), value))
+            .collect();
+        let mut result = String::new();
+        yaml_into(&mut result, &entries, 0);
+        result
+    }
+}
+
+fn yaml_into(result: &mut String, entries: &[(Vec<&str>, &str)], depth: usize) {
+    let indent = "  ".repeat(depth);
+    let mut groups: BTreeMap<&str, Vec<(Vec<&str>, &str)>> = BTreeMap::new();
+    for (path, value) in entries.iter() {
+        groups
+            .entry(path[0])
+            .or_default()
+            .push((path[1..].to_vec(), *value));
+    }
+    for (segment, group) in groups.iter() {
+        let leaf = group.iter().find(|(rest, _)| rest.is_empty());
+        let children: Vec<(Vec<&str>, &str)> = group
+            .iter()
+            .filter(|(rest, _)| !rest.is_empty())
+            .cloned()
+            .collect();
+        match leaf {
+            //a key that is also the prefix of other keys, spring reads dotted keys too
+            Some((_, value)) => {
+                result.push_str(&format!("{}{}: {}\n", indent, segment, yaml_value(value)));
+                for (rest, value) in children.iter() {
+                    result.push_str(&format!(
+                        "{}{}.{}: {}\n",
+                        indent,
+                        segment,
+                        rest.join("."),
+                        yaml_value(value)
+                    ));
+                }
+            }
+            None => {
+                result.push_str(&format!("{}{}:\n", indent, segment));
+                yaml_into(result, &children, depth + 1);
+            }
+        }
+    }
+}
@@ -1,3 +1,12 @@
+use super::{
+    ids::{IdStrategy, IdType},
+    maven_builder::capitalize,
+    migrations::{snake_case, table_name},
+    output::{Layer, OutputConfig},
+    queries::QueryIntent,
+    relationships::{imports_for, is_to_one, NestedRoute, Relationship},
+    test_suites::{is_required, sample_value, unknown_value, TestSuite},
+};
 use crate::{
     annotations::Annotation,
     classes::JavaClass,
@@ -6,129 +15,1355 @@ use crate::{
     interfaces::Interface,
     java_structs::VariableParam,
     methods::Method,
+    modifiers::AccessModifiers,
     types::{GenericParams, TypeName},
 };
+//how the list endpoint filters on the searchable fields
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FilterStrategy {
+    //the repository is a JpaSpecificationExecutor, every filter given is applied
+    #[default]
+    Specification,
+    //one findBy method per field in the repository, only the first filter given is applied
+    DerivedQueries,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchConfig {
+    //optional request params of the list endpoint, strings match a part ignoring the case
+    pub searchable: Vec<String>,
+    //the sort of the pageable is restricted to these, any field can be sorted on when empty
+    pub sortable: Vec<String>,
+    pub filter: FilterStrategy,
+}
+
+impl SearchConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn searchable(mut self, fields: Vec<String>) -> Self {
+        self.searchable = fields;
+        self
+    }
+
+    pub fn sortable(mut self, fields: Vec<String>) -> Self {
+        self.sortable = fields;
+        self
+    }
+
+    pub fn filter(mut self, filter: FilterStrategy) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MapperStyle {
+    //a @Component with the conversions written out
+    #[default]
+    HandWritten,
+    //a mapstruct @Mapper, the conversions are generated when the project compiles
+    MapStruct,
+}
+
+//what the request and response of the endpoints carry, the names are fields of the entity
+#[derive(Clone, Debug, Default)]
+pub struct DtoConfig {
+    //every field and the id when empty
+    pub response_fields: Vec<String>,
+    //every field that is not read only when empty
+    pub request_fields: Vec<String>,
+    //left out of the request, the id always is
+    pub read_only: Vec<String>,
+    pub mapper: MapperStyle,
+}
+
+impl DtoConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn response_fields(mut self, fields: Vec<String>) -> Self {
+        self.response_fields = fields;
+        self
+    }
+
+    pub fn request_fields(mut self, fields: Vec<String>) -> Self {
+        self.request_fields = fields;
+        self
+    }
+
+    pub fn read_only(mut self, fields: Vec<String>) -> Self {
+        self.read_only = fields;
+        self
+    }
+
+    pub fn mapper(mut self, mapper: MapperStyle) -> Self {
+        self.mapper = mapper;
+        self
+    }
+}
+
+//what gets generated for one entity besides the defaults
+#[derive(Clone, Debug, Default)]
+pub struct CrudOptions {
+    pub search: SearchConfig,
+    //extra repository methods
+    pub queries: Vec<QueryIntent>,
+    pub dto: DtoConfig,
+    //createdAt and updatedAt, filled in by spring data
+    pub auditing: bool,
+    //createdBy, filled in from the AuditorAware of the main class
+    pub created_by: bool,
+    //deleting sets a deleted flag and every query leaves the flagged rows out
+    pub soft_delete: bool,
+    //a @Version field, a concurrent update fails instead of overwriting the other
+    pub versioned: bool,
+    //left out when the input class has an @Id field of its own
+    pub id: IdStrategy,
+}
+
+impl CrudOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn search(mut self, search: SearchConfig) -> Self {
+        self.search = search;
+        self
+    }
+
+    pub fn query(mut self, intent: QueryIntent) -> Self {
+        self.queries.push(intent);
+        self
+    }
+
+    pub fn dto(mut self, dto: DtoConfig) -> Self {
+        self.dto = dto;
+        self
+    }
+
+    pub fn auditing(mut self) -> Self {
+        self.auditing = true;
+        self
+    }
+
+    pub fn created_by(mut self) -> Self {
+        self.created_by = true;
+        self
+    }
+
+    pub fn soft_delete(mut self) -> Self {
+        self.soft_delete = true;
+        self
+    }
+
+    pub fn versioned(mut self) -> Self {
+        self.versioned = true;
+        self
+    }
+
+    pub fn id(mut self, id: IdStrategy) -> Self {
+        self.id = id;
+        self
+    }
+
+    //the main class needs @EnableJpaAuditing
+    pub fn is_audited(&self) -> bool {
+        self.auditing || self.created_by
+    }
+}
+
+const DELETED_FIELD: &str = "deleted";
+
+//a field of a request or response, a to one relation is carried by the id of the other side
+struct DtoField {
+    entity: Field,
+    reference: bool,
+    //the id of the other side for a reference
+    id_type: TypeName,
+}
+
+impl DtoField {
+    fn name(&self) -> String {
+        match self.reference {
+            true => self.entity.name.clone() + "Id",
+            false => self.entity.name.clone(),
+        }
+    }
+
+    fn type_(&self) -> TypeName {
+        match self.reference {
+            true => self.id_type.clone(),
+            false => self.entity.type_.clone(),
+        }
+    }
+
+    fn getter(&self) -> String {
+        getter(&Field::n(self.name(), self.type_()))
+    }
+
+    fn setter(&self) -> String {
+        "set".to_owned() + &capitalize(&self.name())
+    }
+}
+
 pub struct CrudBuilder {
     for_class: JavaClass,
-    // service_suffix: String,
-    // repository_suffix: String,
-    // controller_suffix: String,
-    // service_package: String,
-    // repository_package: String,
-    // controller_package: String,
+    options: CrudOptions,
+    //the relationships of the codebase this entity is part of
+    relationships: Vec<Relationship>,
+    //the names and packages of the generated classes
+    output: OutputConfig,
+    root_package: String,
+    //thrown by the services when an entity does not exist, the controllers then leave the
+    //404 to the exception handler
+    not_found: Option<Import>,
+    //the id fields of the other entities, a Long id is assumed for the ones missing
+    entity_ids: Vec<(String, Field)>,
 }
 
 impl CrudBuilder {
     pub fn new(jclass: JavaClass) -> Self {
-        Self { for_class: jclass }
+        Self {
+            for_class: jclass,
+            options: CrudOptions::default(),
+            relationships: vec![],
+            output: OutputConfig::default(),
+            root_package: "".to_owned(),
+            not_found: None,
+            entity_ids: vec![],
+        }
+    }
+
+    pub fn output(mut self, output: OutputConfig) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn root_package(mut self, package: String) -> Self {
+        self.root_package = package;
+        self
+    }
+
+    pub fn not_found_exception(mut self, exception: Import) -> Self {
+        self.not_found = Some(exception);
+        self
+    }
+
+    pub fn entity_ids(mut self, ids: Vec<(String, Field)>) -> Self {
+        self.entity_ids = ids;
+        self
+    }
+
+    //the @Id of the input class, made from the id strategy when it has none
+    pub fn id_field(&self) -> Field {
+        self.for_class
+            .fields
+            .iter()
+            .find(|f| f.annotation.iter().any(|a| a.qualified_name == "Id"))
+            .cloned()
+            .unwrap_or_else(|| self.options.id.field())
+    }
+
+    fn has_own_id(&self) -> bool {
+        self.for_class
+            .fields
+            .iter()
+            .any(|f| f.annotation.iter().any(|a| a.qualified_name == "Id"))
+    }
+
+    //boxed, a primitive can not be a generic param
+    fn id_type(&self) -> String {
+        boxed(&self.id_field().type_.name)
+    }
+
+    fn id_of(&self, entity: &str) -> Field {
+        if entity == self.for_class.class_name {
+            return self.id_field();
+        }
+        self.entity_ids
+            .iter()
+            .find(|(name, _)| name == entity)
+            .map(|(_, id)| id.clone())
+            .unwrap_or_else(|| IdStrategy::default().field())
+    }
+
+    //the request carries the id when nothing generates it
+    fn assigned_id(&self) -> bool {
+        match self.has_own_id() {
+            true => !self
+                .id_field()
+                .annotation
+                .iter()
+                .any(|a| a.qualified_name == "GeneratedValue"),
+            false => self.options.id.is_assigned(),
+        }
+    }
+
+    //what a class needs to refer to an id of the type
+    fn id_import(&self, type_: &str) -> Option<Import> {
+        match type_ {
+            "UUID" => Some(Import::new("java.util".into(), "UUID".into())),
+            _ => match &self.options.id.type_ {
+                IdType::Composite(name, _) if name == type_ && !self.has_own_id() => {
+                    Some(self.import_of(Layer::Models, &self.for_class.class_name, name.clone()))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    //the types of the fields that come from the entity, the input class does not import them
+    fn with_type_imports(&self, mut jclass: JavaClass, types: &[String]) -> JavaClass {
+        for type_ in types {
+            let import = match type_.as_str() {
+                "Instant" => Some(Import::new("java.time".into(), type_.clone())),
+                _ => self.id_import(type_),
+            };
+            if let Some(import) = import {
+                jclass = with_import(jclass, import);
+            }
+        }
+        jclass
+    }
+
+    //"/{id}" or a segment per field of a composite key, the path variables and the code
+    //that puts the key together
+    fn id_path(&self) -> (String, Vec<VariableParam>, String) {
+        let id = self.id_field();
+        let key_fields = match self.has_own_id() {
+            true => vec![],
+            false => self.options.id.key_fields(),
+        };
+        if key_fields.is_empty() {
+            return (
+                "/{id}".to_owned(),
+                vec![VariableParam::new(id.type_, "id".into()).annotation("PathVariable".into())],
+                "".to_owned(),
+            );
+        }
+        let mut code = format!("{key} id = new {key}();\n", key = id.type_.name);
+        let mut path = String::new();
+        let mut params = vec![];
+        for field in key_fields {
+            path.push_str(&format!("/{{{}}}", field.name));
+            code.push_str(&format!("id.{}({});\n", setter(&field), field.name));
+            params.push(
+                VariableParam::new(field.type_.clone(), field.name.clone())
+                    .annotation("PathVariable".into()),
+            );
+        }
+        (path, params, code)
+    }
+
+    //where the controller of the entity is mapped, e.g. /customer
+    fn base_path(&self) -> String {
+        "/".to_owned() + &self.for_class.class_name.to_lowercase()
+    }
+
+    fn package(&self, layer: Layer, entity: &str) -> String {
+        if self.root_package.is_empty() {
+            return "".to_owned();
+        }
+        self.output.package_of(&self.root_package, layer, entity)
     }
-    //TODO, find a way to get rid of the imports
 
-    fn jpa_class_name() -> String {
-        todo!();
+    //a class generated for an entity, it can be this one or another one
+    fn import_of(&self, layer: Layer, entity: &str, class_name: String) -> Import {
+        Import::new(self.package(layer, entity), class_name)
     }
-    fn service_class_name() -> String {
-        todo!();
+
+    fn model_import(&self, entity: &str) -> Import {
+        self.import_of(Layer::Models, entity, entity.to_owned())
+    }
+
+    //the entities on the other side of the relationships of this one
+    fn related_entities(&self) -> Vec<String> {
+        let name = &self.for_class.class_name;
+        let mut related: Vec<String> = vec![];
+        for relationship in self.relationships.iter() {
+            for other in [&relationship.entity, &relationship.target] {
+                if other != name && !related.contains(other) {
+                    related.push(other.clone());
+                }
+            }
+        }
+        related
+    }
+
+    pub fn options(mut self, options: CrudOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn relationships(mut self, relationships: Vec<Relationship>) -> Self {
+        let name = self.for_class.class_name.clone();
+        self.relationships = relationships
+            .into_iter()
+            .filter(|r| r.involves(&name))
+            .collect();
+        self
+    }
+
+    //the mapped fields, they replace the fields of the class with the same name
+    fn relation_fields(&self) -> Vec<Field> {
+        self.relationships
+            .iter()
+            .flat_map(|r| r.fields_of(&self.for_class.class_name))
+            .collect()
+    }
+
+    //the fields of the class that are not part of a relationship
+    fn plain_fields(&self) -> Vec<Field> {
+        let relation_fields = self.relation_fields();
+        let id = self.id_field();
+        sorted_fields(&self.for_class)
+            .into_iter()
+            .filter(|f| f.name != id.name && !relation_fields.iter().any(|r| r.name == f.name))
+            .collect()
+    }
+
+    //routes that list the children of this entity
+    fn nested_routes(&self) -> Vec<NestedRoute> {
+        self.relationships
+            .iter()
+            .filter(|r| r.route_parent() == self.for_class.class_name)
+            .filter_map(|r| r.route(&self.id_field()))
+            .collect()
+    }
+
+    //routes where this entity is listed under a parent
+    fn parent_routes(&self) -> Vec<NestedRoute> {
+        self.relationships
+            .iter()
+            .filter_map(|r| r.route(&self.id_of(r.route_parent())))
+            .filter(|route| route.child == self.for_class.class_name)
+            .collect()
+    }
+
+    //the configured names that are fields of the class, in the configured order
+    fn fields_named(&self, names: &[String]) -> Vec<Field> {
+        let fields = sorted_fields(&self.for_class);
+        names
+            .iter()
+            .filter_map(|name| {
+                let field = fields.iter().find(|f| &f.name == name).cloned();
+                if field.is_none() && *name != self.id_field().name {
+                    log::warn!("{} has no field {}", self.for_class.class_name, name);
+                }
+                field
+            })
+            .collect()
+    }
+
+    //what queries can refer to, the fields of the class and the id
+    fn entity_properties(&self) -> Vec<Field> {
+        let mut properties = vec![self.id_field()];
+        properties.extend(self.plain_fields());
+        properties.extend(self.relation_fields());
+        properties.extend(self.bookkeeping_fields());
+        properties.sort_by(|a, b| a.name.cmp(&b.name));
+        properties
+    }
+
+    //the audit, version and soft delete fields the options ask for, a field the input
+    //class already has is left as it is
+    fn bookkeeping_fields(&self) -> Vec<Field> {
+        let options = &self.options;
+        let created_at = Field::n("createdAt".into(), "Instant".into())
+            .annotation("CreatedDate".into())
+            .annotation(Annotation::new("Column".into()).param("updatable".into(), "false".into()));
+        let updated_at =
+            Field::n("updatedAt".into(), "Instant".into()).annotation("LastModifiedDate".into());
+        let created_by = Field::n("createdBy".into(), "String".into())
+            .annotation("CreatedBy".into())
+            .annotation(Annotation::new("Column".into()).param("updatable".into(), "false".into()));
+        //the defaults fill in the rows that were there before the column
+        let version = Field::n("version".into(), "Long".into())
+            .annotation("Version".into())
+            .annotation(
+                Annotation::new("ColumnDefault".into()).param("value".into(), "\"0\"".into()),
+            );
+        let deleted = Field::n(DELETED_FIELD.into(), "boolean".into()).annotation(
+            Annotation::new("ColumnDefault".into()).param("value".into(), "\"false\"".into()),
+        );
+        [
+            (options.auditing, created_at),
+            (options.auditing, updated_at),
+            (options.created_by, created_by),
+            (options.versioned, version),
+            (options.soft_delete, deleted),
+        ]
+        .into_iter()
+        .filter(|(enabled, field)| {
+            *enabled && !self.for_class.fields.iter().any(|f| f.name == field.name)
+        })
+        .map(|(_, field)| field)
+        .collect()
+    }
+
+    fn searchable_fields(&self) -> Vec<Field> {
+        self.fields_named(&self.options.search.searchable)
     }
+    //TODO, find a way to get rid of the imports
 
-    pub fn jpa_repository_of(&self, cls_import: Import) -> Interface {
+    pub fn jpa_repository_of(&self) -> Interface {
         let jclass = self.for_class.to_owned();
-        let mut repo = Interface::new("".to_string(), jclass.class_name.clone() + "Repository");
-        //theses calls could be completely written in the OutputDirs class
-        let find_by_id_method = Method::new(
-            TypeName::new_with_generics(
-                "Optional".into(),
-                GenericParams::new(vec![jclass.class_name.clone()]),
-            ),
-            "findById".into(),
-        )
-        .param(VariableParam::new("Long".into(), "id".into()));
+        let mut repo = Interface::new(
+            self.package(Layer::Repositories, &jclass.class_name),
+            self.output.repository_name(&jclass.class_name),
+        );
         repo = repo
             .public()
-            .import(cls_import)
-            .import(Import::new("java.util".into(), "Optional".into()))
+            .import(self.model_import(&jclass.class_name))
             .import(Import::new(
                 "org.springframework.data.jpa.repository".into(),
                 "JpaRepository".into(),
             ))
             .extends(TypeName::new_with_generics(
                 "JpaRepository".to_owned(),
-                GenericParams::new(vec![jclass.class_name.clone().into(), "Long".into()]),
-            ))
-            .method(find_by_id_method);
+                GenericParams::new(vec![jclass.class_name.clone(), self.id_type()]),
+            ));
+        let mut id_types = vec![self.id_type()];
+        id_types.extend(self.parent_routes().into_iter().map(|r| r.parent_id));
+        for import in id_types.iter().filter_map(|t| self.id_import(t)) {
+            if !repo.imports.contains(&import) {
+                repo = repo.import(import);
+            }
+        }
+        let properties = self.entity_properties();
+        for intent in self.options.queries.iter() {
+            if let Some(method) = intent.method(&jclass.class_name, &properties) {
+                for import in intent.imports() {
+                    if !repo.imports.contains(&import) {
+                        repo = repo.import(import);
+                    }
+                }
+                repo = repo.method(method);
+            }
+        }
+        for route in self.parent_routes() {
+            if let Some(method) = route.repository_method() {
+                for import in route.query.imports() {
+                    if !repo.imports.contains(&import) {
+                        repo = repo.import(import);
+                    }
+                }
+                repo = repo.method(method);
+            }
+        }
+        match self.options.search.filter {
+            FilterStrategy::Specification if !self.searchable_fields().is_empty() => {
+                repo = repo
+                    .import(Import::new(
+                        "org.springframework.data.jpa.repository".into(),
+                        "JpaSpecificationExecutor".into(),
+                    ))
+                    .extends(TypeName::new_with_generics(
+                        "JpaSpecificationExecutor".to_owned(),
+                        GenericParams::new(vec![jclass.class_name.clone()]),
+                    ));
+            }
+            FilterStrategy::Specification => {}
+            FilterStrategy::DerivedQueries => {
+                for field in self.searchable_fields() {
+                    let find_by = Method::new(
+                        TypeName::new_with_generics(
+                            "Page".into(),
+                            GenericParams::new(vec![jclass.class_name.clone()]),
+                        ),
+                        derived_find_by(&field),
+                    )
+                    .param(VariableParam::new(
+                        boxed(&field.type_.name).into(),
+                        field.name.clone(),
+                    ))
+                    .param(VariableParam::new("Pageable".into(), "pageable".into()));
+                    repo = repo.method(find_by);
+                }
+                if !self.searchable_fields().is_empty() {
+                    for import in QueryIntent::find_by(vec![]).paged().imports() {
+                        if !repo.imports.contains(&import) {
+                            repo = repo.import(import);
+                        }
+                    }
+                }
+            }
+        }
         repo
     }
 
+    //the pageable with the sort restricted to the sortable fields
+    fn sorted_pageable_code(&self) -> Vec<String> {
+        let sortable = &self.options.search.sortable;
+        if sortable.is_empty() {
+            return vec![];
+        }
+        let mut names: Vec<String> = self
+            .fields_named(sortable)
+            .iter()
+            .map(|f| format!("\"{}\"", f.name))
+            .collect();
+        let id = format!("\"{}\"", self.id_field().name);
+        if sortable.contains(&self.id_field().name) && !names.contains(&id) {
+            names.insert(0, id);
+        }
+        vec![
+            format!("Set<String> sortable = Set.of({});", names.join(", ")),
+            "Sort sort = Sort.by(pageable.getSort().filter(order -> sortable.contains(order.getProperty())).toList());".to_owned(),
+            "pageable = PageRequest.of(pageable.getPageNumber(), pageable.getPageSize(), sort);".to_owned(),
+        ]
+    }
+
+    fn find_all_code(&self) -> String {
+        let entity = &self.for_class.class_name;
+        let mut code = self.sorted_pageable_code();
+        let searchable = self.searchable_fields();
+        match self.options.search.filter {
+            _ if searchable.is_empty() => {}
+            FilterStrategy::Specification => {
+                code.push(format!(
+                    "Specification<{}> spec = (root, query, cb) -> cb.conjunction();",
+                    entity
+                ));
+                for field in searchable.iter() {
+                    let predicate = match field.type_.name.as_str() {
+                        "String" => format!(
+                            "cb.like(cb.lower(root.get(\"{name}\")), \"%\" + {name}.toLowerCase() + \"%\")",
+                            name = field.name
+                        ),
+                        _ => format!("cb.equal(root.get(\"{name}\"), {name})", name = field.name),
+                    };
+                    code.push(format!(
+                        "if ({} != null) {{\n    spec = spec.and((root, query, cb) -> {});\n}}",
+                        field.name, predicate
+                    ));
+                }
+                code.push("return repository.findAll(spec, pageable);".to_owned());
+                return code.join("\n");
+            }
+            FilterStrategy::DerivedQueries => {
+                for field in searchable.iter() {
+                    code.push(format!(
+                        "if ({name} != null) {{\n    return repository.{method}({name}, pageable);\n}}",
+                        name = field.name,
+                        method = derived_find_by(field)
+                    ));
+                }
+            }
+        }
+        code.push("return repository.findAll(pageable);".to_owned());
+        code.join("\n")
+    }
+
+    //optional filters of the list endpoint, boxed so they can be left out
+    fn filter_params(&self) -> Vec<VariableParam> {
+        self.searchable_fields()
+            .iter()
+            .map(|f| VariableParam::new(boxed(&f.type_.name).into(), f.name.clone()))
+            .collect()
+    }
+
     pub fn spring_boot_entity(&self) -> JavaClass {
         let jclass = self.for_class.to_owned();
-        let id_field = id_field_for_entity();
         let lombok_annots: Vec<Annotation> = vec![
             "Data".into(),
             "AllArgsConstructor".into(),
             "NoArgsConstructor".into(),
         ];
         let entity_annotation = "Entity".into();
-        let entity = jclass
+        let mut entity = jclass
             .import(Import::new("jakarta.persistence".into(), "Entity".into()))
-            .import(Import::new(
-                "jakarta.persistence".into(),
-                "GeneratedValue".into(),
-            ))
-            .import(Import::new(
-                "jakarta.persistence".into(),
-                "GenerationType".into(),
-            ))
-            .import(Import::new("jakarta.persistence".into(), "Id".into()))
             .import(Import::new("lombok".into(), "AllArgsConstructor".into()))
             .import(Import::new("lombok".into(), "Data".into()))
             .import(Import::new("lombok".into(), "NoArgsConstructor".into()))
             .annotations(lombok_annots)
-            .annotation(entity_annotation)
-            .field(id_field);
+            .annotation(entity_annotation);
+        if !self.has_own_id() {
+            for import in self.options.id.imports() {
+                entity = with_import(entity, import);
+            }
+            entity = entity.field(self.options.id.field());
+        } else {
+            //the input class annotates its id without importing the annotations
+            let id = self.id_field();
+            let mut names: Vec<&str> = id
+                .annotation
+                .iter()
+                .map(|a| a.qualified_name.as_str())
+                .filter(|n| ["Id", "GeneratedValue", "SequenceGenerator"].contains(n))
+                .collect();
+            if names.contains(&"GeneratedValue") {
+                names.push("GenerationType");
+            }
+            for name in names {
+                entity = with_import(
+                    entity,
+                    Import::new("jakarta.persistence".into(), name.into()),
+                );
+            }
+        }
+        let entity = self.with_bookkeeping_fields(entity);
+        self.with_relation_fields(entity)
+    }
+
+    //the @Embeddable of a composite id, in the package of the entity
+    pub fn id_class(&self) -> Option<JavaClass> {
+        if self.has_own_id() {
+            return None;
+        }
+        self.options
+            .id
+            .key_class(self.package(Layer::Models, &self.for_class.class_name))
+    }
+
+    fn with_bookkeeping_fields(&self, mut entity: JavaClass) -> JavaClass {
+        let fields = self.bookkeeping_fields();
+        let imports = [
+            ("CreatedDate", "org.springframework.data.annotation"),
+            ("LastModifiedDate", "org.springframework.data.annotation"),
+            ("CreatedBy", "org.springframework.data.annotation"),
+            ("Column", "jakarta.persistence"),
+            ("Version", "jakarta.persistence"),
+            ("ColumnDefault", "org.hibernate.annotations"),
+        ];
+        for field in fields.iter() {
+            if field.type_.name == "Instant" {
+                entity = with_import(entity, Import::new("java.time".into(), "Instant".into()));
+            }
+            for annotation in field.annotation.iter() {
+                if let Some((name, package)) = imports
+                    .iter()
+                    .find(|(name, _)| *name == annotation.qualified_name)
+                {
+                    entity = with_import(entity, Import::new((*package).into(), (*name).into()));
+                }
+            }
+        }
+        if self.options.is_audited() {
+            entity = entity
+                .import(Import::new(
+                    "jakarta.persistence".into(),
+                    "EntityListeners".into(),
+                ))
+                .import(Import::new(
+                    "org.springframework.data.jpa.domain.support".into(),
+                    "AuditingEntityListener".into(),
+                ))
+                .annotation(
+                    Annotation::new("EntityListeners".into())
+                        .param("value".into(), "AuditingEntityListener.class".into()),
+                );
+        }
+        if fields.iter().any(|f| f.name == DELETED_FIELD) {
+            //hibernate binds the id, a composite one field by field in the order of their
+            //names, and then the version when the entity has one
+            let key_fields = match self.has_own_id() {
+                true => vec![],
+                false => self.options.id.key_fields(),
+            };
+            let mut columns: Vec<String> = match key_fields.is_empty() {
+                true => vec![snake_case(&self.id_field().name)],
+                false => key_fields.iter().map(|f| snake_case(&f.name)).collect(),
+            };
+            columns.extend(
+                self.entity_properties()
+                    .iter()
+                    .filter(|f| f.annotation.iter().any(|a| a.qualified_name == "Version"))
+                    .map(|f| snake_case(&f.name)),
+            );
+            let condition = columns
+                .iter()
+                .map(|c| format!("{} = ?", c))
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            entity = entity
+                .import(Import::new(
+                    "org.hibernate.annotations".into(),
+                    "SQLDelete".into(),
+                ))
+                .import(Import::new(
+                    "org.hibernate.annotations".into(),
+                    "SQLRestriction".into(),
+                ))
+                .annotation(Annotation::new("SQLDelete".into()).param(
+                    "sql".into(),
+                    format!(
+                        "\"UPDATE {} SET {} = true WHERE {}\"",
+                        table_name(&self.for_class),
+                        DELETED_FIELD,
+                        condition
+                    ),
+                ))
+                .annotation(
+                    Annotation::new("SQLRestriction".into())
+                        .param("value".into(), format!("\"{} = false\"", DELETED_FIELD)),
+                );
+        }
+        fields
+            .into_iter()
+            .fold(entity, |entity, field| entity.field(field))
+    }
+
+    fn with_relation_fields(&self, mut entity: JavaClass) -> JavaClass {
+        if !self.root_package.is_empty() {
+            entity.package_in_place(self.package(Layer::Models, &entity.class_name.clone()));
+        }
+        for other in self.related_entities() {
+            entity = with_import(entity, self.model_import(&other));
+        }
+        for field in self.relation_fields() {
+            for import in imports_for(&field) {
+                if !entity.imports.contains(&import) {
+                    entity = entity.import(import);
+                }
+            }
+            entity.fields.retain(|f| f.name != field.name);
+            entity = entity.field(field);
+        }
         entity
     }
 
-    pub fn dto_from_class(&self, class_import: Import) -> JavaClass {
-        let jclass = self.for_class.to_owned();
-        let name = jclass.class_name.clone() + "DTO";
-        let initial_class_name = jclass.class_name.clone();
-        let mut dto = jclass.class_name(name.clone());
-        dto = dto.import(class_import);
-        //DTO Constructor
-        let dto_constructor =
-            Method::new(TypeName::new("".into()), name)
-                .public()
-                .param(VariableParam::new(
-                    TypeName::new(initial_class_name.clone()),
-                    initial_class_name.clone().to_lowercase(),
-                ));
-        dto = dto.method(dto_constructor);
+    //the fields a dto can carry, sorted by name
+    fn dto_candidates(&self) -> Vec<DtoField> {
+        let mut candidates: Vec<DtoField> = self
+            .plain_fields()
+            .into_iter()
+            .map(|entity| DtoField {
+                id_type: entity.type_.clone(),
+                entity,
+                reference: false,
+            })
+            .collect();
+        //the other side of a many to one is referenced by its id, collections are left
+        //out so the json does not go around in circles
+        candidates.extend(
+            self.relation_fields()
+                .into_iter()
+                .filter(is_to_one)
+                .map(|entity| DtoField {
+                    id_type: boxed(&self.id_of(&entity.type_.name).type_.name).into(),
+                    entity,
+                    reference: true,
+                }),
+        );
+        //the deleted flag is never seen, the rows that have it set are not found
+        candidates.extend(
+            self.bookkeeping_fields()
+                .into_iter()
+                .filter(|f| f.name != DELETED_FIELD)
+                .map(|entity| DtoField {
+                    id_type: entity.type_.clone(),
+                    entity,
+                    reference: false,
+                }),
+        );
+        candidates.sort_by(|a, b| a.entity.name.cmp(&b.entity.name));
+        candidates
+    }
+
+    //all the candidates when nothing was selected
+    fn dto_fields_named(&self, names: &[String]) -> Vec<DtoField> {
+        let candidates = self.dto_candidates();
+        if names.is_empty() {
+            return candidates;
+        }
+        for name in names.iter() {
+            if *name != self.id_field().name && !candidates.iter().any(|c| &c.entity.name == name) {
+                log::warn!("{} has no field {} to map", self.for_class.class_name, name);
+            }
+        }
+        candidates
+            .into_iter()
+            .filter(|c| names.contains(&c.entity.name))
+            .collect()
+    }
+
+    //never written from a request
+    fn read_only_fields(&self) -> Vec<String> {
+        let mut read_only = vec![self.id_field().name];
+        read_only.extend(self.bookkeeping_fields().into_iter().map(|f| f.name));
+        read_only.extend(self.options.dto.read_only.iter().cloned());
+        read_only
+    }
+
+    fn response_has_id(&self) -> bool {
+        let selected = &self.options.dto.response_fields;
+        selected.is_empty() || selected.contains(&self.id_field().name)
+    }
 
-        dto
+    fn response_fields(&self) -> Vec<DtoField> {
+        self.dto_fields_named(&self.options.dto.response_fields)
     }
 
-    pub fn service_from_class(&self, jpa_import: Import) -> JavaClass {
+    fn request_fields(&self) -> Vec<DtoField> {
+        let read_only = self.read_only_fields();
+        self.dto_fields_named(&self.options.dto.request_fields)
+            .into_iter()
+            .filter(|f| !read_only.contains(&f.entity.name))
+            .collect()
+    }
+
+    //the imports of the input class that the dto fields may still need
+    fn dto_imports(&self, keep_validation: bool) -> Vec<Import> {
+        self.for_class
+            .imports
+            .iter()
+            .filter(|i| {
+                !i.package_name.starts_with("jakarta.persistence")
+                    && !i.package_name.starts_with("org.hibernate")
+                    && i.package_name != "lombok"
+                    && (keep_validation || !is_validation_import(i))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn lombok_dto(&self, name: String, imports: Vec<Import>) -> JavaClass {
+        JavaClass::new(name, self.package(Layer::Dtos, &self.for_class.class_name))
+            .public()
+            .imports(imports)
+            .import(Import::new("lombok".into(), "Data".into()))
+            .import(Import::new("lombok".into(), "NoArgsConstructor".into()))
+            .annotation("Data".into())
+            .annotation("NoArgsConstructor".into())
+    }
+
+    //what the endpoints return
+    pub fn response_dto(&self) -> JavaClass {
+        let name = self.output.response_name(&self.for_class.class_name);
+        let mut dto = self.lombok_dto(name, self.dto_imports(false));
+        if self.response_has_id() {
+            let id = self.id_field();
+            dto = dto.field(Field::n(id.name, id.type_));
+        }
+        for field in self.response_fields() {
+            dto = dto.field(Field::n(field.name(), field.type_()));
+        }
+        let types: Vec<String> = dto.fields.iter().map(|f| f.type_.name.clone()).collect();
+        self.with_type_imports(dto, &types)
+    }
+
+    //the body of a create or update, the constraints of the input fields are kept
+    pub fn request_dto(&self) -> JavaClass {
+        let name = self.output.request_name(&self.for_class.class_name);
+        let constraints: Vec<String> = self
+            .for_class
+            .imports
+            .iter()
+            .filter(|i| is_validation_import(i))
+            .map(|i| i.class_name.clone())
+            .collect();
+        let any_constraint = constraints.iter().any(|c| c == "*");
+        let mut dto = self.lombok_dto(name, self.dto_imports(true));
+        //only read when the entity is created, an update keeps the id of the path
+        if self.assigned_id() {
+            let id = self.id_field();
+            dto = dto.field(Field::n(id.name, id.type_));
+        }
+        for field in self.request_fields() {
+            let mut dto_field = Field::n(field.name(), field.type_());
+            dto_field.annotation = field
+                .entity
+                .annotation
+                .iter()
+                .filter(|a| any_constraint || constraints.contains(&a.qualified_name))
+                .cloned()
+                .collect();
+            dto = dto.field(dto_field);
+        }
+        let types: Vec<String> = dto.fields.iter().map(|f| f.type_.name.clone()).collect();
+        self.with_type_imports(dto, &types)
+    }
+
+    //converts between the entity and its request and response
+    pub fn mapper(&self) -> JavaClass {
+        let entity = self.for_class.class_name.clone();
+        let mut mapper = JavaClass::new(
+            self.output.mapper_name(&entity),
+            self.package(Layer::Dtos, &entity),
+        )
+        .public();
+        mapper = with_import(mapper, self.model_import(&entity));
+        match self.options.dto.mapper {
+            MapperStyle::HandWritten => self.hand_written_mapper(mapper),
+            MapperStyle::MapStruct => self.mapstruct_mapper(mapper),
+        }
+    }
+
+    //the entities the request refers to by their id, the service looks them up
+    fn request_references(&self) -> Vec<String> {
+        let mut references: Vec<String> = vec![];
+        for field in self.request_fields().iter().filter(|f| f.reference) {
+            if !references.contains(&field.entity.type_.name) {
+                references.push(field.entity.type_.name.clone());
+            }
+        }
+        references
+    }
+
+    //sets the references of `entity` to what the ids of `request` point to
+    fn reference_assignments(&self) -> Vec<String> {
+        self.request_fields()
+            .iter()
+            .filter(|f| f.reference)
+            .map(|f| {
+                format!(
+                    "entity.{}(service.{}Reference(request.{}()));",
+                    setter(&f.entity),
+                    lower_first(&f.entity.type_.name),
+                    f.getter()
+                )
+            })
+            .collect()
+    }
+
+    fn mapper_signatures(&self) -> (Method, Method, Method) {
+        let entity = self.for_class.class_name.clone();
+        let request =
+            VariableParam::new(self.output.request_name(&entity).into(), "request".into());
+        let to_response = Method::new(
+            self.output.response_name(&entity).into(),
+            "toResponse".into(),
+        )
+        .public()
+        .param(VariableParam::new(entity.clone().into(), "entity".into()));
+        let to_entity = Method::new(entity.clone().into(), "toEntity".into())
+            .public()
+            .param(request.clone());
+        let update_entity = Method::new("void".into(), "updateEntity".into())
+            .public()
+            .param(request);
+        (to_response, to_entity, update_entity)
+    }
+
+    fn hand_written_mapper(&self, mapper: JavaClass) -> JavaClass {
+        let entity = self.for_class.class_name.clone();
+        let (to_response, to_entity, update_entity) = self.mapper_signatures();
+
+        let mut response = vec![format!(
+            "{response} response = new {response}();",
+            response = self.output.response_name(&entity)
+        )];
+        if self.response_has_id() {
+            let id = self.id_field();
+            response.push(format!(
+                "response.{}(entity.{}());",
+                setter(&id),
+                getter(&id)
+            ));
+        }
+        for field in self.response_fields() {
+            let value = match field.reference {
+                true => format!(
+                    "entity.{getter}() == null ? null : entity.{getter}().{id}()",
+                    getter = getter(&field.entity),
+                    id = getter(&self.id_of(&field.entity.type_.name))
+                ),
+                false => format!("entity.{}()", getter(&field.entity)),
+            };
+            response.push(format!("response.{}({});", field.setter(), value));
+        }
+        response.push("return response;".to_owned());
+
+        //the references are looked up by the service
+        let update: Vec<String> = self
+            .request_fields()
+            .iter()
+            .filter(|f| !f.reference)
+            .map(|f| format!("entity.{}(request.{}());", setter(&f.entity), f.getter()))
+            .collect();
+
+        mapper
+            .import(Import::new(
+                "org.springframework.stereotype".into(),
+                "Component".into(),
+            ))
+            .annotation("Component".into())
+            .method(to_response.code(response.join("\n")))
+            .method(to_entity.code(format!(
+                "{entity} entity = new {entity}();\n{id}updateEntity(request, entity);\nreturn entity;",
+                entity = entity,
+                id = match self.assigned_id() {
+                    true => {
+                        let id = self.id_field();
+                        format!("entity.{}(request.{}());\n", setter(&id), getter(&id))
+                    }
+                    false => "".to_owned(),
+                }
+            )))
+            .method(
+                update_entity
+                    .param(VariableParam::new(entity.into(), "entity".into()))
+                    .code(update.join("\n")),
+            )
+    }
+
+    fn mapstruct_mapper(&self, mapper: JavaClass) -> JavaClass {
+        let entity = self.for_class.class_name.clone();
+        let (mut to_response, mut to_entity, mut update_entity) = self.mapper_signatures();
+        let mapping = |target: &str, source: &str| {
+            Annotation::new("Mapping".into())
+                .param("target".into(), format!("\"{}\"", target))
+                .param("source".into(), format!("\"{}\"", source))
+        };
+        for field in self.response_fields().iter().filter(|f| f.reference) {
+            to_response = to_response.annotation(mapping(
+                &field.name(),
+                &format!(
+                    "{}.{}",
+                    field.entity.name,
+                    self.id_of(&field.entity.type_.name).name
+                ),
+            ));
+        }
+
+        //everything of the entity the request does not write stays as it is, the
+        //references are looked up by the service
+        let request_fields = self.request_fields();
+        let mut request_mappings = vec![];
+        for property in self.entity_properties() {
+            match request_fields
+                .iter()
+                .find(|f| f.entity.name == property.name)
+            {
+                Some(field) if !field.reference => {}
+                _ => request_mappings.push(
+                    Annotation::new("Mapping".into())
+                        .param("target".into(), format!("\"{}\"", property.name))
+                        .param("ignore".into(), "true".into()),
+                ),
+            }
+        }
+        let id = self.id_field().name;
+        for annotation in request_mappings {
+            //an assigned id is taken from the request when the entity is created
+            let is_id = annotation
+                .params_list
+                .iter()
+                .flatten()
+                .any(|(name, value)| name == "target" && *value == format!("\"{}\"", id));
+            if !(is_id && self.assigned_id()) {
+                to_entity = to_entity.annotation(annotation.clone());
+            }
+            update_entity = update_entity.annotation(annotation);
+        }
+        update_entity = update_entity.param(
+            VariableParam::new(entity.into(), "entity".into()).annotation("MappingTarget".into()),
+        );
+
+        mapper
+            .abstract_()
+            .import(Import::new("org.mapstruct".into(), "Mapper".into()))
+            .import(Import::new("org.mapstruct".into(), "Mapping".into()))
+            .import(Import::new("org.mapstruct".into(), "MappingTarget".into()))
+            .annotation(
+                Annotation::new("Mapper".into())
+                    .param("componentModel".into(), "\"spring\"".into()),
+            )
+            .method(to_response.abstract_())
+            .method(to_entity.abstract_())
+            .method(update_entity.abstract_())
+    }
+
+    pub fn service_from_class(&self) -> JavaClass {
         let jclass = self.for_class.to_owned();
-        let mut service = jclass.clone();
-        //need to find a way to not have to do the "magic" strings
-        service.class_name = jclass.class_name.clone() + "Service";
+        let entity = jclass.class_name.clone();
+        let mut service = JavaClass::new(
+            self.output.service_name(&entity),
+            self.package(Layer::Services, &entity),
+        );
         service = service.annotation("Service".into());
-        let repo_name = (&jclass).class_name.to_owned() + "Repository";
-        service = service.field(
-            Field::n("repository".into(), TypeName::new(repo_name.clone()))
-                .annotation(Annotation::autowired()),
+        let repo_name = self.output.repository_name(&entity);
+        let mut repository = Field::n("repository".into(), TypeName::new(repo_name.clone()));
+        repository.modifiers.push(AccessModifiers::Final);
+        service = service.field(repository);
+        let mut constructor = Method::new(TypeName::new("".into()), service.class_name.clone())
+            .public()
+            .annotation(Annotation::autowired())
+            .param(VariableParam::new(
+                repo_name.clone().into(),
+                "repository".into(),
+            ));
+        let mut assignments = vec!["this.repository = repository;".to_owned()];
+        //the references of a request are looked up in the repository of their entity, an
+        //unknown id is answered with a 404
+        let mut id_types = vec![self.id_type()];
+        let mut lookups = vec![];
+        for reference in self.request_references() {
+            let reference_repository = match reference == entity {
+                true => "repository".to_owned(),
+                false => {
+                    let type_ = self.output.repository_name(&reference);
+                    let name = lower_first(&type_);
+                    let mut field = Field::n(name.clone(), TypeName::new(type_.clone()));
+                    field.modifiers.push(AccessModifiers::Final);
+                    service = service.field(field);
+                    service = with_import(
+                        service,
+                        self.import_of(Layer::Repositories, &reference, type_.clone()),
+                    );
+                    constructor = constructor.param(VariableParam::new(type_.into(), name.clone()));
+                    assignments.push(format!("this.{} = {};", name, name));
+                    name
+                }
+            };
+            let missing = match &self.not_found {
+                None => format!(
+                    "new ResponseStatusException(HttpStatus.NOT_FOUND, \"{} \" + id + \" was not found\")",
+                    reference
+                ),
+                Some(exception) => format!("new {}(\"{}\", id)", exception.class_name, reference),
+            };
+            let id_type = boxed(&self.id_of(&reference).type_.name);
+            lookups.push(
+                Method::new(reference.clone().into(), lower_first(&reference) + "Reference")
+                    .public()
+                    .param(VariableParam::new(id_type.clone().into(), "id".into()))
+                    .code(format!(
+                        "if (id == null) {{\n    return null;\n}}\nreturn {}.findById(id)\n    .orElseThrow(() -> {});",
+                        reference_repository, missing
+                    )),
+            );
+            service = with_import(service, self.model_import(&reference));
+            id_types.push(id_type);
+            if self.not_found.is_none() {
+                service = service
+                    .import(Import::new(
+                        "org.springframework.web.server".into(),
+                        "ResponseStatusException".into(),
+                    ))
+                    .import(Import::new(
+                        "org.springframework.http".into(),
+                        "HttpStatus".into(),
+                    ));
+            }
+        }
+        service = service.method(constructor.code(assignments.join("\n")));
+        for lookup in lookups {
+            service = service.method(lookup);
+        }
+
+        let entity_param = VariableParam::new(TypeName::new(entity.clone()), "entity".into());
+        let id_param = VariableParam::new(self.id_type().into(), "id".into());
+        let mut find_all = Method::new(
+            TypeName::new_with_generics("Page".into(), GenericParams::new(vec![entity.clone()])),
+            "findAll".into(),
+        )
+        .public();
+        for param in self.filter_params() {
+            find_all = find_all.param(param);
+        }
+        find_all = find_all
+            .param(VariableParam::new("Pageable".into(), "pageable".into()))
+            .code(self.find_all_code());
+        let optional_entity = TypeName::new_with_generics(
+            "Optional".into(),
+            GenericParams::new(vec![entity.clone()]),
         );
-        let sclass_name = service.class_name.clone();
-        service = service.method(
-            Method::new(TypeName::new("".into()), sclass_name)
+        //empty or false when the entity does not exist, unless there is an exception to throw
+        let (find_by_id, update, delete) = match &self.not_found {
+            None => (
+                Method::new(optional_entity.clone(), "findById".into())
+                    .code("return repository.findById(id);".into()),
+                Method::new(optional_entity, "update".into()).code(
+                    "return repository.findById(id).map(entity -> {\n    changes.accept(entity);\n    return repository.save(entity);\n});"
+                        .into(),
+                ),
+                Method::new("boolean".into(), "delete".into()).code(
+                    "if (!repository.existsById(id)) {\n    return false;\n}\nrepository.deleteById(id);\nreturn true;"
+                        .into(),
+                ),
+            ),
+            Some(exception) => {
+                let throw = format!("new {}(\"{}\", id)", exception.class_name, entity);
+                (
+                    Method::new(TypeName::new(entity.clone()), "findById".into()).code(format!(
+                        "return repository.findById(id)\n    .orElseThrow(() -> {});",
+                        throw
+                    )),
+                    Method::new(TypeName::new(entity.clone()), "update".into()).code(
+                        format!(
+                            "{} entity = findById(id);\nchanges.accept(entity);\nreturn repository.save(entity);",
+                            entity
+                        ),
+                    ),
+                    Method::new("void".into(), "delete".into()).code(format!(
+                        "if (!repository.existsById(id)) {{\n    throw {};\n}}\nrepository.deleteById(id);",
+                        throw
+                    )),
+                )
+            }
+        };
+        service = service
+            .method(
+                Method::new(TypeName::new(entity.clone()), "create".into())
+                    .public()
+                    .param(entity_param.clone())
+                    .code("return repository.save(entity);".into()),
+            )
+            .method(find_by_id.public().param(id_param.clone()))
+            .method(find_all)
+            //the changes are made on the stored entity so what they do not touch is kept
+            .method(
+                update
+                    .public()
+                    .param(id_param.clone())
+                    .param(VariableParam::new(
+                        TypeName::new_with_generics(
+                            "Consumer".into(),
+                            GenericParams::new(vec![entity.clone()]),
+                        ),
+                        "changes".into(),
+                    )),
+            )
+            .method(delete.public().param(id_param));
+
+        //the children of a parent, for its nested route
+        for route in self.parent_routes() {
+            let finder = route.finder();
+            service = service.method(
+                Method::new(
+                    TypeName::new_with_generics(
+                        "Page".into(),
+                        GenericParams::new(vec![entity.clone()]),
+                    ),
+                    finder.clone(),
+                )
                 .public()
-                .annotation(Annotation::autowired())
-                .param(VariableParam::new(repo_name.into(), "repository".into())),
-        );
+                .param(VariableParam::new(
+                    route.parent_id.as_str().into(),
+                    "id".into(),
+                ))
+                .param(VariableParam::new("Pageable".into(), "pageable".into()))
+                .code(format!("return repository.{}(id, pageable);", finder)),
+            );
+        }
 
+        service = with_import(
+            service,
+            self.import_of(Layer::Repositories, &entity, repo_name),
+        );
+        service = with_import(service, self.model_import(&entity));
+        id_types.extend(self.parent_routes().into_iter().map(|r| r.parent_id));
+        service = self.with_type_imports(service, &id_types);
+        service = match &self.not_found {
+            None => service.import(Import::new("java.util".into(), "Optional".into())),
+            Some(exception) => with_import(service, exception.clone()),
+        };
         service = service
             .public()
-            .import(jpa_import)
+            .import(Import::new("java.util.function".into(), "Consumer".into()))
+            .import(Import::new(
+                "org.springframework.data.domain".into(),
+                "Page".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.data.domain".into(),
+                "Pageable".into(),
+            ))
             .import(Import::new(
                 "org.springframework.beans.factory.annotation".into(),
                 "Autowired".into(),
@@ -137,94 +1372,147 @@ impl CrudBuilder {
                 "org.springframework.stereotype".into(),
                 "Service".into(),
             ));
+        if !self.options.search.sortable.is_empty() {
+            service = service
+                .import(Import::new("java.util".into(), "Set".into()))
+                .import(Import::new(
+                    "org.springframework.data.domain".into(),
+                    "PageRequest".into(),
+                ))
+                .import(Import::new(
+                    "org.springframework.data.domain".into(),
+                    "Sort".into(),
+                ));
+        }
+        if self.options.search.filter == FilterStrategy::Specification
+            && !self.searchable_fields().is_empty()
+        {
+            service = service.import(Import::new(
+                "org.springframework.data.jpa.domain".into(),
+                "Specification".into(),
+            ));
+        }
         service
     }
 
-    pub fn controller_from_class(&self, service_import: Import, dto_import: Import) -> JavaClass {
+    pub fn controller_from_class(&self) -> JavaClass {
         let jclass = self.for_class.to_owned();
-        let id_path_variable =
-            VariableParam::new("Long".into(), "id".into()).annotation("PathVariable".into());
+        let (id_path, id_params, id_code) = self.id_path();
+        let with_id = |method: Method| {
+            id_params
+                .iter()
+                .fold(method, |method, param| method.param(param.clone()))
+        };
         let initial_class_name = jclass.class_name.clone();
-        let mut controller = JavaClass::new(initial_class_name.clone() + "Controller", "".into());
+        let request_name = self.output.request_name(&initial_class_name);
+        let response_name = self.output.response_name(&initial_class_name);
+        //spring answers an invalid body with a 400, the exception handler only shapes it
+        let request_body =
+            VariableParam::new(TypeName::new(request_name.clone()), "request".into())
+                .annotation("Valid".into())
+                .annotation("RequestBody".into());
+        let mut controller = JavaClass::new(
+            self.output.controller_name(&initial_class_name),
+            self.package(Layer::Controllers, &initial_class_name),
+        );
         let post_mapping = "PostMapping".into();
         let get_mapping = "GetMapping".into();
         let get_mapping_id = Annotation::new("GetMapping".into())
-            .param("value".into(), "\"".to_owned() + "/{id}" + "\"");
+            .param("value".into(), "\"".to_owned() + &id_path + "\"");
         let delete_mapping_id = Annotation::new("DeleteMapping".into())
-            .param("value".into(), "\"".to_owned() + "/{id}" + "\"");
+            .param("value".into(), "\"".to_owned() + &id_path + "\"");
         let update_mapping_id = Annotation::new("PutMapping".into())
-            .param("value".into(), "\"".to_owned() + "/{id}" + "\"");
-        let post = Method::new(
-            TypeName::new_with_generics(
-                "ResponseEntity".into(),
-                GenericParams::new(vec![initial_class_name.clone() + "DTO"]),
+            .param("value".into(), "\"".to_owned() + &id_path + "\"");
+        let response_of_dto = TypeName::new_with_generics(
+            "ResponseEntity".into(),
+            GenericParams::new(vec![response_name.clone()]),
+        );
+        //the ids of the request are looked up by the service before the entity is saved
+        let references = self.reference_assignments();
+        let changes = match references.is_empty() {
+            true => "entity -> mapper.updateEntity(request, entity)".to_owned(),
+            false => format!(
+                "entity -> {{\n    mapper.updateEntity(request, entity);\n    {}\n}}",
+                references.join("\n    ")
             ),
+        };
+        let post = Method::new(
+            response_of_dto.clone(),
             "create".to_owned() + &initial_class_name,
         )
+        .public()
         .annotation(post_mapping)
-        .code(format!(
-            r#"
-            return null;
-    "#,
-        ));
-        let get_by_id = Method::new(
-            TypeName::new_with_generics(
-                "ResponseEntity".into(),
-                GenericParams::new(vec![initial_class_name.clone() + "DTO"]),
+        .param(request_body.clone())
+        .code(match references.is_empty() {
+            true => "var created = service.create(mapper.toEntity(request));\n".to_owned(),
+            false => format!(
+                "var entity = mapper.toEntity(request);\n{}\nvar created = service.create(entity);\n",
+                references.join("\n")
             ),
-            "get".to_owned() + &initial_class_name + "ById",
+        } + "return ResponseEntity.status(HttpStatus.CREATED).body(mapper.toResponse(created));");
+        let get_by_id = with_id(
+            Method::new(
+                response_of_dto.clone(),
+                "get".to_owned() + &initial_class_name + "ById",
+            )
+            .public()
+            .annotation(get_mapping_id),
         )
-        .annotation(get_mapping_id)
-        .param(id_path_variable.clone())
-        .code(format!(
-            r#"
-            return null;
-    "#,
-        ));
+        .code(id_code.clone() + match self.not_found {
+            None => "return service.findById(id)\n    .map(mapper::toResponse)\n    .map(ResponseEntity::ok)\n    .orElseGet(() -> ResponseEntity.notFound().build());",
+            Some(_) => "return ResponseEntity.ok(mapper.toResponse(service.findById(id)));",
+        });
 
-        let get_all = Method::new(
+        let mut get_all = Method::new(
             TypeName::new_with_generics(
                 "ResponseEntity".into(),
-                GenericParams::new(vec![format!("List<{}DTO>", initial_class_name)]),
+                GenericParams::new(vec![format!("Page<{}>", response_name)]),
             ),
             "getAll".to_owned() + &initial_class_name + "s",
         )
-        .annotation(get_mapping)
-        .code(format!(
-            r#"
-            return null;
-    "#,
-        ));
+        .public()
+        .annotation(get_mapping);
+        let filters = self.filter_params();
+        let mut arguments: Vec<String> = filters.iter().map(|p| p.name.clone()).collect();
+        arguments.push("pageable".to_owned());
+        for param in filters {
+            get_all = get_all.param(param.annotation(
+                Annotation::new("RequestParam".into()).param("required".into(), "false".into()),
+            ));
+        }
+        get_all = get_all
+            .param(VariableParam::new("Pageable".into(), "pageable".into()))
+            .code(format!(
+                "return ResponseEntity.ok(service.findAll({}).map(mapper::toResponse));",
+                arguments.join(", ")
+            ));
 
-        let update = Method::new(
-            TypeName::new_with_generics(
-                "ResponseEntity".into(),
-                GenericParams::new(vec![initial_class_name.clone() + "DTO"]),
-            ),
-            "update".to_owned() + &initial_class_name,
+        let update = with_id(
+            Method::new(response_of_dto, "update".to_owned() + &initial_class_name)
+                .public()
+                .annotation(update_mapping_id),
         )
-        .annotation(update_mapping_id)
-        .param(id_path_variable.clone())
-        .code(format!(
-            r#"
-            return null;
-    "#,
-        ));
+        .param(request_body)
+        .code(id_code.clone() + &match self.not_found {
+            None => format!("return service.update(id, {})\n    .map(mapper::toResponse)\n    .map(ResponseEntity::ok)\n    .orElseGet(() -> ResponseEntity.notFound().build());", changes),
+            Some(_) => format!("var updated = service.update(id, {});\nreturn ResponseEntity.ok(mapper.toResponse(updated));", changes),
+        });
 
-        let delete = Method::new(
-            TypeName::new_with_generics(
-                "ResponseEntity".into(),
-                GenericParams::new(vec!["Void".to_string()]),
-            ),
-            "delete".to_owned() + &initial_class_name,
+        let delete = with_id(
+            Method::new(
+                TypeName::new_with_generics(
+                    "ResponseEntity".into(),
+                    GenericParams::new(vec!["Void".to_string()]),
+                ),
+                "delete".to_owned() + &initial_class_name,
+            )
+            .public()
+            .annotation(delete_mapping_id),
         )
-        .annotation(delete_mapping_id)
-        .param(id_path_variable)
-        .code(format!(
-            r#"
-            return null;
-    "#,
-        ));
+        .code(id_code.clone() + match self.not_found {
+            None => "if (!service.delete(id)) {\n    return ResponseEntity.notFound().build();\n}\nreturn ResponseEntity.noContent().build();",
+            Some(_) => "service.delete(id);\nreturn ResponseEntity.noContent().build();",
+        });
 
         controller = controller
             .method(post)
@@ -237,36 +1525,604 @@ impl CrudBuilder {
             Import::new("org.springframework.http".into(), "ResponseEntity".into()),
             Import::new("org.springframework.web.bind.annotation".into(), "*".into()),
         ];
-        controller = controller.imports(spring_imports);
+        controller = controller
+            .imports(spring_imports)
+            .import(Import::new("jakarta.validation".into(), "Valid".into()));
+
+        //the service and the mapper of this entity and of the children it lists
+        let mut dependencies = vec![
+            (
+                "service".to_owned(),
+                Layer::Services,
+                initial_class_name.clone(),
+            ),
+            ("mapper".to_owned(), Layer::Dtos, initial_class_name.clone()),
+        ];
+        controller = with_import(
+            controller,
+            self.import_of(Layer::Dtos, &initial_class_name, request_name),
+        );
+        controller = with_import(
+            controller,
+            self.import_of(Layer::Dtos, &initial_class_name, response_name),
+        );
+        controller = controller
+            .import(Import::new(
+                "org.springframework.data.domain".into(),
+                "Page".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.data.domain".into(),
+                "Pageable".into(),
+            ));
 
         controller = controller
-            .import(service_import)
-            .import(dto_import)
-            .import(Import::new("java.util".into(), "List".into()));
-
-        controller = controller.annotation("RestController".into()).annotation(
-            Annotation::new("RequestMapping".into()).param(
-                "value".into(),
-                "\"".to_owned() + "/" + &initial_class_name.to_lowercase() + "\"",
+            .public()
+            .annotation("RestController".into())
+            .annotation(
+                Annotation::new("RequestMapping".into())
+                    .param("value".into(), format!("\"{}\"", self.base_path())),
+            );
+
+        let mut constructor = Method::new("".into(), controller.class_name.clone()).public();
+        let mut assignments = vec![];
+
+        //GET /{id}/orders lists the orders of the customer with that id
+        for route in self.nested_routes() {
+            let (child_service, child_mapper) = match route.child == initial_class_name {
+                true => ("service".to_owned(), "mapper".to_owned()),
+                false => (
+                    lower_first(&self.output.service_name(&route.child)),
+                    lower_first(&self.output.mapper_name(&route.child)),
+                ),
+            };
+            let child_response = self.output.response_name(&route.child);
+            if !dependencies
+                .iter()
+                .any(|(name, _, _)| name == &child_service)
+            {
+                dependencies.push((child_service.clone(), Layer::Services, route.child.clone()));
+                dependencies.push((child_mapper.clone(), Layer::Dtos, route.child.clone()));
+                controller = with_import(
+                    controller,
+                    self.import_of(Layer::Dtos, &route.child, child_response.clone()),
+                );
+            }
+            let nested = with_id(
+                Method::new(
+                    TypeName::new_with_generics(
+                        "ResponseEntity".into(),
+                        GenericParams::new(vec![format!("Page<{}>", child_response)]),
+                    ),
+                    format!("get{}{}", initial_class_name, capitalize(&route.segment)),
+                )
+                .public()
+                .annotation(Annotation::new("GetMapping".into()).param(
+                    "value".into(),
+                    format!("\"{}/{}\"", id_path, route.segment),
+                )),
+            )
+            .param(VariableParam::new("Pageable".into(), "pageable".into()))
+            .code(format!(
+                "{}{}\nreturn ResponseEntity.ok({}.{}(id, pageable).map({}::toResponse));",
+                id_code,
+                match self.not_found {
+                    None => "if (service.findById(id).isEmpty()) {\n    return ResponseEntity.notFound().build();\n}",
+                    //throws when the parent does not exist
+                    Some(_) => "service.findById(id);",
+                },
+                child_service,
+                route.finder(),
+                child_mapper
+            ));
+            controller = controller.method(nested);
+        }
+        for (name, layer, entity) in dependencies {
+            let type_ = match layer {
+                Layer::Services => self.output.service_name(&entity),
+                _ => self.output.mapper_name(&entity),
+            };
+            controller = with_import(controller, self.import_of(layer, &entity, type_.clone()));
+            let mut field = Field::n(name.clone(), type_.clone().into());
+            field.modifiers.push(AccessModifiers::Final);
+            controller = controller.field(field);
+            constructor = constructor.param(VariableParam::new(type_.into(), name.clone()));
+            assignments.push(format!("this.{} = {};", name, name));
+        }
+        controller = controller.method(constructor.code(assignments.join("\n")));
+
+        let mut types = vec![self.id_type()];
+        types.extend(id_params.iter().map(|p| p.type_.name.clone()));
+        self.with_type_imports(controller, &types)
+    }
+
+    //statements declaring `id` with a stored or an unknown value and the arguments of the
+    //path that has it, none when the id has a type there is no sample for
+    fn sample_id(&self, unknown: bool) -> Option<(String, Vec<String>, Vec<Import>)> {
+        let value = |field: &Field| match unknown {
+            true => unknown_value(&boxed(&field.type_.name)),
+            false => sample_value(&Field::n(field.name.clone(), field.type_.clone())),
+        };
+        let id_type = self.id_type();
+        let mut imports: Vec<Import> = self.id_import(&id_type).into_iter().collect();
+        let key_fields = match self.has_own_id() {
+            true => vec![],
+            false => self.options.id.key_fields(),
+        };
+        if key_fields.is_empty() {
+            let (value, import) = value(&self.id_field())?;
+            imports.extend(import);
+            let code = format!("{} id = {};\n", id_type, value);
+            return Some((code, vec!["id".to_owned()], imports));
+        }
+        let mut code = format!("{key} id = new {key}();\n", key = id_type);
+        let mut arguments = vec![];
+        for field in key_fields {
+            let (value, import) = value(&field)?;
+            imports.extend(import);
+            code.push_str(&format!("id.{}({});\n", setter(&field), value));
+            arguments.push(format!("id.{}()", getter(&field)));
+        }
+        Some((code, arguments, imports))
+    }
+
+    //the entity the tests store or return, none when a required field has no sample
+    fn sample_entity(&self, id_param: bool, lenient: bool) -> Option<(Method, Vec<Import>)> {
+        let entity = self.for_class.class_name.clone();
+        let variable = lower_first(&entity);
+        let mut code = format!("{entity} {variable} = new {entity}();\n");
+        let mut imports = vec![];
+        let id = self.id_field();
+        let mut method = Method::new(entity.clone().into(), variable.clone()).private();
+        if id_param {
+            method = method.param(VariableParam::new(self.id_type().into(), "id".into()));
+            code.push_str(&format!("{}.{}(id);\n", variable, setter(&id)));
+        } else if self.assigned_id() {
+            let (id_code, _, id_imports) = self.sample_id(false)?;
+            code.push_str(&id_code);
+            code.push_str(&format!("{}.{}(id);\n", variable, setter(&id)));
+            imports.extend(id_imports);
+        }
+        let mut fields = self.plain_fields();
+        fields.extend(self.relation_fields());
+        let (fill, fill_imports) = fill_in(&variable, &fields, lenient)?;
+        code.push_str(&fill);
+        imports.extend(fill_imports);
+        code.push_str(&format!("return {};", variable));
+        Some((method.code(code), imports))
+    }
+
+    //the body of a create or update the constraints of the request accept
+    fn sample_request(&self) -> Option<(Method, Vec<Import>)> {
+        let name = self.output.request_name(&self.for_class.class_name);
+        let variable = lower_first(&name);
+        let mut fields = vec![];
+        if self.assigned_id() {
+            let id = self.id_field();
+            fields.push(Field::n(id.name, id.type_));
+        }
+        for field in self.request_fields() {
+            let mut request_field = Field::n(field.name(), field.type_());
+            request_field.annotation = field.entity.annotation.clone();
+            fields.push(request_field);
+        }
+        let (fill, imports) = fill_in(&variable, &fields, false)?;
+        let method = Method::new(name.clone().into(), variable.clone())
+            .private()
+            .code(format!(
+                "{name} {variable} = new {name}();\n{fill}return {variable};"
+            ));
+        Some((method, imports))
+    }
+
+    //mock mvc against the controller alone, its services are mocks and the mapper is the real one
+    pub fn controller_test(&self, suite: &TestSuite) -> JavaClass {
+        let entity = self.for_class.class_name.clone();
+        let controller = self.output.controller_name(&entity);
+        let service = self.output.service_name(&entity);
+        let mapper = match self.options.dto.mapper {
+            MapperStyle::HandWritten => self.output.mapper_name(&entity),
+            //mapstruct writes the implementation when the project compiles
+            MapperStyle::MapStruct => self.output.mapper_name(&entity) + "Impl",
+        };
+        let package = self.package(Layer::Controllers, &entity);
+        let mut test = suite.web_slice(
+            TestSuite::test_class(controller.clone() + "Test", package),
+            &controller,
+        );
+        test = test
+            .annotation(
+                Annotation::new("Import".into()).param("value".into(), format!("{}.class", mapper)),
+            )
+            .import(Import::new(
+                "org.springframework.context.annotation".into(),
+                "Import".into(),
+            ));
+        test = with_import(test, self.import_of(Layer::Dtos, &entity, mapper));
+        test = with_import(test, self.model_import(&entity));
+        test = with_import(
+            test,
+            self.import_of(Layer::Services, &entity, service.clone()),
+        );
+        test = suite.mocked(test, "service", &service);
+        //the children listed under this entity are looked up by the controller too
+        for route in self.nested_routes() {
+            let child_service = self.output.service_name(&route.child);
+            if route.child == entity || test.fields.iter().any(|f| f.type_.name == child_service) {
+                continue;
+            }
+            let child_mapper = self.output.mapper_name(&route.child);
+            for (layer, type_) in [
+                (Layer::Services, child_service),
+                (Layer::Dtos, child_mapper),
+            ] {
+                test = with_import(test, self.import_of(layer, &route.child, type_.clone()));
+                test = suite.mocked(test, &lower_first(&type_), &type_);
+            }
+        }
+
+        let mut statics = vec![("org.mockito.BDDMockito", "given")];
+        let request_builders =
+            "org.springframework.test.web.servlet.request.MockMvcRequestBuilders";
+        let matchers = "org.mockito.ArgumentMatchers";
+        let mut imports = vec![];
+        let mut methods = vec![];
+        let path = |id_path: bool, arguments: &[String]| match id_path {
+            true => format!(
+                "\"{}{}\", {}",
+                self.base_path(),
+                self.id_path().0,
+                arguments.join(", ")
             ),
+            false => format!("\"{}\"", self.base_path()),
+        };
+        let variable = lower_first(&entity);
+        let found = |value: String| match self.not_found {
+            None => format!("Optional.of({})", value),
+            Some(_) => value,
+        };
+        let request = self.sample_request();
+        if let (Some((id_code, arguments, id_imports)), Some((sample, sample_imports))) =
+            (self.sample_id(false), self.sample_entity(true, true))
+        {
+            imports.extend(id_imports);
+            imports.extend(sample_imports);
+            methods.push(sample);
+            let stored = found(format!("{}(id)", variable));
+            methods.push(TestSuite::test_method(
+                format!("gets{}ById", entity),
+                format!(
+                    "{id_code}given(service.findById(id)).willReturn({stored});\nmockMvc.perform(get({}))\n    .andExpect(status().isOk());",
+                    path(true, &arguments)
+                ),
+            ));
+            let missing = match self.not_found {
+                None => "willReturn(Optional.empty())".to_owned(),
+                Some(ref exception) => {
+                    imports.push(exception.clone());
+                    format!(
+                        "willThrow(new {}(\"{}\", id))",
+                        exception.class_name, entity
+                    )
+                }
+            };
+            methods.push(TestSuite::test_method(
+                format!("answersNotFoundForMissing{}", entity),
+                format!(
+                    "{id_code}given(service.findById(id)).{missing};\nmockMvc.perform(get({}))\n    .andExpect(status().isNotFound());",
+                    path(true, &arguments)
+                ),
+            ));
+            let mut filters: Vec<String> = self
+                .filter_params()
+                .iter()
+                .map(|_| "isNull()".to_owned())
+                .collect();
+            if !filters.is_empty() {
+                statics.push((matchers, "isNull"));
+            }
+            filters.push("any(Pageable.class)".to_owned());
+            statics.push((matchers, "any"));
+            imports.extend([
+                Import::new("java.util".into(), "List".into()),
+                Import::new("org.springframework.data.domain".into(), "PageImpl".into()),
+                Import::new("org.springframework.data.domain".into(), "Pageable".into()),
+            ]);
+            methods.push(TestSuite::test_method(
+                format!("lists{}s", entity),
+                format!(
+                    "{id_code}given(service.findAll({})).willReturn(new PageImpl<>(List.of({}(id))));\nmockMvc.perform(get({}))\n    .andExpect(status().isOk());",
+                    filters.join(", "),
+                    variable,
+                    path(false, &arguments)
+                ),
+            ));
+            if let Some((request, request_imports)) = request {
+                let body = format!(
+                    "\n        .contentType(MediaType.APPLICATION_JSON)\n        .content(objectMapper.writeValueAsString({}())))",
+                    request.name
+                );
+                imports.extend(request_imports);
+                imports.push(Import::new(
+                    "org.springframework.http".into(),
+                    "MediaType".into(),
+                ));
+                imports.push(Import::new(
+                    "com.fasterxml.jackson.databind".into(),
+                    "ObjectMapper".into(),
+                ));
+                imports.push(self.import_of(
+                    Layer::Dtos,
+                    &entity,
+                    self.output.request_name(&entity),
+                ));
+                statics.push((matchers, "eq"));
+                methods.push(TestSuite::test_method(
+                    format!("creates{}", entity),
+                    format!(
+                        "{id_code}given(service.create(any({entity}.class))).willReturn({variable}(id));\nmockMvc.perform(post({}){body}\n    .andExpect(status().isCreated());",
+                        path(false, &arguments)
+                    ),
+                ));
+                methods.push(TestSuite::test_method(
+                    format!("updates{}", entity),
+                    format!(
+                        "{id_code}given(service.update(eq(id), any())).willReturn({stored});\nmockMvc.perform(put({}){body}\n    .andExpect(status().isOk());",
+                        path(true, &arguments)
+                    ),
+                ));
+                methods.push(request);
+                statics.push((request_builders, "post"));
+                statics.push((request_builders, "put"));
+                test = test.field(TestSuite::autowired("objectMapper", "ObjectMapper"));
+            }
+            //a void delete of a mock does nothing
+            let deleted = match self.not_found {
+                None => "given(service.delete(id)).willReturn(true);\n",
+                Some(_) => "",
+            };
+            methods.push(TestSuite::test_method(
+                format!("deletes{}", entity),
+                format!(
+                    "{id_code}{deleted}mockMvc.perform(delete({}))\n    .andExpect(status().isNoContent());",
+                    path(true, &arguments)
+                ),
+            ));
+            statics.push((request_builders, "delete"));
+            if self.not_found.is_none() {
+                imports.push(Import::new("java.util".into(), "Optional".into()));
+            }
+        }
+        statics.push((request_builders, "get"));
+        statics.push((
+            "org.springframework.test.web.servlet.result.MockMvcResultMatchers",
+            "status",
+        ));
+        for import in imports {
+            test = with_import(test, import);
+        }
+        for (package, name) in statics {
+            test = with_import(test, Import::new(package.into(), name.into()).static_());
+        }
+        sorted_methods(test, methods)
+    }
+
+    //the repository on the database the tests run against, every test is rolled back
+    pub fn repository_test(&self, suite: &TestSuite) -> JavaClass {
+        let entity = self.for_class.class_name.clone();
+        let repository = self.output.repository_name(&entity);
+        let package = self.package(Layer::Repositories, &entity);
+        let mut test = suite
+            .jpa_slice(
+                TestSuite::test_class(repository.clone() + "Test", package),
+                &self.root_package,
+            )
+            .field(TestSuite::autowired("repository", &repository))
+            .import(
+                Import::new(
+                    "org.assertj.core.api.Assertions".into(),
+                    "assertThat".into(),
+                )
+                .static_(),
+            );
+        test = with_import(test, self.model_import(&entity));
+        let variable = lower_first(&entity);
+        let id_getter = getter(&self.id_field());
+        let mut imports = vec![];
+        let mut methods = vec![];
+        if let Some((sample, sample_imports)) = self.sample_entity(false, false) {
+            imports.extend(sample_imports);
+            methods.push(sample);
+            methods.push(TestSuite::test_method(
+                format!("savesAndFinds{}", entity),
+                format!(
+                    "{entity} saved = repository.saveAndFlush({variable}());\nassertThat(repository.findById(saved.{id_getter}())).isPresent();"
+                ),
+            ));
+            methods.push(TestSuite::test_method(
+                format!("deletes{}", entity),
+                format!(
+                    "{entity} saved = repository.saveAndFlush({variable}());\nrepository.delete(saved);\nrepository.flush();\nassertThat(repository.findById(saved.{id_getter}())).isEmpty();"
+                ),
+            ));
+        }
+        if let Some((id_code, _, id_imports)) = self.sample_id(true) {
+            imports.extend(id_imports);
+            methods.push(TestSuite::test_method(
+                format!("findsNo{}ForAnUnknownId", entity),
+                format!("{id_code}assertThat(repository.findById(id)).isEmpty();"),
+            ));
+        }
+        for import in imports {
+            test = with_import(test, import);
+        }
+        sorted_methods(test, methods)
+    }
+
+    //the endpoints of the entity with everything behind them, against the container database
+    pub fn integration_test(&self, suite: &TestSuite) -> JavaClass {
+        let entity = self.for_class.class_name.clone();
+        let repository = self.output.repository_name(&entity);
+        let package = self.package(Layer::Controllers, &entity);
+        let mut test = suite
+            .application(
+                TestSuite::test_class(entity.clone() + "IntegrationTest", package),
+                &self.root_package,
+            )
+            .field(TestSuite::autowired("repository", &repository));
+        test = with_import(
+            test,
+            self.import_of(Layer::Repositories, &entity, repository),
         );
+        test = with_import(test, self.model_import(&entity));
+        let request_builders =
+            "org.springframework.test.web.servlet.request.MockMvcRequestBuilders";
+        test = test
+            .import(Import::new(request_builders.into(), "get".into()).static_())
+            .import(
+                Import::new(
+                    "org.springframework.test.web.servlet.result.MockMvcResultMatchers".into(),
+                    "status".into(),
+                )
+                .static_(),
+            );
+        let path = |arguments: &[String]| {
+            format!(
+                "\"{}{}\", {}",
+                self.base_path(),
+                self.id_path().0,
+                arguments.join(", ")
+            )
+        };
+        let variable = lower_first(&entity);
+        let mut imports = vec![];
+        let mut methods = vec![TestSuite::test_method(
+            format!("lists{}s", entity),
+            format!(
+                "mockMvc.perform(get(\"{}\"))\n    .andExpect(status().isOk());",
+                self.base_path()
+            ),
+        )];
+        if let (Some((sample, sample_imports)), Some((_, arguments, _))) =
+            (self.sample_entity(false, false), self.sample_id(false))
+        {
+            imports.extend(sample_imports);
+            methods.push(sample);
+            methods.push(TestSuite::test_method(
+                format!("readsAStored{}", entity),
+                format!(
+                    "{entity} saved = repository.save({variable}());\n{} id = saved.{}();\nmockMvc.perform(get({}))\n    .andExpect(status().isOk());",
+                    self.id_type(),
+                    getter(&self.id_field()),
+                    path(&arguments)
+                ),
+            ));
+            imports.extend(self.id_import(&self.id_type()));
+        }
+        if let Some((id_code, arguments, id_imports)) = self.sample_id(true) {
+            imports.extend(id_imports);
+            methods.push(TestSuite::test_method(
+                format!("answersNotFoundForMissing{}", entity),
+                format!(
+                    "{id_code}mockMvc.perform(get({}))\n    .andExpect(status().isNotFound());",
+                    path(&arguments)
+                ),
+            ));
+        }
+        for import in imports {
+            test = with_import(test, import);
+        }
+        sorted_methods(test, methods)
+    }
+}
+
+//setters for the fields that have a sample, none when a required one has not
+fn fill_in(variable: &str, fields: &[Field], lenient: bool) -> Option<(String, Vec<Import>)> {
+    let mut code = String::new();
+    let mut imports = vec![];
+    for field in fields {
+        match sample_value(field) {
+            Some((value, import)) => {
+                code.push_str(&format!("{}.{}({});\n", variable, setter(field), value));
+                imports.extend(import);
+            }
+            None if is_required(field) && !lenient => return None,
+            None => {}
+        }
+    }
+    Some((code, imports))
+}
+
+//the tests first, the helpers they share after them
+fn sorted_methods(mut jclass: JavaClass, methods: Vec<Method>) -> JavaClass {
+    let (tests, helpers): (Vec<Method>, Vec<Method>) = methods
+        .into_iter()
+        .partition(|m| m.annotations.iter().any(|a| a.qualified_name == "Test"));
+    for method in tests.into_iter().chain(helpers) {
+        jclass = jclass.method(method);
+    }
+    jclass
+}
+
+//adds the import unless the class is in the same package or imports it already
+fn with_import(jclass: JavaClass, import: Import) -> JavaClass {
+    if import.package_name == jclass.package || jclass.imports.contains(&import) {
+        return jclass;
+    }
+    jclass.import(import)
+}
 
-        let service_type: TypeName = (initial_class_name + "Service").into();
-        controller = controller.field(service_type.clone().into());
-        let constructor = Method::new("".into(), controller.class_name.clone())
-            .param(service_type.into())
-            .code("".into());
-        controller = controller.method(constructor);
+//the fields of the input class by name, a set has no order of its own
+fn sorted_fields(jclass: &JavaClass) -> Vec<Field> {
+    let mut fields: Vec<Field> = jclass.fields.iter().cloned().collect();
+    fields.sort_by(|a, b| a.name.cmp(&b.name));
+    fields
+}
 
-        controller
+pub(crate) fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
     }
 }
-fn id_field_for_entity() -> Field {
-    let id_annotation = Annotation::new("Id".into());
-    let id_annotation_strategy = Annotation::new("GeneratedValue".into())
-        .param("strategy".into(), "GenerationType.IDENTITY".into());
-    let id_field = Field::n("id".into(), TypeName::new("Long".into()))
-        .annotation(id_annotation)
-        .annotation(id_annotation_strategy);
-    id_field
+
+//the accessors lombok generates
+fn getter(field: &Field) -> String {
+    let prefix = match field.type_.name.as_str() {
+        "boolean" => "is",
+        _ => "get",
+    };
+    prefix.to_owned() + &capitalize(&field.name)
+}
+
+//request params can be missing, primitives can not be null
+pub(crate) fn boxed(type_name: &str) -> String {
+    match type_name {
+        "int" => "Integer",
+        "long" => "Long",
+        "short" => "Short",
+        "byte" => "Byte",
+        "char" => "Character",
+        "float" => "Float",
+        "double" => "Double",
+        "boolean" => "Boolean",
+        other => other,
+    }
+    .to_owned()
+}
+
+fn derived_find_by(field: &Field) -> String {
+    match field.type_.name.as_str() {
+        "String" => format!("findBy{}ContainingIgnoreCase", capitalize(&field.name)),
+        _ => format!("findBy{}", capitalize(&field.name)),
+    }
+}
+
+fn is_validation_import(import: &Import) -> bool {
+    import.package_name == "jakarta.validation.constraints"
+}
+
+fn setter(field: &Field) -> String {
+    "set".to_owned() + &capitalize(&field.name)
 }
@@ -0,0 +1,294 @@
+//the databases a generated project can run against, each one knows its driver, how to
+//connect to it and how to start it in a container
+use super::{
+    maven_builder::DBInfo,
+    pom_xml::{Library, PomXml, Scope},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DatabaseKind {
+    #[default]
+    PostgreSql,
+    MySql,
+    MariaDb,
+    //in memory, nothing to start
+    H2,
+    SqlServer,
+    Oracle,
+}
+
+//a database container as compose, github actions and gitlab describe it
+#[derive(Clone, Debug, PartialEq)]
+pub struct DatabaseService {
+    pub image: String,
+    //the port inside the container
+    pub port: u16,
+    pub environment: Vec<(String, String)>,
+    pub data_dir: String,
+    //run by a shell, exits with 0 once the database accepts connections
+    pub healthcheck: String,
+}
+
+impl DatabaseKind {
+    pub const ALL: [DatabaseKind; 6] = [
+        DatabaseKind::PostgreSql,
+        DatabaseKind::MySql,
+        DatabaseKind::MariaDb,
+        DatabaseKind::H2,
+        DatabaseKind::SqlServer,
+        DatabaseKind::Oracle,
+    ];
+
+    //the first kind whose driver the pom declares, a driver only the tests use does not count
+    pub fn detect(pom_xml: &PomXml) -> Option<DatabaseKind> {
+        Self::ALL.into_iter().find(|kind| {
+            let driver = kind.driver();
+            pom_xml.has_dependency_that(|d| {
+                !d.is_test()
+                    && d.group_id() == driver.group_id()
+                    && d.artifact_id == driver.artifact_id
+            })
+        })
+    }
+
+    //runtime scoped, the code only talks to jdbc
+    pub fn driver(&self) -> Library {
+        let (group_id, artifact_id) = match self {
+            DatabaseKind::PostgreSql => ("org.postgresql", "postgresql"),
+            DatabaseKind::MySql => ("com.mysql", "mysql-connector-j"),
+            DatabaseKind::MariaDb => ("org.mariadb.jdbc", "mariadb-java-client"),
+            DatabaseKind::H2 => ("com.h2database", "h2"),
+            DatabaseKind::SqlServer => ("com.microsoft.sqlserver", "mssql-jdbc"),
+            DatabaseKind::Oracle => ("com.oracle.database.jdbc", "ojdbc11"),
+        };
+        Library::new(group_id.to_owned(), artifact_id.to_owned()).scope(Scope::Runtime)
+    }
+
+    pub fn driver_class(&self) -> &'static str {
+        match self {
+            DatabaseKind::PostgreSql => "org.postgresql.Driver",
+            DatabaseKind::MySql => "com.mysql.cj.jdbc.Driver",
+            DatabaseKind::MariaDb => "org.mariadb.jdbc.Driver",
+            DatabaseKind::H2 => "org.h2.Driver",
+            DatabaseKind::SqlServer => "com.microsoft.sqlserver.jdbc.SQLServerDriver",
+            DatabaseKind::Oracle => "oracle.jdbc.OracleDriver",
+        }
+    }
+
+    pub fn dialect(&self) -> &'static str {
+        match self {
+            DatabaseKind::PostgreSql => "org.hibernate.dialect.PostgreSQLDialect",
+            DatabaseKind::MySql => "org.hibernate.dialect.MySQLDialect",
+            DatabaseKind::MariaDb => "org.hibernate.dialect.MariaDBDialect",
+            DatabaseKind::H2 => "org.hibernate.dialect.H2Dialect",
+            DatabaseKind::SqlServer => "org.hibernate.dialect.SQLServerDialect",
+            DatabaseKind::Oracle => "org.hibernate.dialect.OracleDialect",
+        }
+    }
+
+    //0 for h2, it does not listen on a port
+    pub fn default_port(&self) -> u16 {
+        match self {
+            DatabaseKind::PostgreSql => 5432,
+            DatabaseKind::MySql | DatabaseKind::MariaDb => 3306,
+            DatabaseKind::H2 => 0,
+            DatabaseKind::SqlServer => 1433,
+            DatabaseKind::Oracle => 1521,
+        }
+    }
+
+    //flyway 10 moved the support for most databases out of flyway-core
+    pub fn flyway_module(&self) -> Option<Library> {
+        let artifact_id = match self {
+            DatabaseKind::PostgreSql => "flyway-database-postgresql",
+            DatabaseKind::MySql | DatabaseKind::MariaDb => "flyway-mysql",
+            DatabaseKind::SqlServer => "flyway-sqlserver",
+            DatabaseKind::Oracle => "flyway-database-oracle",
+            DatabaseKind::H2 => return None,
+        };
+        Some(Library::new(
+            "org.flywaydb".to_owned(),
+            artifact_id.to_owned(),
+        ))
+    }
+
+    //the column type hibernate expects for a java type, enums are stored by name and
+    //anything unknown ends up as text
+    pub fn column_type(&self, java_type: &str, length: Option<u32>) -> String {
+        use DatabaseKind::*;
+        let text = |n: u32| match self {
+            Oracle => format!("VARCHAR2({})", n),
+            SqlServer => format!("NVARCHAR({})", n),
+            _ => format!("VARCHAR({})", n),
+        };
+        let sql_type = match (java_type, self) {
+            ("Long" | "long", Oracle) => "NUMBER(19)",
+            ("Long" | "long", _) => "BIGINT",
+            ("Integer" | "int", Oracle) => "NUMBER(10)",
+            ("Integer" | "int", _) => "INTEGER",
+            ("Short" | "short", Oracle) => "NUMBER(5)",
+            ("Short" | "short", _) => "SMALLINT",
+            ("Boolean" | "boolean", SqlServer) => "BIT",
+            ("Boolean" | "boolean", Oracle) => "NUMBER(1)",
+            ("Boolean" | "boolean", _) => "BOOLEAN",
+            ("Double" | "double", MySql | MariaDb) => "DOUBLE",
+            ("Double" | "double", SqlServer) => "FLOAT",
+            ("Double" | "double", Oracle) => "BINARY_DOUBLE",
+            ("Double" | "double", _) => "DOUBLE PRECISION",
+            ("Float" | "float", MySql | MariaDb) => "FLOAT",
+            ("Float" | "float", Oracle) => "BINARY_FLOAT",
+            ("Float" | "float", _) => "REAL",
+            ("BigDecimal", Oracle) => "NUMBER(19,2)",
+            ("BigDecimal", _) => "DECIMAL(19,2)",
+            ("Character" | "char", _) => "CHAR(1)",
+            ("LocalDate", _) => "DATE",
+            ("LocalTime", _) => "TIME",
+            ("LocalDateTime", MySql | MariaDb) => "DATETIME(6)",
+            ("LocalDateTime", SqlServer) => "DATETIME2",
+            ("LocalDateTime", _) => "TIMESTAMP",
+            ("Instant" | "OffsetDateTime" | "ZonedDateTime", MySql | MariaDb) => "DATETIME(6)",
+            ("Instant" | "OffsetDateTime" | "ZonedDateTime", SqlServer) => "DATETIMEOFFSET",
+            ("Instant" | "OffsetDateTime" | "ZonedDateTime", _) => "TIMESTAMP WITH TIME ZONE",
+            ("UUID", PostgreSql | H2) => "UUID",
+            ("UUID", SqlServer) => "UNIQUEIDENTIFIER",
+            ("UUID", Oracle) => "RAW(16)",
+            ("UUID", _) => "BINARY(16)",
+            ("byte[]", PostgreSql) => "BYTEA",
+            ("byte[]", MySql | MariaDb) => "LONGBLOB",
+            ("byte[]", SqlServer) => "VARBINARY(MAX)",
+            ("byte[]", Oracle) => "BLOB",
+            ("byte[]", H2) => "VARBINARY",
+            _ => return text(length.unwrap_or(255)),
+        };
+        sql_type.to_owned()
+    }
+
+    //how a column that the database numbers by itself is declared
+    pub fn identity_column(&self, sql_type: &str) -> String {
+        match self {
+            DatabaseKind::MySql | DatabaseKind::MariaDb => format!("{} AUTO_INCREMENT", sql_type),
+            DatabaseKind::SqlServer => format!("{} IDENTITY(1,1)", sql_type),
+            _ => format!("{} GENERATED BY DEFAULT AS IDENTITY", sql_type),
+        }
+    }
+
+    //h2 pretends to be the real database in tests, so the same migrations run on it
+    pub fn h2_mode(&self) -> Option<&'static str> {
+        match self {
+            DatabaseKind::PostgreSql => Some("PostgreSQL"),
+            DatabaseKind::MySql => Some("MySQL"),
+            DatabaseKind::MariaDb => Some("MariaDB"),
+            DatabaseKind::SqlServer => Some("MSSQLServer"),
+            DatabaseKind::Oracle => Some("Oracle"),
+            DatabaseKind::H2 => None,
+        }
+    }
+
+    //e.g. jdbc:postgresql://localhost:5432/shop_db
+    pub fn jdbc_url(&self, host: &str, port: u16, db: &str) -> String {
+        match self {
+            DatabaseKind::PostgreSql => format!("jdbc:postgresql://{}:{}/{}", host, port, db),
+            DatabaseKind::MySql => format!("jdbc:mysql://{}:{}/{}", host, port, db),
+            DatabaseKind::MariaDb => format!("jdbc:mariadb://{}:{}/{}", host, port, db),
+            DatabaseKind::H2 => format!("jdbc:h2:mem:{};DB_CLOSE_DELAY=-1", db),
+            DatabaseKind::SqlServer => format!(
+                "jdbc:sqlserver://{}:{};databaseName={};encrypt=true;trustServerCertificate=true",
+                host, port, db
+            ),
+            DatabaseKind::Oracle => format!("jdbc:oracle:thin:@//{}:{}/{}", host, port, db),
+        }
+    }
+
+    //the credentials the container is started with, some images are picky about them
+    pub fn db_info(&self, project_name: &str) -> DBInfo {
+        let name = project_name.to_ascii_lowercase();
+        let (username, password, db) = match self {
+            //only sa exists and the password has to be complex, master is always there
+            DatabaseKind::SqlServer => (
+                "sa".to_owned(),
+                format!("{}_Passw0rd", name),
+                "master".to_owned(),
+            ),
+            //the image creates the app user inside the default pluggable database
+            DatabaseKind::Oracle => (name.clone(), name, "FREEPDB1".to_owned()),
+            DatabaseKind::H2 => ("sa".to_owned(), "".to_owned(), name + "_db"),
+            _ => (name.clone(), name.clone(), name + "_db"),
+        };
+        DBInfo {
+            kind: *self,
+            db_port: self.default_port(),
+            username,
+            password,
+            db,
+        }
+    }
+
+    pub fn service(&self, db: &DBInfo) -> Option<DatabaseService> {
+        let env = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let service = match self {
+            DatabaseKind::H2 => return None,
+            DatabaseKind::PostgreSql => DatabaseService {
+                image: "postgres:16-alpine".to_owned(),
+                port: 5432,
+                environment: env(&[
+                    ("POSTGRES_DB", &db.db),
+                    ("POSTGRES_USER", &db.username),
+                    ("POSTGRES_PASSWORD", &db.password),
+                ]),
+                data_dir: "/var/lib/postgresql/data".to_owned(),
+                healthcheck: format!("pg_isready -U {} -d {}", db.username, db.db),
+            },
+            DatabaseKind::MySql => DatabaseService {
+                image: "mysql:8.4".to_owned(),
+                port: 3306,
+                environment: env(&[
+                    ("MYSQL_DATABASE", &db.db),
+                    ("MYSQL_USER", &db.username),
+                    ("MYSQL_PASSWORD", &db.password),
+                    ("MYSQL_ROOT_PASSWORD", &db.password),
+                ]),
+                data_dir: "/var/lib/mysql".to_owned(),
+                healthcheck: "mysqladmin ping -h localhost".to_owned(),
+            },
+            DatabaseKind::MariaDb => DatabaseService {
+                image: "mariadb:11".to_owned(),
+                port: 3306,
+                environment: env(&[
+                    ("MARIADB_DATABASE", &db.db),
+                    ("MARIADB_USER", &db.username),
+                    ("MARIADB_PASSWORD", &db.password),
+                    ("MARIADB_ROOT_PASSWORD", &db.password),
+                ]),
+                data_dir: "/var/lib/mysql".to_owned(),
+                healthcheck: "healthcheck.sh --connect --innodb_initialized".to_owned(),
+            },
+            DatabaseKind::SqlServer => DatabaseService {
+                image: "mcr.microsoft.com/mssql/server:2022-latest".to_owned(),
+                port: 1433,
+                environment: env(&[("ACCEPT_EULA", "Y"), ("MSSQL_SA_PASSWORD", &db.password)]),
+                data_dir: "/var/opt/mssql".to_owned(),
+                healthcheck: format!(
+                    "/opt/mssql-tools18/bin/sqlcmd -C -S localhost -U sa -P {} -Q 'SELECT 1'",
+                    db.password
+                ),
+            },
+            DatabaseKind::Oracle => DatabaseService {
+                image: "gvenzl/oracle-free:23-slim".to_owned(),
+                port: 1521,
+                environment: env(&[
+                    ("ORACLE_PASSWORD", &db.password),
+                    ("APP_USER", &db.username),
+                    ("APP_USER_PASSWORD", &db.password),
+                ]),
+                data_dir: "/opt/oracle/oradata".to_owned(),
+                healthcheck: "healthcheck.sh".to_owned(),
+            },
+        };
+        Some(service)
+    }
+}
@@ -0,0 +1,216 @@
+//container files for a generated service: a multi-stage Dockerfile that builds the jar
+//and a compose.yaml that runs it next to its database
+use super::{
+    database::DatabaseService,
+    maven_builder::{BuildSystem, DBInfo},
+    modules::ModuleLayout,
+    pom_xml::PomXml,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct DockerConfig {
+    //the jdk image the jar is built with, derived from the build system when None
+    pub build_image: Option<String>,
+    //a jre is enough to run the jar
+    pub runtime_image: Option<String>,
+    //replaces the image the database kind comes with, e.g. postgis instead of postgres
+    pub database_image: Option<String>,
+    //the image name compose tags the build with
+    pub image_name: Option<String>,
+}
+
+//what the Dockerfile and compose.yaml need to know about the project
+pub struct ContainerTarget<'a> {
+    pub pom_xml: &'a PomXml,
+    pub build_system: &'a BuildSystem,
+    pub modules: Option<&'a ModuleLayout>,
+    pub port: u16,
+    //None when the project has no database driver
+    pub db_info: Option<&'a DBInfo>,
+}
+
+impl ContainerTarget<'_> {
+    fn java_version(&self) -> &str {
+        match self.pom_xml.java.as_str() {
+            "" => "17",
+            v => v,
+        }
+    }
+
+    //spring-boot-docker-compose starts the database from compose.yaml when the app runs
+    //on the host, the app service then only starts with --profile app
+    pub fn uses_docker_compose_support(&self) -> bool {
+        self.pom_xml
+            .has_dependency_that(|d| d.artifact_id == "spring-boot-docker-compose")
+    }
+
+    //None for an in memory database
+    pub fn database_service(&self, docker: Option<&DockerConfig>) -> Option<DatabaseService> {
+        let db = self.db_info?;
+        let mut service = db.kind.service(db)?;
+        if let Some(image) = docker.and_then(|d| d.database_image.clone()) {
+            service.image = image;
+        }
+        Some(service)
+    }
+}
+
+impl DockerConfig {
+    pub fn build_image(mut self, image: String) -> Self {
+        self.build_image = Some(image);
+        self
+    }
+
+    pub fn runtime_image(mut self, image: String) -> Self {
+        self.runtime_image = Some(image);
+        self
+    }
+
+    pub fn database_image(mut self, image: String) -> Self {
+        self.database_image = Some(image);
+        self
+    }
+
+    pub fn image_name(mut self, name: String) -> Self {
+        self.image_name = Some(name);
+        self
+    }
+
+    pub fn image_name_for(&self, pom_xml: &PomXml) -> String {
+        self.image_name.clone().unwrap_or_else(|| {
+            let tag = match pom_xml.project_info.version.as_str() {
+                "" => "latest",
+                v => v,
+            };
+            format!(
+                "{}:{}",
+                pom_xml.project_info.artifact_id.to_ascii_lowercase(),
+                tag
+            )
+        })
+    }
+
+    pub fn dockerfile(&self, target: &ContainerTarget) -> String {
+        let java = target.java_version();
+        //the executable jar is built by the web module in a multi-module project
+        let module_dir = match target.modules {
+            Some(layout) => format!("{}/", layout.web),
+            None => "".to_owned(),
+        };
+        let (default_image, build, jar) = match target.build_system {
+            BuildSystem::Maven => (
+                format!("maven:3.9-eclipse-temurin-{}", java),
+                "RUN --mount=type=cache,target=/root/.m2 mvn -B package -DskipTests".to_owned(),
+                format!("{}target/*.jar", module_dir),
+            ),
+            BuildSystem::Gradle(gradle) => (
+                format!("gradle:{}-jdk{}", gradle.gradle_version, java),
+                "RUN --mount=type=cache,target=/home/gradle/.gradle gradle bootJar --no-daemon"
+                    .to_owned(),
+                format!("{}build/libs/*.jar", module_dir),
+            ),
+        };
+        let build_image = self.build_image.clone().unwrap_or(default_image);
+        let runtime_image = self
+            .runtime_image
+            .clone()
+            .unwrap_or_else(|| format!("eclipse-temurin:{}-jre", java));
+        format!(
+            "# syntax=docker/dockerfile:1
+
+FROM {build_image} AS build
+WORKDIR /workspace
+COPY . .
+{build}
+
+FROM {runtime_image}
+WORKDIR /app
+RUN useradd --system --no-create-home spring
+COPY --from=build /workspace/{jar} app.jar
+USER spring
+EXPOSE {port}
+ENTRYPOINT [\"java\", \"-jar\", \"app.jar\"]
+",
+            port = target.port
+        )
+    }
+
+    //build output and ide files stay out of the build context
+    pub fn dockerignore(&self) -> String {
+        ["target/", "build/", ".gradle/", ".idea/", "*.iml", ".git/"]
+            .iter()
+            .map(|l| format!("{}\n", l))
+            .collect()
+    }
+
+    pub fn compose(&self, target: &ContainerTarget) -> String {
+        let port = target.port;
+        let mut app = format!(
+            "  app:
+    build: .
+    image: {}
+",
+            self.image_name_for(target.pom_xml)
+        );
+        if target.uses_docker_compose_support() {
+            app.push_str("    profiles: [\"app\"]\n");
+        }
+        app.push_str(&format!(
+            "    ports:
+      - \"{port}:{port}\"
+    environment:
+      SERVER_ADDRESS: 0.0.0.0
+      SERVER_PORT: \"{port}\"
+"
+        ));
+        //inside the container the database is already there
+        if target.uses_docker_compose_support() {
+            app.push_str("      SPRING_DOCKER_COMPOSE_ENABLED: \"false\"\n");
+        }
+        let (Some(db), Some(service)) = (target.db_info, target.database_service(Some(self)))
+        else {
+            return format!("services:\n{}", app);
+        };
+        app.push_str(&format!(
+            "      SPRING_DATASOURCE_URL: {url}
+      SPRING_DATASOURCE_USERNAME: {user}
+      SPRING_DATASOURCE_PASSWORD: {password}
+    depends_on:
+      db:
+        condition: service_healthy
+",
+            url = db.kind.jdbc_url("db", service.port, &db.db),
+            user = db.username,
+            password = db.password
+        ));
+        let environment: String = service
+            .environment
+            .iter()
+            .map(|(k, v)| format!("      {}: {}\n", k, v))
+            .collect();
+        format!(
+            "services:
+{app}  db:
+    image: {image}
+    environment:
+{environment}    ports:
+      - \"{db_port}:{port}\"
+    volumes:
+      - db-data:{data_dir}
+    healthcheck:
+      test: [\"CMD-SHELL\", \"{healthcheck}\"]
+      interval: 5s
+      timeout: 5s
+      retries: 10
+
+volumes:
+  db-data:
+",
+            image = service.image,
+            db_port = db.db_port,
+            port = service.port,
+            data_dir = service.data_dir,
+            healthcheck = service.healthcheck
+        )
+    }
+}
@@ -0,0 +1,56 @@
+use std::{fmt::Display, io, path::PathBuf};
+
+use zip::result::ZipError;
+
+use super::validation::ValidationReport;
+
+#[derive(Debug)]
+pub enum CodegenError {
+    Io { path: PathBuf, source: io::Error },
+    Zip(ZipError),
+    //validation refused to write the project, the report says why
+    InvalidModel(ValidationReport),
+    //an existing file could not be read back into the model
+    Parse(String),
+    //something the generator does not know how to emit (yet)
+    Unsupported(String),
+}
+
+impl CodegenError {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        CodegenError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodegenError::Io { path, source } => {
+                write!(f, "Could not write {}: {}", path.display(), source)
+            }
+            CodegenError::Zip(e) => write!(f, "Could not zip the generated project: {}", e),
+            CodegenError::InvalidModel(report) => write!(f, "Invalid model\n{}", report),
+            CodegenError::Parse(what) => write!(f, "Could not parse: {}", what),
+            CodegenError::Unsupported(what) => write!(f, "Unsupported: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodegenError::Io { source, .. } => Some(source),
+            CodegenError::Zip(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ZipError> for CodegenError {
+    fn from(e: ZipError) -> Self {
+        CodegenError::Zip(e)
+    }
+}
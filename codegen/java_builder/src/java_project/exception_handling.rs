@@ -0,0 +1,243 @@
+//opt-in error handling: a @RestControllerAdvice answering with rfc 9457 problem details and the
+//exception the generated services throw when an entity does not exist
+use crate::{
+    annotations::Annotation, classes::JavaClass, imports::Import, java_structs::VariableParam,
+    methods::Method, types::TypeName,
+};
+
+pub const NOT_FOUND_EXCEPTION: &str = "NotFoundException";
+pub const EXCEPTION_HANDLER: &str = "GlobalExceptionHandler";
+
+#[derive(Clone, Debug, Default)]
+pub struct ErrorHandling {
+    //problems are typed {type_base}/not-found etc., about:blank when unset
+    pub type_base: Option<String>,
+    //unexpected exceptions send their message to the client, meant for development
+    pub expose_messages: bool,
+}
+
+fn imports(package: &str, names: &[&str]) -> Vec<Import> {
+    names
+        .iter()
+        .map(|n| Import::new(package.to_owned(), (*n).to_owned()))
+        .collect()
+}
+
+fn exception_handler(exception: &str) -> Annotation {
+    Annotation::new("ExceptionHandler".into()).param("value".into(), format!("{}.class", exception))
+}
+
+impl ErrorHandling {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn type_base(mut self, base: String) -> Self {
+        self.type_base = Some(base.trim_end_matches('/').to_owned());
+        self
+    }
+
+    pub fn expose_messages(mut self) -> Self {
+        self.expose_messages = true;
+        self
+    }
+
+    //the import the services and controllers use for the exception
+    pub fn not_found_import(package: String) -> Import {
+        Import::new(package, NOT_FOUND_EXCEPTION.into())
+    }
+
+    //secured codebases let spring security answer 401 and 403 itself
+    pub fn classes(&self, package: String, secured: bool) -> Vec<JavaClass> {
+        vec![
+            self.not_found_exception(package.clone()),
+            self.advice(package, secured),
+        ]
+    }
+
+    fn not_found_exception(&self, package: String) -> JavaClass {
+        JavaClass::new(NOT_FOUND_EXCEPTION.into(), package)
+            .public()
+            .extends("RuntimeException".into())
+            .method(
+                Method::new("".into(), NOT_FOUND_EXCEPTION.into())
+                    .public()
+                    .param(VariableParam::new("String".into(), "entity".into()))
+                    .param(VariableParam::new("Object".into(), "id".into()))
+                    .code("super(entity + \" \" + id + \" was not found\");".into()),
+            )
+    }
+
+    fn problem(&self, status: &str, detail: &str, title: &str, type_: &str) -> String {
+        let mut code = format!(
+            "ProblemDetail problem = ProblemDetail.forStatusAndDetail(HttpStatus.{}, {});\nproblem.setTitle(\"{}\");\n",
+            status, detail, title
+        );
+        if let Some(base) = &self.type_base {
+            code.push_str(&format!(
+                "problem.setType(URI.create(\"{}/{}\"));\n",
+                base, type_
+            ));
+        }
+        code
+    }
+
+    fn advice(&self, package: String, secured: bool) -> JavaClass {
+        let problem_detail: TypeName = "ProblemDetail".into();
+        let not_found = Method::new(problem_detail.clone(), "handleNotFound".into())
+            .public()
+            .annotation(exception_handler(NOT_FOUND_EXCEPTION))
+            .param(VariableParam::new(
+                NOT_FOUND_EXCEPTION.into(),
+                "exception".into(),
+            ))
+            .code(
+                self.problem(
+                    "NOT_FOUND",
+                    "exception.getMessage()",
+                    "Not Found",
+                    "not-found",
+                ) + "return problem;",
+            );
+
+        let errors_of = |loop_: &str| {
+            format!(
+                "Map<String, String> errors = new LinkedHashMap<>();\n{}\nproblem.setProperty(\"errors\", errors);\nreturn problem;",
+                loop_
+            )
+        };
+        let invalid_body = Method::new(problem_detail.clone(), "handleInvalidBody".into())
+            .public()
+            .annotation(exception_handler("MethodArgumentNotValidException"))
+            .param(VariableParam::new(
+                "MethodArgumentNotValidException".into(),
+                "exception".into(),
+            ))
+            .code(
+                self.problem(
+                    "BAD_REQUEST",
+                    "\"The request has invalid fields\"",
+                    "Validation Failed",
+                    "validation",
+                ) + &errors_of(
+                    "for (FieldError error : exception.getBindingResult().getFieldErrors()) {\n    errors.putIfAbsent(error.getField(), error.getDefaultMessage());\n}",
+                ),
+            );
+        let constraint_violation = Method::new(
+            problem_detail.clone(),
+            "handleConstraintViolation".into(),
+        )
+        .public()
+        .annotation(exception_handler("ConstraintViolationException"))
+        .param(VariableParam::new(
+            "ConstraintViolationException".into(),
+            "exception".into(),
+        ))
+        .code(
+            self.problem(
+                "BAD_REQUEST",
+                "\"The request violates constraints\"",
+                "Constraint Violation",
+                "constraint-violation",
+            ) + &errors_of(
+                "for (ConstraintViolation<?> violation : exception.getConstraintViolations()) {\n    errors.putIfAbsent(violation.getPropertyPath().toString(), violation.getMessage());\n}",
+            ),
+        );
+
+        //the entity was changed since it was read, only versioned entities notice
+        let conflict = Method::new(problem_detail.clone(), "handleConflict".into())
+            .public()
+            .annotation(exception_handler("ObjectOptimisticLockingFailureException"))
+            .param(VariableParam::new(
+                "ObjectOptimisticLockingFailureException".into(),
+                "exception".into(),
+            ))
+            .code(
+                self.problem(
+                    "CONFLICT",
+                    "\"The entity was changed by another request\"",
+                    "Conflict",
+                    "conflict",
+                ) + "return problem;",
+            );
+
+        //spring's own exceptions already know their status
+        let mut unexpected_code =
+            "if (exception instanceof ErrorResponse errorResponse) {\n    return errorResponse.getBody();\n}\n"
+                .to_owned();
+        if secured {
+            unexpected_code.push_str(
+                "if (exception instanceof AccessDeniedException || exception instanceof AuthenticationException) {\n    throw exception;\n}\n",
+            );
+        }
+        let detail = match self.expose_messages {
+            true => "String.valueOf(exception.getMessage())",
+            false => "\"An unexpected error occurred\"",
+        };
+        unexpected_code.push_str("log.error(\"Unexpected error\", exception);\n");
+        unexpected_code.push_str(&self.problem(
+            "INTERNAL_SERVER_ERROR",
+            detail,
+            "Internal Server Error",
+            "internal-error",
+        ));
+        unexpected_code.push_str("return problem;");
+        let mut unexpected = Method::new(problem_detail, "handleUnexpected".into())
+            .public()
+            .annotation(exception_handler("Exception"))
+            .param(VariableParam::new("Exception".into(), "exception".into()))
+            .code(unexpected_code);
+        if secured {
+            unexpected = unexpected.throws("Exception".into());
+        }
+
+        let mut advice = JavaClass::new(EXCEPTION_HANDLER.into(), package)
+            .public()
+            .annotation("Slf4j".into())
+            .annotation("RestControllerAdvice".into())
+            .import(Import::new("lombok.extern.slf4j".into(), "Slf4j".into()))
+            .imports(imports("java.util", &["LinkedHashMap", "Map"]))
+            .imports(imports(
+                "jakarta.validation",
+                &["ConstraintViolation", "ConstraintViolationException"],
+            ))
+            .imports(imports(
+                "org.springframework.http",
+                &["HttpStatus", "ProblemDetail"],
+            ))
+            .imports(imports(
+                "org.springframework.orm",
+                &["ObjectOptimisticLockingFailureException"],
+            ))
+            .imports(imports("org.springframework.validation", &["FieldError"]))
+            .imports(imports("org.springframework.web", &["ErrorResponse"]))
+            .imports(imports(
+                "org.springframework.web.bind",
+                &["MethodArgumentNotValidException"],
+            ))
+            .imports(imports(
+                "org.springframework.web.bind.annotation",
+                &["ExceptionHandler", "RestControllerAdvice"],
+            ))
+            .method(not_found)
+            .method(invalid_body)
+            .method(constraint_violation)
+            .method(conflict)
+            .method(unexpected);
+        if self.type_base.is_some() {
+            advice = advice.import(Import::new("java.net".into(), "URI".into()));
+        }
+        if secured {
+            advice = advice
+                .imports(imports(
+                    "org.springframework.security.access",
+                    &["AccessDeniedException"],
+                ))
+                .imports(imports(
+                    "org.springframework.security.core",
+                    &["AuthenticationException"],
+                ));
+        }
+        advice
+    }
+}
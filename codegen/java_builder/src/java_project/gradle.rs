@@ -0,0 +1,220 @@
+use super::pom_xml::{Library, PomXml, Scope};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GradleDsl {
+    #[default]
+    Kotlin,
+    Groovy,
+}
+
+//renders the gradle files of a project from the same model as the pom.xml
+#[derive(Debug, Clone)]
+pub struct GradleBuild {
+    pub dsl: GradleDsl,
+    pub gradle_version: String,
+    pub dependency_management_version: String,
+}
+
+impl Default for GradleBuild {
+    fn default() -> Self {
+        Self {
+            dsl: GradleDsl::default(),
+            gradle_version: "8.12".to_owned(),
+            dependency_management_version: "1.1.7".to_owned(),
+        }
+    }
+}
+
+impl GradleBuild {
+    pub fn new(dsl: GradleDsl) -> Self {
+        Self {
+            dsl,
+            ..Default::default()
+        }
+    }
+
+    pub fn gradle_version(mut self, version: String) -> Self {
+        self.gradle_version = version;
+        self
+    }
+
+    pub fn build_file_name(&self) -> &'static str {
+        match self.dsl {
+            GradleDsl::Kotlin => "build.gradle.kts",
+            GradleDsl::Groovy => "build.gradle",
+        }
+    }
+
+    pub fn settings_file_name(&self) -> &'static str {
+        match self.dsl {
+            GradleDsl::Kotlin => "settings.gradle.kts",
+            GradleDsl::Groovy => "settings.gradle",
+        }
+    }
+
+    //relative to the project root
+    pub fn wrapper_properties_path(&self) -> &'static str {
+        "gradle/wrapper/gradle-wrapper.properties"
+    }
+
+    fn quote(&self, s: &str) -> String {
+        match self.dsl {
+            GradleDsl::Kotlin => format!(
+                "\"{}\"",
+                s.replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('$', "\\$")
+            ),
+            GradleDsl::Groovy => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        }
+    }
+
+    fn plugin(&self, id: &str, version: Option<&str>) -> String {
+        match (self.dsl, version) {
+            (GradleDsl::Kotlin, Some(v)) => format!("    id(\"{}\") version \"{}\"\n", id, v),
+            (GradleDsl::Kotlin, None) => format!("    id(\"{}\")\n", id),
+            (GradleDsl::Groovy, Some(v)) => format!("    id '{}' version '{}'\n", id, v),
+            (GradleDsl::Groovy, None) => format!("    id '{}'\n", id),
+        }
+    }
+
+    fn dependency(&self, configuration: &str, lib: &Library) -> String {
+        let mut notation = format!("{}:{}", lib.group_id(), lib.artifact_id);
+        if let Some(v) = lib.version() {
+            notation = notation + ":" + v;
+        }
+        match self.dsl {
+            GradleDsl::Kotlin => format!("    {}({})\n", configuration, self.quote(&notation)),
+            GradleDsl::Groovy => format!("    {} {}\n", configuration, self.quote(&notation)),
+        }
+    }
+
+    fn platform(&self, bom: &Library) -> String {
+        let notation = format!(
+            "{}:{}:{}",
+            bom.group_id(),
+            bom.artifact_id,
+            bom.version().unwrap_or_default()
+        );
+        match self.dsl {
+            GradleDsl::Kotlin => {
+                format!("    implementation(platform({}))\n", self.quote(&notation))
+            }
+            GradleDsl::Groovy => {
+                format!("    implementation platform({})\n", self.quote(&notation))
+            }
+        }
+    }
+
+    pub fn build_script(&self, pom_xml: &PomXml) -> String {
+        let mut result = "plugins {\n".to_owned();
+        result += &self.plugin("java", None);
+        if let Some(boot_version) = pom_xml.spring_boot_version() {
+            result += &self.plugin("org.springframework.boot", Some(boot_version));
+            result += &self.plugin(
+                "io.spring.dependency-management",
+                Some(&self.dependency_management_version),
+            );
+        }
+        result += "}\n\n";
+
+        let info = &pom_xml.project_info;
+        result += &format!("group = {}\n", self.quote(&info.group_id));
+        result += &format!("version = {}\n", self.quote(info.version_or_default()));
+        result += &format!("description = {}\n\n", self.quote(&info.description));
+
+        if !pom_xml.java.is_empty() {
+            result += "java {\n    toolchain {\n";
+            result += &format!(
+                "        languageVersion = JavaLanguageVersion.of({})\n",
+                pom_xml.java
+            );
+            result += "    }\n}\n\n";
+        }
+
+        //lombok and the other annotation processors have to be visible at compile time
+        result += "configurations {\n    compileOnly {\n";
+        result += match self.dsl {
+            GradleDsl::Kotlin => "        extendsFrom(configurations.annotationProcessor.get())\n",
+            GradleDsl::Groovy => "        extendsFrom annotationProcessor\n",
+        };
+        result += "    }\n}\n\n";
+
+        result += "repositories {\n    mavenCentral()\n}\n\n";
+
+        result += "dependencies {\n";
+        for bom in pom_xml
+            .dependency_management
+            .iter()
+            .filter(|l| l.scope == Some(Scope::Import))
+        {
+            result += &self.platform(bom);
+        }
+        for lib in pom_xml.dependencies.iter() {
+            for configuration in configurations_for(lib) {
+                result += &self.dependency(configuration, lib);
+            }
+        }
+        if pom_xml.has_dependency_that(|d| d.artifact_id == "spring-boot-starter-test") {
+            result += &self.dependency(
+                "testRuntimeOnly",
+                &Library::new(
+                    "org.junit.platform".to_owned(),
+                    "junit-platform-launcher".to_owned(),
+                ),
+            );
+        }
+        result += "}\n\n";
+
+        result += match self.dsl {
+            GradleDsl::Kotlin => "tasks.withType<Test> {\n    useJUnitPlatform()\n}\n",
+            GradleDsl::Groovy => "tasks.named('test') {\n    useJUnitPlatform()\n}\n",
+        };
+        //only the executable jar of bootJar ends up in build/libs, not the -plain one
+        if pom_xml.spring_boot_version().is_some() {
+            result += match self.dsl {
+                GradleDsl::Kotlin => "\ntasks.named<Jar>(\"jar\") {\n    enabled = false\n}\n",
+                GradleDsl::Groovy => "\ntasks.named('jar') {\n    enabled = false\n}\n",
+            };
+        }
+        result
+    }
+
+    pub fn settings_script(&self, pom_xml: &PomXml) -> String {
+        format!(
+            "rootProject.name = {}\n",
+            self.quote(&pom_xml.project_info.artifact_id)
+        )
+    }
+
+    pub fn wrapper_properties(&self) -> String {
+        let mut result = "distributionBase=GRADLE_USER_HOME\n".to_owned();
+        result += "distributionPath=wrapper/dists\n";
+        result += &format!(
+            "distributionUrl=https\\://services.gradle.org/distributions/gradle-{}-bin.zip\n",
+            self.gradle_version
+        );
+        result += "networkTimeout=10000\n";
+        result += "validateDistributionUrl=true\n";
+        result += "zipStoreBase=GRADLE_USER_HOME\n";
+        result += "zipStorePath=wrapper/dists\n";
+        result
+    }
+}
+
+//maven only has scopes, gradle needs to know how the library is used
+fn configurations_for(lib: &Library) -> Vec<&'static str> {
+    match lib.artifact_id.as_str() {
+        "lombok" => vec!["compileOnly", "annotationProcessor"],
+        "spring-boot-configuration-processor"
+        | "mapstruct-processor"
+        | "lombok-mapstruct-binding" => vec!["annotationProcessor"],
+        "spring-boot-devtools" | "spring-boot-docker-compose" => vec!["developmentOnly"],
+        _ => match lib.scope {
+            Some(Scope::Test) => vec!["testImplementation"],
+            Some(Scope::Runtime) => vec!["runtimeOnly"],
+            Some(Scope::Provided) => vec!["compileOnly"],
+            _ => vec!["implementation"],
+        },
+    }
+}
@@ -0,0 +1,108 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::classes::JavaClass;
+
+use super::{
+    crud_builder::CrudOptions,
+    exception_handling::ErrorHandling,
+    gradle::{GradleBuild, GradleDsl},
+    maven_builder::{BuildSystem, MavenCodebase},
+    output::OutputConfig,
+    pom_xml::PomXml,
+    relationships::Relationship,
+    validation::ValidationMode,
+};
+
+//same codebase as MavenCodebase, the pom.xml is replaced by the gradle build files
+//dependencies are still declared on the PomXml
+pub struct GradleCodebase {
+    codebase: MavenCodebase,
+    gradle: GradleBuild,
+}
+
+impl GradleCodebase {
+    pub fn new(pom_xml: PomXml, output_dir: &str) -> Self {
+        let gradle = GradleBuild::default();
+        Self {
+            codebase: MavenCodebase::new(pom_xml, output_dir)
+                .build_system(BuildSystem::Gradle(gradle.clone())),
+            gradle,
+        }
+    }
+
+    fn with_gradle(mut self, gradle: GradleBuild) -> Self {
+        self.codebase = self
+            .codebase
+            .build_system(BuildSystem::Gradle(gradle.clone()));
+        self.gradle = gradle;
+        self
+    }
+
+    pub fn dsl(self, dsl: GradleDsl) -> Self {
+        let gradle = GradleBuild {
+            dsl,
+            ..self.gradle.clone()
+        };
+        self.with_gradle(gradle)
+    }
+
+    pub fn gradle_version(self, version: String) -> Self {
+        let gradle = self.gradle.clone().gradle_version(version);
+        self.with_gradle(gradle)
+    }
+
+    pub fn add_entity(mut self, jclass: JavaClass) -> Self {
+        self.codebase = self.codebase.add_entity(jclass);
+        self
+    }
+
+    pub fn add_entity_with(mut self, jclass: JavaClass, options: CrudOptions) -> Self {
+        self.codebase = self.codebase.add_entity_with(jclass, options);
+        self
+    }
+
+    pub fn add_entities(mut self, jclasses: Vec<JavaClass>) -> Self {
+        self.codebase = self.codebase.add_entities(jclasses);
+        self
+    }
+
+    pub fn relationship(mut self, relationship: Relationship) -> Self {
+        self.codebase = self.codebase.relationship(relationship);
+        self
+    }
+
+    pub fn output(mut self, config: OutputConfig) -> Self {
+        self.codebase = self.codebase.output(config);
+        self
+    }
+
+    pub fn error_handling(mut self, config: ErrorHandling) -> Self {
+        self.codebase = self.codebase.error_handling(config);
+        self
+    }
+
+    pub fn validation(mut self, mode: ValidationMode) -> Self {
+        self.codebase = self.codebase.validation(mode);
+        self
+    }
+
+    //for the MavenCodebase options that are not repeated here
+    pub fn configure(mut self, f: impl FnOnce(MavenCodebase) -> MavenCodebase) -> Self {
+        self.codebase = f(self.codebase);
+        self
+    }
+}
+
+impl Deref for GradleCodebase {
+    type Target = MavenCodebase;
+
+    fn deref(&self) -> &Self::Target {
+        &self.codebase
+    }
+}
+
+impl DerefMut for GradleCodebase {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.codebase
+    }
+}
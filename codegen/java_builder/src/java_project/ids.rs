@@ -0,0 +1,218 @@
+//how the entities are identified: the type of the id and who hands it out. a Long
+//numbered by the database unless chosen otherwise
+use crate::{annotations::Annotation, classes::JavaClass, fields::Field, imports::Import};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdType {
+    Long,
+    Uuid,
+    String,
+    //an @EmbeddedId, the key class gets the (name, type) fields and the path of an entity
+    //one segment per field. relationships to such an entity are not mapped
+    Composite(String, Vec<(String, String)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdGeneration {
+    //an auto increment column
+    Identity,
+    //numbers handed out by a database sequence, allocation_size at a time
+    Sequence {
+        generator: String,
+        allocation_size: u32,
+    },
+    //a random uuid set by hibernate before the insert
+    Uuid,
+    //the request carries the id
+    Assigned,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdStrategy {
+    pub type_: IdType,
+    pub generation: IdGeneration,
+}
+
+impl Default for IdStrategy {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+fn persistence(name: &str) -> Import {
+    Import::new("jakarta.persistence".into(), name.into())
+}
+
+impl IdStrategy {
+    //a generation the type can not have falls back to assigned ids
+    pub fn new(type_: IdType, generation: IdGeneration) -> Self {
+        let supported = matches!(
+            (&type_, &generation),
+            (_, IdGeneration::Assigned)
+                | (
+                    IdType::Long,
+                    IdGeneration::Identity | IdGeneration::Sequence { .. }
+                )
+                | (IdType::Uuid | IdType::String, IdGeneration::Uuid)
+        );
+        if !supported {
+            log::warn!(
+                "{:?} ids can not be generated by {:?}, they are assigned",
+                type_,
+                generation
+            );
+        }
+        Self {
+            type_,
+            generation: match supported {
+                true => generation,
+                false => IdGeneration::Assigned,
+            },
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(IdType::Long, IdGeneration::Identity)
+    }
+
+    //the sequence is named like the generator
+    pub fn sequence(generator: String) -> Self {
+        Self::new(
+            IdType::Long,
+            IdGeneration::Sequence {
+                generator,
+                allocation_size: 50,
+            },
+        )
+    }
+
+    pub fn uuid() -> Self {
+        Self::new(IdType::Uuid, IdGeneration::Uuid)
+    }
+
+    pub fn assigned(type_: IdType) -> Self {
+        Self::new(type_, IdGeneration::Assigned)
+    }
+
+    pub fn composite(class_name: String, fields: Vec<(String, String)>) -> Self {
+        Self::assigned(IdType::Composite(class_name, fields))
+    }
+
+    pub fn allocation_size(mut self, size: u32) -> Self {
+        if let IdGeneration::Sequence {
+            ref mut allocation_size,
+            ..
+        } = self.generation
+        {
+            *allocation_size = size;
+        }
+        self
+    }
+
+    //what repositories and path variables use
+    pub fn java_type(&self) -> String {
+        match &self.type_ {
+            IdType::Long => "Long".to_owned(),
+            IdType::Uuid => "UUID".to_owned(),
+            IdType::String => "String".to_owned(),
+            IdType::Composite(class_name, _) => class_name.clone(),
+        }
+    }
+
+    pub fn is_assigned(&self) -> bool {
+        self.generation == IdGeneration::Assigned
+    }
+
+    //the fields of a composite key, empty for the others
+    pub fn key_fields(&self) -> Vec<Field> {
+        match &self.type_ {
+            IdType::Composite(_, fields) => {
+                let mut fields: Vec<Field> = fields
+                    .iter()
+                    .map(|(name, type_)| Field::n(name.clone(), type_.as_str().into()))
+                    .collect();
+                fields.sort_by(|a, b| a.name.cmp(&b.name));
+                fields
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        let field = Field::n("id".into(), self.java_type().into());
+        if let IdType::Composite(..) = self.type_ {
+            return field.annotation("EmbeddedId".into());
+        }
+        let field = field.annotation("Id".into());
+        let generated_value = Annotation::new("GeneratedValue".into());
+        match &self.generation {
+            IdGeneration::Identity => field.annotation(
+                generated_value.param("strategy".into(), "GenerationType.IDENTITY".into()),
+            ),
+            IdGeneration::Sequence {
+                generator,
+                allocation_size,
+            } => field
+                .annotation(
+                    generated_value
+                        .param("strategy".into(), "GenerationType.SEQUENCE".into())
+                        .param("generator".into(), format!("\"{}\"", generator)),
+                )
+                .annotation(
+                    Annotation::new("SequenceGenerator".into())
+                        .param("name".into(), format!("\"{}\"", generator))
+                        .param("sequenceName".into(), format!("\"{}\"", generator))
+                        .param("allocationSize".into(), allocation_size.to_string()),
+                ),
+            IdGeneration::Uuid => field
+                .annotation(generated_value.param("strategy".into(), "GenerationType.UUID".into())),
+            IdGeneration::Assigned => field,
+        }
+    }
+
+    //what the entity needs for its id field
+    pub fn imports(&self) -> Vec<Import> {
+        let mut imports = match &self.generation {
+            _ if matches!(self.type_, IdType::Composite(..)) => vec![persistence("EmbeddedId")],
+            IdGeneration::Assigned => vec![persistence("Id")],
+            IdGeneration::Sequence { .. } => vec![
+                persistence("GeneratedValue"),
+                persistence("GenerationType"),
+                persistence("Id"),
+                persistence("SequenceGenerator"),
+            ],
+            _ => vec![
+                persistence("GeneratedValue"),
+                persistence("GenerationType"),
+                persistence("Id"),
+            ],
+        };
+        if self.type_ == IdType::Uuid {
+            imports.push(Import::new("java.util".into(), "UUID".into()));
+        }
+        imports
+    }
+
+    //the @Embeddable of a composite key, next to the entity
+    pub fn key_class(&self, package: String) -> Option<JavaClass> {
+        let IdType::Composite(ref class_name, _) = self.type_ else {
+            return None;
+        };
+        let mut key = JavaClass::new(class_name.clone(), package)
+            .public()
+            .implements("Serializable".into())
+            .import(Import::new("java.io".into(), "Serializable".into()))
+            .import(persistence("Embeddable"))
+            .import(Import::new("lombok".into(), "AllArgsConstructor".into()))
+            .import(Import::new("lombok".into(), "Data".into()))
+            .import(Import::new("lombok".into(), "NoArgsConstructor".into()))
+            .annotation("Embeddable".into())
+            .annotation("Data".into())
+            .annotation("NoArgsConstructor".into())
+            .annotation("AllArgsConstructor".into());
+        for field in self.key_fields() {
+            key = key.field(field);
+        }
+        Some(key)
+    }
+}
@@ -0,0 +1,368 @@
+//checks the dependencies of a pom against a local maven repository, without maven and
+//without network access. parents, properties and imported boms are followed far enough to
+//know which version every dependency ends up with
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use super::pom_xml::{Library, PomXml, Scope};
+use crate::java_structs::diagnostics::Diagnostic;
+
+//parents and boms nest a few levels at most, this only guards against cycles
+const MAX_PARENT_DEPTH: usize = 16;
+
+pub struct LocalRepository {
+    root: PathBuf,
+    //parsed poms by groupId:artifactId:version, None when the file is missing or broken
+    poms: RefCell<HashMap<String, Option<PomXml>>>,
+}
+
+//what maven would see after inheritance and bom imports
+#[derive(Default, Clone)]
+struct EffectiveModel {
+    properties: HashMap<String, String>,
+    //groupId:artifactId -> version
+    managed: HashMap<String, String>,
+    dependencies: Vec<Library>,
+}
+
+fn key(group_id: &str, artifact_id: &str) -> String {
+    format!("{}:{}", group_id, artifact_id)
+}
+
+//replaces ${...} with the properties it knows about, the rest is left alone
+fn interpolate(value: &str, properties: &HashMap<String, String>) -> String {
+    let mut result = value.to_owned();
+    for _ in 0..MAX_PARENT_DEPTH {
+        let Some(start) = result.find("${") else {
+            break;
+        };
+        let Some(len) = result[start..].find('}') else {
+            break;
+        };
+        let name = &result[start + 2..start + len];
+        let Some(replacement) = properties.get(name) else {
+            break;
+        };
+        result = format!(
+            "{}{}{}",
+            &result[..start],
+            replacement,
+            &result[start + len + 1..]
+        );
+    }
+    result
+}
+
+impl LocalRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            poms: RefCell::new(HashMap::new()),
+        }
+    }
+
+    //~/.m2/repository
+    pub fn user_default() -> Option<Self> {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+        Some(Self::new(Path::new(&home).join(".m2").join("repository")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn artifact_dir(&self, group_id: &str, artifact_id: &str) -> PathBuf {
+        let mut dir = self.root.clone();
+        dir.extend(group_id.split('.'));
+        dir.push(artifact_id);
+        dir
+    }
+
+    pub fn pom_path(&self, group_id: &str, artifact_id: &str, version: &str) -> PathBuf {
+        self.artifact_dir(group_id, artifact_id)
+            .join(version)
+            .join(format!("{}-{}.pom", artifact_id, version))
+    }
+
+    fn load(&self, group_id: &str, artifact_id: &str, version: &str) -> Option<PomXml> {
+        let coordinates = format!("{}:{}:{}", group_id, artifact_id, version);
+        if let Some(cached) = self.poms.borrow().get(&coordinates) {
+            return cached.clone();
+        }
+        let pom = std::fs::read_to_string(self.pom_path(group_id, artifact_id, version))
+            .ok()
+            .and_then(|xml| PomXml::parse(&xml).ok());
+        self.poms.borrow_mut().insert(coordinates, pom.clone());
+        pom
+    }
+
+    fn effective_model(&self, pom_xml: &PomXml, depth: usize) -> EffectiveModel {
+        let parent = &pom_xml.parent_pom;
+        let mut model = match parent.version() {
+            Some(version) if depth < MAX_PARENT_DEPTH && !parent.artifact_id.is_empty() => self
+                .load(parent.group_id(), &parent.artifact_id, version)
+                .map(|p| self.effective_model(&p, depth + 1))
+                .unwrap_or_default(),
+            _ => EffectiveModel::default(),
+        };
+        let info = &pom_xml.project_info;
+        for (name, value) in [
+            ("project.groupId", &info.group_id),
+            ("project.artifactId", &info.artifact_id),
+            ("project.version", &info.version),
+        ] {
+            model.properties.insert(name.to_owned(), value.clone());
+        }
+        if let Some(v) = parent.version() {
+            model
+                .properties
+                .insert("project.parent.version".to_owned(), v.to_owned());
+        }
+        if !pom_xml.java.is_empty() {
+            model
+                .properties
+                .insert("java.version".to_owned(), pom_xml.java.clone());
+        }
+        for (name, value) in pom_xml.properties.iter() {
+            model.properties.insert(name.clone(), value.clone());
+        }
+
+        for lib in pom_xml.dependency_management.iter() {
+            let Some(version) = lib.version().map(|v| interpolate(v, &model.properties)) else {
+                continue;
+            };
+            let group_id = interpolate(lib.group_id(), &model.properties);
+            if lib.scope == Some(Scope::Import) {
+                if depth >= MAX_PARENT_DEPTH {
+                    continue;
+                }
+                if let Some(bom) = self.load(&group_id, &lib.artifact_id, &version) {
+                    //entries declared before the import win
+                    for (k, v) in self.effective_model(&bom, depth + 1).managed {
+                        model.managed.entry(k).or_insert(v);
+                    }
+                }
+            } else {
+                model
+                    .managed
+                    .insert(key(&group_id, &lib.artifact_id), version);
+            }
+        }
+        model
+            .dependencies
+            .extend(pom_xml.dependencies.iter().cloned());
+        model
+    }
+
+    fn version_in(model: &EffectiveModel, lib: &Library) -> Option<String> {
+        let group_id = interpolate(lib.group_id(), &model.properties);
+        lib.version()
+            .map(|v| interpolate(v, &model.properties))
+            .or_else(|| {
+                model
+                    .managed
+                    .get(&key(&group_id, &lib.artifact_id))
+                    .cloned()
+            })
+            .filter(|v| !v.contains("${"))
+    }
+
+    //missing directories or poms are errors, maven would not resolve the project offline
+    pub fn check(&self, pom_xml: &PomXml) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let parent = &pom_xml.parent_pom;
+        if let Some(version) = parent.version() {
+            if !parent.artifact_id.is_empty() {
+                self.check_exists(
+                    parent.group_id(),
+                    &parent.artifact_id,
+                    version,
+                    &mut diagnostics,
+                );
+            }
+        }
+        let model = self.effective_model(pom_xml, 0);
+
+        let mut direct = vec![];
+        for lib in pom_xml.dependencies.iter() {
+            let element = key(lib.group_id(), &lib.artifact_id);
+            match Self::version_in(&model, lib) {
+                None => diagnostics.push(Diagnostic::error(
+                    "unresolved-version",
+                    element,
+                    "Neither the pom nor its parents and boms declare a version".to_owned(),
+                )),
+                Some(version) => {
+                    if self.check_exists(
+                        lib.group_id(),
+                        &lib.artifact_id,
+                        &version,
+                        &mut diagnostics,
+                    ) {
+                        direct.push((lib, version));
+                    }
+                }
+            }
+        }
+        self.check_transitive(&model, &direct, &mut diagnostics);
+        diagnostics
+    }
+
+    fn check_exists(
+        &self,
+        group_id: &str,
+        artifact_id: &str,
+        version: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> bool {
+        let element = format!("{}:{}:{}", group_id, artifact_id, version);
+        let mut group_dir = self.root.clone();
+        group_dir.extend(group_id.split('.'));
+        let artifact_dir = self.artifact_dir(group_id, artifact_id);
+        let pom = self.pom_path(group_id, artifact_id, version);
+        let missing = if !group_dir.is_dir() {
+            Some(group_dir)
+        } else if !artifact_dir.is_dir() {
+            Some(artifact_dir)
+        } else if !artifact_dir.join(version).is_dir() {
+            Some(artifact_dir.join(version))
+        } else if !pom.is_file() {
+            Some(pom)
+        } else {
+            None
+        };
+        match missing {
+            Some(path) => {
+                diagnostics.push(Diagnostic::error(
+                    "missing-artifact",
+                    element,
+                    format!("{} does not exist", path.display()),
+                ));
+                false
+            }
+            None => true,
+        }
+    }
+
+    //walks the compile and runtime dependencies breadth first, the way maven mediates them
+    fn check_transitive(
+        &self,
+        root: &EffectiveModel,
+        direct: &[(&Library, String)],
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let declared: HashSet<String> = direct
+            .iter()
+            .map(|(l, _)| key(l.group_id(), &l.artifact_id))
+            .collect();
+        //groupId:artifactId -> (version, the direct dependency that pulls it in)
+        let mut found: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<(Library, String, String, Vec<String>)> = direct
+            .iter()
+            .map(|(lib, version)| {
+                let excluded = lib
+                    .exclusions
+                    .iter()
+                    .map(|e| key(&e.group_id, &e.artifact_id))
+                    .collect();
+                (
+                    (*lib).clone(),
+                    version.clone(),
+                    key(lib.group_id(), &lib.artifact_id),
+                    excluded,
+                )
+            })
+            .collect();
+
+        while let Some((lib, version, via, excluded)) = queue.pop_front() {
+            if !visited.insert(format!(
+                "{}:{}:{}",
+                lib.group_id(),
+                lib.artifact_id,
+                version
+            )) {
+                continue;
+            }
+            let Some(pom) = self.load(lib.group_id(), &lib.artifact_id, &version) else {
+                continue;
+            };
+            let model = self.effective_model(&pom, 0);
+            for dependency in model.dependencies.iter() {
+                let transitive = matches!(
+                    dependency.scope,
+                    None | Some(Scope::Compile) | Some(Scope::Runtime)
+                );
+                let element = key(
+                    &interpolate(dependency.group_id(), &model.properties),
+                    &dependency.artifact_id,
+                );
+                if !transitive || dependency.optional || excluded.contains(&element) {
+                    continue;
+                }
+                //the dependencyManagement of the project wins over what the library asks for
+                let version = root
+                    .managed
+                    .get(&element)
+                    .cloned()
+                    .or_else(|| Self::version_in(&model, dependency));
+                let Some(version) = version else {
+                    diagnostics.push(Diagnostic::warning(
+                        "unresolved-version",
+                        element,
+                        format!("The version required by {} could not be resolved", via),
+                    ));
+                    continue;
+                };
+                let (group_id, artifact_id) = element.split_once(':').unwrap_or_default();
+                if !self.check_exists(group_id, artifact_id, &version, diagnostics) {
+                    continue;
+                }
+                let versions = found.entry(element.clone()).or_default();
+                if !versions.iter().any(|(v, _)| *v == version) {
+                    versions.push((version.clone(), via.clone()));
+                }
+                let mut excluded = excluded.clone();
+                excluded.extend(
+                    dependency
+                        .exclusions
+                        .iter()
+                        .map(|e| key(&e.group_id, &e.artifact_id)),
+                );
+                queue.push_back((
+                    Library::new(group_id.to_owned(), artifact_id.to_owned()),
+                    version,
+                    via.clone(),
+                    excluded,
+                ));
+            }
+        }
+
+        let mut conflicts: Vec<_> = found
+            .into_iter()
+            .filter(|(element, versions)| {
+                versions.len() > 1
+                    && !declared.contains(element)
+                    && !root.managed.contains_key(element)
+            })
+            .collect();
+        conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+        for (element, versions) in conflicts {
+            let described: Vec<String> = versions
+                .iter()
+                .map(|(v, via)| format!("{} via {}", v, via))
+                .collect();
+            diagnostics.push(Diagnostic::warning(
+                "transitive-conflict",
+                element,
+                format!(
+                    "Required in different versions: {}, maven picks the nearest one",
+                    described.join(", ")
+                ),
+            ));
+        }
+    }
+}
@@ -1,29 +1,40 @@
 use crate::java_structs::*;
 
 use classes::JavaClass;
+use diagnostics::Diagnostic;
+use fields::Field;
 use imports::Import;
 use interfaces::Interface;
+use lint::Linter;
 use methods::Method;
 use std::{
-    collections::HashMap,
-    fs::{remove_dir_all, write},
+    fs::{read_to_string, remove_dir_all},
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
-use types::TypeName;
+use types::{GenericParams, TypeName};
 
 use super::{
-    crud_builder::CrudBuilder,
-    output::OutputDirs,
-    pom_xml::{Generate, PomXml},
+    catalog::{self, compare_versions, Catalog},
+    ci::CiConfig,
+    config::{AppConfig, ConfigFormat, SpringProfile},
+    crud_builder::{CrudBuilder, CrudOptions, MapperStyle},
+    database::DatabaseKind,
+    docker::{ContainerTarget, DockerConfig},
+    error::CodegenError,
+    exception_handling::ErrorHandling,
+    gradle::GradleBuild,
+    local_repository::LocalRepository,
+    migrations::{MigrationTool, Schema},
+    modules::ModuleLayout,
+    output::{write_file, CompilationUnit, Layer, OutputConfig, OutputDirs},
+    pom_xml::{Generate, Library, PomXml, Scope},
+    relationships::Relationship,
+    security::{AuthMode, SecurityConfig},
+    test_suites::TestSuite,
+    validation::{ValidationMode, ValidationReport},
 };
 
-//TODO Do security in spring boot
-//All paths need an auth except /login
-//Also need an admin thingie
-
-//TODO dockerise application(docker-compose and cloud)
-//TODO Github Actions for uploading to container registry
 //TODO Polish front facing API and upload to crates.io
 
 struct Progress {
@@ -34,22 +45,34 @@ struct Progress {
 
 #[derive(Clone)]
 pub struct DBInfo {
+    pub kind: DatabaseKind,
     pub db_port: u16,
     pub username: String,
     pub password: String,
     pub db: String,
 }
 
-fn create_db_info(pom_xml: &PomXml) -> DBInfo {
-    DBInfo {
-        db_port: 5432,
-        username: pom_xml.project_info.name.to_ascii_lowercase(),
-        password: pom_xml.project_info.name.to_ascii_lowercase(),
-        db: pom_xml.project_info.name.to_ascii_lowercase() + "_db",
+impl DBInfo {
+    //how the app reaches the database from the host
+    pub fn jdbc_url(&self, host: &str) -> String {
+        self.kind.jdbc_url(host, self.db_port, &self.db)
     }
 }
 
-//TODO make an OutputConfig option that contains the folders and the package names and everything
+//postgresql when the pom has no driver yet
+fn create_db_info(pom_xml: &PomXml) -> DBInfo {
+    DatabaseKind::detect(pom_xml)
+        .unwrap_or_default()
+        .db_info(&pom_xml.project_info.name)
+}
+
+//the files that describe how the project is built, the sources are laid out the same way
+#[derive(Clone, Default)]
+pub enum BuildSystem {
+    #[default]
+    Maven,
+    Gradle(GradleBuild),
+}
 
 pub struct MavenCodebase {
     port: u16,
@@ -57,12 +80,44 @@ pub struct MavenCodebase {
     root_folder: PathBuf,
     pom_xml: PomXml,
     out_dirs: OutputDirs,
+    //package names, class suffixes and packaging by layer or by feature
+    output: OutputConfig,
     entities: Vec<JavaClass>,
     controller_classes: Vec<JavaClass>,
     dto_classes: Vec<JavaClass>,
     services: Vec<JavaClass>,
     jpa_repos: Vec<Interface>,
     progress: Progress,
+    validation: ValidationMode,
+    build_system: BuildSystem,
+    //None for a single module project
+    modules: Option<ModuleLayout>,
+    //opened from a folder that already has a pom.xml, its main class and properties are kept
+    existing_project: bool,
+    //used to check the dependencies and fill in missing versions
+    catalog: Catalog,
+    //when set the dependencies are looked up in a local maven repository before writing
+    local_repository: Option<LocalRepository>,
+    //spring security is only generated when asked for
+    security: Option<SecurityConfig>,
+    //the exception handler and the not found exception, controllers answer 404 themselves without
+    error_handling: Option<ErrorHandling>,
+    //Dockerfile and compose.yaml are only written when set
+    docker: Option<DockerConfig>,
+    //github actions or gitlab pipelines, only written when set
+    ci: Option<CiConfig>,
+    //the schema is created by versioned migrations instead of ddl-auto when set
+    migrations: Option<MigrationTool>,
+    config_format: ConfigFormat,
+    //one configuration file per profile, a single one when empty
+    profiles: Vec<SpringProfile>,
+    //keys the user set, None for the shared file
+    custom_config: Vec<(Option<SpringProfile>, String, String)>,
+    //the classes and options given to add_entity, kept to generate them again
+    crud_inputs: Vec<(JavaClass, CrudOptions)>,
+    relationships: Vec<Relationship>,
+    //junit tests for the crud classes of every entity, written unless turned off
+    tests: bool,
 }
 
 impl MavenCodebase {
@@ -76,51 +131,73 @@ impl MavenCodebase {
     pub fn get_db_port(&self) -> u16 {
         self.db_info.db_port
     }
-    fn create_initial_folders(&mut self) {
-        if !self.progress.has_created_initial_folders {
-            self.progress.has_created_initial_folders = true;
-        } else {
-            return;
-        }
 
-        self.out_dirs.create_folders();
+    fn has_database(&self) -> bool {
+        DatabaseKind::detect(&self.pom_xml).is_some()
     }
+    fn create_initial_folders(&mut self) -> Result<(), CodegenError> {
+        if self.progress.has_created_initial_folders {
+            return Ok(());
+        }
 
-    pub fn create_application_properties(&mut self) -> String {
-        let mut app: HashMap<&str, &str> = HashMap::new();
-        app.insert("spring.application.version", "0.0.1");
-        app.insert("spring.config.validate", "true");
-        app.insert("server.address", "localhost");
-        let binding = self.port.to_string();
-        app.insert("server.port", &binding);
-
-        let mut db_url = "".to_string();
-        if self
-            .pom_xml
-            .has_dependency_that(|d| d.artifact_id.contains("postgresql"))
-        {
-            db_url = "jdbc:postgresql://localhost:".to_owned()
-                + &self.db_info.db_port.clone().to_string()
-                + "/"
-                + &self.db_info.db;
-            app.insert("spring.datasource.url", &db_url);
+        match self.modules {
+            None => self.out_dirs.create_folders()?,
+            Some(ref layout) => {
+                for module in layout.modules() {
+                    if *module == layout.web {
+                        self.out_dirs.in_module(module).create_folders()?;
+                    } else {
+                        self.out_dirs.in_module(module).create_source_folders()?;
+                    }
+                }
+            }
+        }
+        self.progress.has_created_initial_folders = true;
+        Ok(())
+    }
 
-            app.insert("spring.datasource.username", &self.db_info.username);
+    //the connection to the database the project was generated for
+    fn datasource_config(&self) -> AppConfig {
+        let mut app = AppConfig::new();
+        app.set("spring.datasource.url", &self.db_info.jdbc_url("localhost"));
+        app.set("spring.datasource.username", &self.db_info.username);
+        app.set("spring.datasource.password", &self.db_info.password);
+        app.set(
+            "spring.datasource.driver-class-name",
+            self.db_info.kind.driver_class(),
+        );
+        app.set("spring.jpa.database-platform", self.db_info.kind.dialect());
+        app
+    }
 
-            app.insert("spring.datasource.password", &self.db_info.password);
+    fn custom_config_for(&self, profile: Option<SpringProfile>) -> AppConfig {
+        let mut app = AppConfig::new();
+        for (_, key, value) in self.custom_config.iter().filter(|(p, _, _)| *p == profile) {
+            app.set(key, value);
+        }
+        app
+    }
 
-            app.insert(
-                "spring.datasource.driver-class-name",
-                "org.postgresql.Driver",
-            );
-            app.insert(
-                "spring.jpa.database-platform",
-                "org.hibernate.dialect.PostgreSQLDialect",
-            );
+    //the configuration every profile shares
+    pub fn application_config(&self) -> AppConfig {
+        let mut app = AppConfig::new();
+        app.set("spring.application.version", "0.0.1");
+        app.set("spring.config.validate", "true");
+        app.set("server.address", "localhost");
+        app.set("server.port", &self.port.to_string());
 
-            app.insert("spring.jpa.hibernate.ddl-auto", "update");
-            app.insert("spring.jpa.show-sql", "true");
-            app.insert("spring.jpa.properties.hibernate.format_sql", "true");
+        if self.has_database() {
+            //the profiles bring their own database
+            if self.profiles.is_empty() {
+                app.merge(&self.datasource_config());
+                app.set("spring.jpa.show-sql", "true");
+            }
+            let ddl_auto = match self.migrations {
+                Some(_) => "validate",
+                None => "update",
+            };
+            app.set("spring.jpa.hibernate.ddl-auto", ddl_auto);
+            app.set("spring.jpa.properties.hibernate.format_sql", "true");
 
             //optionally add Hikari Connection pool stuff
         }
@@ -129,65 +206,268 @@ impl MavenCodebase {
             .pom_xml
             .has_dependency_that(|d| d.artifact_id == "springdoc-openapi-starter-webmvc-api")
         {
-            app.insert("springdoc.api-docs.path", "/api-docs");
-            app.insert("springdoc.show-actuator", "true");
+            app.set("springdoc.api-docs.path", "/api-docs");
+            app.set("springdoc.show-actuator", "true");
         }
 
         if self
             .pom_xml
             .has_dependency_that(|d| d.artifact_id == "springdoc-openapi-starter-webmvc-ui")
         {
-            app.insert("springdoc.swagger-ui.path", "/docs.html");
-            app.insert("springdoc.show-actuator", "true");
+            app.set("springdoc.swagger-ui.path", "/docs.html");
+            app.set("springdoc.show-actuator", "true");
         }
 
-        app.into_iter()
-            .filter(|(_, v)| !v.is_empty())
-            .fold("".into(), |acc, (key, value)| {
-                acc + key + "=" + value + "\n"
-            })
+        if self.docker.is_some() && self.container_target().uses_docker_compose_support() {
+            app.set("spring.docker.compose.file", "compose.yaml");
+        }
+
+        let security_properties = self
+            .security
+            .as_ref()
+            .map(|s| s.properties())
+            .unwrap_or_default();
+        for (key, value) in security_properties.iter() {
+            app.set(key, value);
+        }
+        let migration_properties = self.migrations.map(|m| m.properties()).unwrap_or_default();
+        for (key, value) in migration_properties.iter() {
+            app.set(key, value);
+        }
+
+        //dev when nothing else is asked for
+        let default_profile = self
+            .profiles
+            .iter()
+            .find(|p| **p == SpringProfile::Dev)
+            .or(self.profiles.first());
+        if let Some(profile) = default_profile {
+            app.set("spring.profiles.default", profile.name());
+        }
+        app.merge(&self.custom_config_for(None));
+        app
     }
-    pub fn write_initial_files(&mut self) {
-        if self.progress.has_written_initial_files {
-            println!("Have already written initial_files, skipping");
-            return;
+
+    //what a profile changes on top of the shared configuration
+    pub fn profile_config(&self, profile: SpringProfile) -> AppConfig {
+        let mut app = AppConfig::new();
+        let kind = self.db_info.kind;
+        match profile {
+            _ if !self.has_database() => {}
+            SpringProfile::Dev => {
+                app.merge(&self.datasource_config());
+                app.set("spring.jpa.show-sql", "true");
+            }
+            SpringProfile::Test => {
+                let mode = kind
+                    .h2_mode()
+                    .map(|m| format!(";MODE={}", m))
+                    .unwrap_or_default();
+                app.set(
+                    "spring.datasource.url",
+                    &format!("jdbc:h2:mem:testdb{};DB_CLOSE_DELAY=-1", mode),
+                );
+                app.set("spring.datasource.username", "sa");
+                app.set("spring.datasource.password", "");
+                app.set(
+                    "spring.datasource.driver-class-name",
+                    DatabaseKind::H2.driver_class(),
+                );
+                app.set("spring.jpa.database-platform", DatabaseKind::H2.dialect());
+                if self.migrations.is_none() {
+                    app.set("spring.jpa.hibernate.ddl-auto", "create-drop");
+                }
+            }
+            SpringProfile::Prod => {
+                app.set("server.address", "0.0.0.0");
+                app.set("spring.datasource.url", "${DATABASE_URL}");
+                app.set("spring.datasource.username", "${DATABASE_USERNAME}");
+                app.set("spring.datasource.password", "${DATABASE_PASSWORD}");
+                app.set("spring.datasource.driver-class-name", kind.driver_class());
+                app.set("spring.jpa.database-platform", kind.dialect());
+                app.set("spring.jpa.hibernate.ddl-auto", "validate");
+                app.set("spring.jpa.show-sql", "false");
+            }
+        }
+        let security_properties = self
+            .security
+            .as_ref()
+            .map(|s| s.profile_properties(profile))
+            .unwrap_or_default();
+        for (key, value) in security_properties.iter() {
+            app.set(key, value);
+        }
+        app.merge(&self.custom_config_for(Some(profile)));
+        app
+    }
+
+    pub fn create_application_properties(&mut self) -> String {
+        self.application_config().render(self.config_format)
+    }
+
+    //the shared file and one per profile, relative to the resources folder
+    pub fn config_files(&self) -> Vec<(String, String)> {
+        let mut files = vec![(
+            self.config_format.file_name(None),
+            self.application_config().render(self.config_format),
+        )];
+        for profile in self.profiles.iter() {
+            files.push((
+                self.config_format.file_name(Some(*profile)),
+                self.profile_config(*profile).render(self.config_format),
+            ));
         }
+        files
+    }
 
-        if !self.progress.has_created_initial_folders {
-            self.create_initial_folders();
+    pub fn write_initial_files(&mut self) -> Result<(), CodegenError> {
+        if self.progress.has_written_initial_files {
+            log::debug!("Have already written initial_files, skipping");
+            return Ok(());
         }
 
-        let entrypoint = self.create_spring_main_class();
-        let mut main = self.out_dirs.code_folder().clone();
-        main.push(&entrypoint.class_name);
-        main.set_extension("java");
-        let mainfile = write(main, (&entrypoint).generate_code());
+        self.create_initial_folders()?;
 
-        if let Err(e) = mainfile {
-            assert!(false, "Main file could not be written err {}", e);
+        if !self.existing_project {
+            self.out_dirs.write_units(&[self.main_class_unit()])?;
         }
 
-        let mut pom_path = PathBuf::from(self.root_folder.as_path());
-        pom_path.push("pom");
-        pom_path.set_extension("xml");
-        if let Err(e) = write(&pom_path, &self.pom_xml.generate()) {
-            assert!(false, "pom.xml file could not be written err {}", e);
+        self.write_build_files()?;
+        let resources = self.dirs_for(Layer::Application).resources_folder();
+        let has_config = [ConfigFormat::Properties, ConfigFormat::Yaml]
+            .iter()
+            .any(|f| resources.join(f.file_name(None)).exists());
+        if self.existing_project && has_config {
+            self.progress.has_created_application_properties = true;
         }
         if !self.progress.has_created_application_properties {
-            let app_properties = self.create_application_properties();
-            let mut properties_location = self.out_dirs.resources_folder().clone();
-            properties_location.push("application.properties");
-            match write(properties_location, app_properties) {
-                Ok(r) => println!("Succesfully wrote application.properties"),
-                Err(e) => assert!(
-                    false,
-                    "Could not write spring application.properties file err: {e}"
-                ),
+            for (name, content) in self.config_files() {
+                write_file(&resources.join(&name), &content)?;
+                log::debug!("Succesfully wrote {}", name);
             }
         }
 
         self.progress.has_created_application_properties = true;
+        self.write_container_files()?;
+        self.write_ci_files()?;
         self.progress.has_written_initial_files = true;
+        Ok(())
+    }
+
+    pub fn container_target(&self) -> ContainerTarget<'_> {
+        ContainerTarget {
+            pom_xml: &self.pom_xml,
+            build_system: &self.build_system,
+            modules: self.modules.as_ref(),
+            port: self.port,
+            db_info: self.has_database().then_some(&self.db_info),
+        }
+    }
+
+    fn write_container_files(&self) -> Result<(), CodegenError> {
+        let Some(ref docker) = self.docker else {
+            return Ok(());
+        };
+        let target = self.container_target();
+        self.write_project_files(vec![
+            ("Dockerfile".to_owned(), docker.dockerfile(&target)),
+            (".dockerignore".to_owned(), docker.dockerignore()),
+            ("compose.yaml".to_owned(), docker.compose(&target)),
+        ])
+    }
+
+    fn write_ci_files(&self) -> Result<(), CodegenError> {
+        let Some(ref ci) = self.ci else {
+            return Ok(());
+        };
+        self.write_project_files(ci.files(&self.container_target(), self.docker.as_ref()))
+    }
+
+    //paths are relative to the project root, files an existing project already has are left alone
+    fn write_project_files(&self, files: Vec<(String, String)>) -> Result<(), CodegenError> {
+        for (name, content) in files {
+            let path = self.root_folder.join(name);
+            if self.existing_project && path.exists() {
+                continue;
+            }
+            write_file(&path, &content)?;
+        }
+        Ok(())
+    }
+
+    //the entities as they were at the last generation, kept next to the build files
+    fn schema_snapshot_path(&self) -> PathBuf {
+        self.root_folder
+            .join(".codegen")
+            .join("schema-snapshot.json")
+    }
+
+    fn write_migrations(&self) -> Result<(), CodegenError> {
+        let Some(tool) = self.migrations else {
+            return Ok(());
+        };
+        if !self.has_database() {
+            log::warn!(
+                "{:?} migrations need a database driver, none were written",
+                tool
+            );
+            return Ok(());
+        }
+        let resources = self.dirs_for(Layer::Application).resources_folder();
+        let snapshot = self.schema_snapshot_path();
+        if !snapshot.exists() && tool.has_unknown_migrations(&resources) {
+            log::warn!(
+                "{} is missing, not adding a migration next to the existing ones",
+                snapshot.display()
+            );
+            return Ok(());
+        }
+        let previous = Schema::load(&snapshot)?;
+        let current = Schema::from_entities(&self.entities, self.db_info.kind);
+        for (path, content) in
+            tool.migration_files(&resources, &previous, &current, self.db_info.kind)
+        {
+            write_file(&path, &content)?;
+        }
+        write_file(&snapshot, &current.to_json())
+    }
+
+    fn write_build_files(&self) -> Result<(), CodegenError> {
+        let root = self.root_folder.as_path();
+        let pom_xml = &self.catalog.resolve_missing_versions(self.pom_xml.clone());
+        match (&self.build_system, &self.modules) {
+            (BuildSystem::Maven, None) => write_file(&root.join("pom.xml"), &pom_xml.generate()),
+            (BuildSystem::Maven, Some(layout)) => {
+                write_file(
+                    &root.join("pom.xml"),
+                    &layout.aggregator_pom(pom_xml).generate(),
+                )?;
+                for module in layout.modules() {
+                    write_file(
+                        &root.join(module).join("pom.xml"),
+                        &layout.module_pom(pom_xml, module).generate(),
+                    )?;
+                }
+                Ok(())
+            }
+            (BuildSystem::Gradle(_), Some(_)) => Err(CodegenError::Unsupported(
+                "multi-module gradle projects".to_owned(),
+            )),
+            (BuildSystem::Gradle(gradle), None) => {
+                write_file(
+                    &root.join(gradle.build_file_name()),
+                    &gradle.build_script(pom_xml),
+                )?;
+                write_file(
+                    &root.join(gradle.settings_file_name()),
+                    &gradle.settings_script(pom_xml),
+                )?;
+                write_file(
+                    &root.join(gradle.wrapper_properties_path()),
+                    &gradle.wrapper_properties(),
+                )
+            }
+        }
     }
 
     pub fn new(pom_xml: PomXml, output_dir: &str) -> Self {
@@ -195,17 +475,11 @@ impl MavenCodebase {
             "{}.{}",
             &pom_xml.project_info.group_id, &pom_xml.project_info.artifact_id
         );
-        let controllers_suffix = "controllers".to_owned();
-        let dtos_suffix = "dto".to_owned();
-        let repos_suffix = "repositories".to_owned();
-        let services_suffix = "services".to_owned();
-        let models_suffix = "models".to_owned();
-        let out_dirs = OutputDirs::new(output_dir.to_owned(), package_path.to_owned())
-            .models(models_suffix)
-            .controllers(controllers_suffix)
-            .dtos(dtos_suffix)
-            .services(services_suffix)
-            .repos(repos_suffix);
+        let output = OutputConfig::default();
+        let out_dirs = layer_dirs(
+            OutputDirs::new(output_dir.to_owned(), package_path.to_owned()),
+            &output,
+        );
         let mut root_folder = PathBuf::new();
         root_folder.push(output_dir);
         Self {
@@ -214,11 +488,29 @@ impl MavenCodebase {
             pom_xml,
             root_folder,
             out_dirs,
+            output,
             services: vec![],
             jpa_repos: vec![],
             entities: vec![],
             dto_classes: vec![],
             controller_classes: vec![],
+            validation: ValidationMode::default(),
+            build_system: BuildSystem::default(),
+            modules: None,
+            existing_project: false,
+            catalog: catalog::bundled().clone(),
+            local_repository: None,
+            security: None,
+            error_handling: None,
+            docker: None,
+            ci: None,
+            migrations: None,
+            config_format: ConfigFormat::default(),
+            profiles: vec![],
+            custom_config: vec![],
+            crud_inputs: vec![],
+            relationships: vec![],
+            tests: true,
             progress: Progress {
                 has_written_initial_files: false,
                 has_created_initial_folders: false,
@@ -227,51 +519,395 @@ impl MavenCodebase {
         }
     }
 
-    fn models_package(&self) -> String {
-        [&self.pom_xml.get_root_package(), "repositories"].join(".")
+    //targets a project that already exists, its pom.xml is read and written back
+    //with whatever gets added to it
+    pub fn open(project_dir: &str) -> Result<Self, CodegenError> {
+        let pom_location = Path::new(project_dir).join("pom.xml");
+        let pom = read_to_string(&pom_location).map_err(|e| CodegenError::io(&pom_location, e))?;
+        let mut codebase = Self::new(PomXml::parse(&pom)?, project_dir);
+        codebase.existing_project = true;
+        Ok(codebase)
     }
 
-    fn repositories_package(&self) -> String {
-        [&self.pom_xml.get_root_package(), "repositories"].join(".")
+    pub fn get_pom_xml(&self) -> &PomXml {
+        &self.pom_xml
     }
 
-    fn services_package(&self) -> String {
-        [&self.pom_xml.get_root_package(), "services"].join(".")
+    //e.g. add dependencies to an opened project
+    pub fn configure_pom(mut self, f: impl FnOnce(PomXml) -> PomXml) -> Self {
+        self.pom_xml = f(self.pom_xml);
+        //a driver that was added or swapped brings its own connection settings
+        match DatabaseKind::detect(&self.pom_xml) {
+            Some(kind) if kind != self.db_info.kind => {
+                self.db_info = kind.db_info(&self.pom_xml.project_info.name);
+            }
+            _ => {}
+        }
+        match self.migrations {
+            Some(tool) => self.migrations(tool),
+            None => self,
+        }
     }
 
-    fn controllers_package(&self) -> String {
-        [&self.pom_xml.get_root_package(), "services"].join(".")
+    //replaces the driver of any other database, the properties, compose.yaml and
+    //pipelines all follow the kind
+    pub fn database(mut self, kind: DatabaseKind) -> Self {
+        let others: Vec<_> = DatabaseKind::ALL
+            .into_iter()
+            .filter(|k| *k != kind)
+            .flat_map(|k| [Some(k.driver()), k.flyway_module()])
+            .flatten()
+            .collect();
+        //h2 in the test scope stays for the test profile
+        self.pom_xml.dependencies.retain(|d| {
+            d.is_test()
+                || !others
+                    .iter()
+                    .any(|o| o.group_id() == d.group_id() && o.artifact_id == d.artifact_id)
+        });
+        if DatabaseKind::detect(&self.pom_xml).is_none() {
+            self.pom_xml = self.pom_xml.dependency(kind.driver());
+        }
+        self.db_info = kind.db_info(&self.pom_xml.project_info.name);
+        match self.migrations {
+            Some(tool) => self.migrations(tool),
+            None => self,
+        }
     }
 
-    fn dto_package(&self) -> String {
-        [&self.pom_xml.get_root_package(), "dto"].join(".")
+    pub fn build_system(mut self, build_system: BuildSystem) -> Self {
+        self.build_system = build_system;
+        self
     }
 
-    //adds an entity model and the respective service and repo
-    pub fn add_entity(mut self, jclass: JavaClass) -> Self {
-        let model_import = Import::new(self.models_package(), jclass.class_name.clone());
-        let crud_build = CrudBuilder::new(jclass);
-        let entity = crud_build.spring_boot_entity();
-        let jpa_repo = crud_build.jpa_repository_of(model_import.clone());
-
-        let service = crud_build.service_from_class(Import::new(
-            self.repositories_package(),
-            jpa_repo.name.clone(),
-        ));
-
-        let dto = crud_build.dto_from_class(model_import);
-        let controller = crud_build.controller_from_class(
-            Import::new(self.services_package(), service.class_name.clone()),
-            Import::new(self.dto_package(), dto.class_name.clone()),
+    pub fn get_build_system(&self) -> &BuildSystem {
+        &self.build_system
+    }
+
+    //splits the project into an aggregator pom and one module per layer
+    pub fn modules(mut self, layout: ModuleLayout) -> Self {
+        self.modules = Some(layout);
+        self
+    }
+
+    fn dirs_for(&self, layer: Layer) -> OutputDirs {
+        let Some(ref layout) = self.modules else {
+            return self.out_dirs.clone();
+        };
+        let module = match layer {
+            Layer::Models => &layout.domain,
+            Layer::Dtos => &layout.api,
+            Layer::Repositories => &layout.persistence,
+            Layer::Services | Layer::Controllers | Layer::Application => &layout.web,
+        };
+        self.out_dirs.in_module(module)
+    }
+
+    //a catalog loaded from disk instead of the bundled one
+    pub fn catalog(mut self, catalog: Catalog) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    //e.g. LocalRepository::user_default() so air-gapped builds fail before maven runs
+    pub fn local_repository(mut self, repository: LocalRepository) -> Self {
+        self.local_repository = Some(repository);
+        self
+    }
+
+    //every path except the login and the docs needs authentication, the generated
+    //controllers check the roles of the caller
+    pub fn security(mut self, mut config: SecurityConfig) -> Self {
+        if self
+            .pom_xml
+            .has_dependency_that(|d| d.artifact_id.starts_with("springdoc-openapi"))
+        {
+            for path in ["/api-docs/**", "/docs.html", "/swagger-ui/**"] {
+                config = config.public_path(path.to_owned());
+            }
+        }
+        let mut pom_xml = self.pom_xml.clone();
+        if !pom_xml.has_dependency_that(|d| d.artifact_id == "spring-boot-starter-security") {
+            pom_xml = pom_xml.spring_boot_starter_security();
+        }
+        if !pom_xml.has_dependency_that(|d| d.artifact_id == "spring-security-test") {
+            pom_xml = pom_xml.spring_security_test();
+        }
+        if config.mode == AuthMode::Jwt
+            && !pom_xml.has_dependency_that(|d| {
+                d.artifact_id == "spring-boot-starter-oauth2-resource-server"
+            })
+        {
+            pom_xml = pom_xml.spring_boot_starter_oauth2_resource_server();
+        }
+        self.pom_xml = pom_xml;
+
+        self.put_user(&config);
+        self.controller_classes = self
+            .controller_classes
+            .into_iter()
+            .map(|c| config.secure_controller(c))
+            .collect();
+        self.security = Some(config);
+        self
+    }
+
+    //a multi-stage Dockerfile and a compose.yaml with the app and its database
+    pub fn docker(mut self, config: DockerConfig) -> Self {
+        self.docker = Some(config);
+        self
+    }
+
+    //builds and tests the project on every push, the image is built too when docker is set
+    pub fn ci(mut self, config: CiConfig) -> Self {
+        self.ci = Some(config);
+        self
+    }
+
+    //flyway scripts or liquibase changelogs for the entities, every generation adds one
+    //with what changed since the last
+    //the dependencies are added once there is a database driver, see database and configure_pom
+    pub fn migrations(mut self, tool: MigrationTool) -> Self {
+        self.migrations = Some(tool);
+        if !self.has_database() {
+            return self;
+        }
+        for lib in tool.dependencies(self.db_info.kind) {
+            if !self.pom_xml.has_dependency_that(|d| {
+                d.group_id() == lib.group_id() && d.artifact_id == lib.artifact_id
+            }) {
+                self.pom_xml = self.pom_xml.dependency(lib);
+            }
+        }
+        self
+    }
+
+    //application.yml instead of application.properties
+    pub fn config_format(mut self, format: ConfigFormat) -> Self {
+        self.config_format = format;
+        self
+    }
+
+    //a file per profile next to the shared one, the test profile runs against h2
+    pub fn profiles(mut self, profiles: Vec<SpringProfile>) -> Self {
+        let h2 = DatabaseKind::H2.driver();
+        if profiles.contains(&SpringProfile::Test)
+            && !self.pom_xml.has_dependency_that(|d| {
+                d.group_id() == h2.group_id() && d.artifact_id == h2.artifact_id
+            })
+        {
+            self.pom_xml = self.pom_xml.dependency(h2.scope(Scope::Test));
+        }
+        self.profiles = profiles;
+        self
+    }
+
+    //merged over the generated keys of the shared file
+    pub fn property(mut self, key: String, value: String) -> Self {
+        self.custom_config.push((None, key, value));
+        self
+    }
+
+    pub fn profile_property(mut self, profile: SpringProfile, key: String, value: String) -> Self {
+        self.custom_config.push((Some(profile), key, value));
+        self
+    }
+
+    pub fn validation(mut self, mode: ValidationMode) -> Self {
+        self.validation = mode;
+        self
+    }
+
+    //leaves src/test/java to the project, e.g. one that was opened with tests of its own
+    pub fn without_tests(mut self) -> Self {
+        self.tests = false;
+        self
+    }
+
+    //the classes that were already added are generated again with the new names
+    pub fn output(mut self, config: OutputConfig) -> Self {
+        self.out_dirs = layer_dirs(self.out_dirs, &config);
+        self.output = config;
+        self.regenerate();
+        self
+    }
+
+    //problem details for missing entities, invalid requests and unexpected failures, the
+    //services throw when an entity does not exist and the request bodies are validated
+    pub fn error_handling(mut self, config: ErrorHandling) -> Self {
+        if !self
+            .pom_xml
+            .has_dependency_that(|d| d.artifact_id == "spring-boot-starter-validation")
+        {
+            self.pom_xml = self.pom_xml.clone().spring_boot_starter_validation();
+        }
+        self.error_handling = Some(config);
+        self.regenerate();
+        self
+    }
+
+    //the classes that were already added, generated again after a codebase wide change
+    fn regenerate(&mut self) {
+        self.entities.clear();
+        self.jpa_repos.clear();
+        self.services.clear();
+        self.dto_classes.clear();
+        self.controller_classes.clear();
+        if let Some(security) = self.security.clone() {
+            self.put_user(&security);
+        }
+        for (jclass, options) in self.crud_inputs.clone() {
+            self.put_crud(jclass, options);
+        }
+    }
+
+    fn package_of(&self, layer: Layer, entity: &str) -> String {
+        self.output
+            .package_of(&self.pom_xml.get_root_package(), layer, entity)
+    }
+
+    fn security_package(&self) -> String {
+        format!(
+            "{}.{}",
+            self.pom_xml.get_root_package(),
+            self.output.security_package
+        )
+    }
+
+    fn errors_package(&self) -> String {
+        format!(
+            "{}.{}",
+            self.pom_xml.get_root_package(),
+            self.output.errors_package
+        )
+    }
+
+    //the users of spring security are stored like the other entities
+    fn put_user(&mut self, config: &SecurityConfig) {
+        let root_package = self.pom_xml.get_root_package();
+        put_class(
+            &mut self.entities,
+            config.user_entity(&self.output, &root_package),
         );
-        self.entities.push(entity);
-        self.services.push(service);
-        self.jpa_repos.push(jpa_repo);
-        self.dto_classes.push(dto);
-        self.controller_classes.push(controller);
+        let repository = config.user_repository(&self.output, &root_package);
+        match self
+            .jpa_repos
+            .iter_mut()
+            .find(|r| r.name == repository.name)
+        {
+            Some(r) => *r = repository,
+            None => self.jpa_repos.push(repository),
+        }
+    }
+
+    //adds an entity model and the respective service and repo
+    pub fn add_entity(self, jclass: JavaClass) -> Self {
+        self.add_entity_with(jclass, CrudOptions::default())
+    }
+
+    //same as add_entity, the options say what the list endpoint can search and sort on
+    //and which queries the repository has
+    pub fn add_entity_with(mut self, jclass: JavaClass, options: CrudOptions) -> Self {
+        if options.dto.mapper == MapperStyle::MapStruct
+            && !self
+                .pom_xml
+                .has_dependency_that(|d| d.artifact_id == "mapstruct")
+        {
+            self.pom_xml = self.pom_xml.clone().mapstruct();
+        }
+        //the controllers validate the request bodies, @Valid comes with the starter
+        if !self
+            .pom_xml
+            .has_dependency_that(|d| d.artifact_id == "spring-boot-starter-validation")
+        {
+            self.pom_xml = self.pom_xml.clone().spring_boot_starter_validation();
+        }
+        self.crud_inputs.push((jclass.clone(), options.clone()));
+        //the entities related to it refer to its id
+        let related: Vec<(JavaClass, CrudOptions)> =
+            self.crud_inputs
+                .iter()
+                .filter(|(other, _)| {
+                    other.class_name != jclass.class_name
+                        && self.relationships.iter().any(|r| {
+                            r.involves(&other.class_name) && r.involves(&jclass.class_name)
+                        })
+                })
+                .cloned()
+                .collect();
+        self.put_crud(jclass, options);
+        for (other, options) in related {
+            self.put_crud(other, options);
+        }
         self
     }
 
+    //maps a relationship between two entities, the classes of the entities that were
+    //already added are generated again with the mapped fields and the nested route
+    pub fn relationship(mut self, relationship: Relationship) -> Self {
+        self.relationships.push(relationship.clone());
+        let involved: Vec<(JavaClass, CrudOptions)> = self
+            .crud_inputs
+            .iter()
+            .filter(|(jclass, _)| relationship.involves(&jclass.class_name))
+            .cloned()
+            .collect();
+        for (jclass, options) in involved {
+            self.put_crud(jclass, options);
+        }
+        self
+    }
+
+    //the entity and its crud classes, replacing the ones generated before for it
+    fn put_crud(&mut self, jclass: JavaClass, options: CrudOptions) {
+        let crud_build = self.crud_builder(jclass, options);
+        let jpa_repo = crud_build.jpa_repository_of();
+        let mut controller = crud_build.controller_from_class();
+        if let Some(ref security) = self.security {
+            controller = security.secure_controller(controller);
+        }
+        put_class(&mut self.entities, crud_build.spring_boot_entity());
+        if let Some(key) = crud_build.id_class() {
+            put_class(&mut self.entities, key);
+        }
+        put_class(&mut self.services, crud_build.service_from_class());
+        put_class(&mut self.dto_classes, crud_build.request_dto());
+        put_class(&mut self.dto_classes, crud_build.response_dto());
+        put_class(&mut self.dto_classes, crud_build.mapper());
+        put_class(&mut self.controller_classes, controller);
+        match self.jpa_repos.iter_mut().find(|r| r.name == jpa_repo.name) {
+            Some(r) => *r = jpa_repo,
+            None => self.jpa_repos.push(jpa_repo),
+        }
+    }
+
+    fn crud_builder(&self, jclass: JavaClass, options: CrudOptions) -> CrudBuilder {
+        let crud_build = CrudBuilder::new(jclass)
+            .options(options)
+            .relationships(self.relationships.clone())
+            .output(self.output.clone())
+            .root_package(self.pom_xml.get_root_package())
+            .entity_ids(self.entity_ids());
+        match self.error_handling {
+            Some(_) => crud_build
+                .not_found_exception(ErrorHandling::not_found_import(self.errors_package())),
+            None => crud_build,
+        }
+    }
+
+    //the id field of every entity added so far, what a reference to it is made of
+    fn entity_ids(&self) -> Vec<(String, Field)> {
+        self.crud_inputs
+            .iter()
+            .map(|(jclass, options)| {
+                let id = CrudBuilder::new(jclass.clone())
+                    .options(options.clone())
+                    .id_field();
+                (jclass.class_name.clone(), id)
+            })
+            .collect()
+    }
+
     pub fn add_entities(mut self, jclasses: Vec<JavaClass>) -> Self {
         for jclass in jclasses {
             self = self.add_entity(jclass);
@@ -302,75 +938,347 @@ impl MavenCodebase {
                     ))
                     .code(format!("SpringApplication.run({}.class,args);", class_name)),
             );
-        jclass
+        self.with_auditing(jclass)
     }
 
-    fn put_classes_in_packages(&mut self) {
-        let in_package = self.models_package();
-        for i in 0..self.entities.len() {
-            let cls = &mut self.entities[i];
-            cls.package_in_place(in_package.clone());
+    //the audited entities are filled in by spring data, createdBy is the logged in user
+    fn with_auditing(&self, mut jclass: JavaClass) -> JavaClass {
+        if !self.crud_inputs.iter().any(|(_, o)| o.is_audited()) {
+            return jclass;
         }
-        let in_package = self.services_package();
-        for i in 0..self.services.len() {
-            let cls = &mut self.services[i];
-            cls.package_in_place(in_package.clone());
+        jclass = jclass
+            .import(Import::new(
+                "org.springframework.data.jpa.repository.config".into(),
+                "EnableJpaAuditing".into(),
+            ))
+            .annotation("EnableJpaAuditing".into());
+        if !self.crud_inputs.iter().any(|(_, o)| o.created_by) {
+            return jclass;
         }
-        let in_package = self.controllers_package();
-        for i in 0..self.controller_classes.len() {
-            let cls = &mut self.controller_classes[i];
-            cls.package_in_place(in_package.clone());
+        //nobody is logged in without spring security
+        let auditor = match self.security {
+            Some(_) => {
+                jclass = jclass
+                    .import(Import::new(
+                        "org.springframework.security.core".into(),
+                        "Authentication".into(),
+                    ))
+                    .import(Import::new(
+                        "org.springframework.security.core.context".into(),
+                        "SecurityContextHolder".into(),
+                    ));
+                "return () -> Optional.ofNullable(SecurityContextHolder.getContext().getAuthentication())\n    .filter(Authentication::isAuthenticated)\n    .map(Authentication::getName);"
+            }
+            None => "return Optional::empty;",
+        };
+        jclass
+            .import(Import::new("java.util".into(), "Optional".into()))
+            .import(Import::new(
+                "org.springframework.context.annotation".into(),
+                "Bean".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.data.domain".into(),
+                "AuditorAware".into(),
+            ))
+            .method(
+                Method::new(
+                    TypeName::new_with_generics(
+                        "AuditorAware".into(),
+                        GenericParams::new(vec!["String".into()]),
+                    ),
+                    "auditorAware".into(),
+                )
+                .public()
+                .annotation("Bean".into())
+                .code(auditor.into()),
+            )
+    }
+
+    //configuration, login endpoint and user lookup, next to the controllers
+    fn security_classes(&self) -> Vec<JavaClass> {
+        let Some(ref security) = self.security else {
+            return vec![];
+        };
+        security.classes(
+            self.security_package(),
+            Import::new(self.package_of(Layer::Models, "User"), "User".to_owned()),
+            Import::new(
+                self.package_of(Layer::Repositories, "User"),
+                self.output.repository_name("User"),
+            ),
+        )
+    }
+
+    //the advice and the exception the services throw
+    fn error_classes(&self) -> Vec<JavaClass> {
+        let Some(ref error_handling) = self.error_handling else {
+            return vec![];
+        };
+        error_handling.classes(self.errors_package(), self.security.is_some())
+    }
+
+    //a container replaces h2 when the pom has testcontainers for postgresql, the database
+    //of the project has to be postgresql for the migrations and the dialect to fit
+    fn test_suite(&self) -> TestSuite {
+        let has_container = self.pom_xml.has_dependency_that(|d| {
+            d.group_id() == "org.testcontainers" && d.artifact_id == "postgresql"
+        });
+        let container_image = match self.db_info.kind {
+            DatabaseKind::PostgreSql if has_container => self
+                .db_info
+                .kind
+                .service(&self.db_info)
+                .map(|service| service.image),
+            kind if has_container => {
+                log::warn!(
+                    "no integration tests, testcontainers for postgresql can not stand in for {:?}",
+                    kind
+                );
+                None
+            }
+            _ => None,
+        };
+        TestSuite {
+            secured: self.security.is_some(),
+            signs_tokens: self
+                .security
+                .as_ref()
+                .is_some_and(|s| s.mode == AuthMode::Jwt),
+            read_roles: self
+                .security
+                .as_ref()
+                .map(|s| s.read_roles.clone())
+                .unwrap_or_default(),
+            audited: self.crud_inputs.iter().any(|(_, o)| o.is_audited()),
+            mockito_bean: self
+                .pom_xml
+                .spring_boot_version()
+                .is_some_and(|v| compare_versions(v, "3.4.0").is_ge()),
+            container_image,
+            migrations: self.migrations,
         }
+    }
 
-        let in_package = self.repositories_package();
-        for i in 0..self.jpa_repos.len() {
-            let cls = &mut self.jpa_repos[i];
-            cls.package_in_place(in_package.clone());
+    //a controller and a repository test per entity, an integration test with a container
+    fn test_classes(&self) -> Vec<JavaClass> {
+        if !self.tests || self.crud_inputs.is_empty() {
+            return vec![];
+        }
+        let suite = self.test_suite();
+        let mut classes = vec![];
+        for (jclass, options) in self.crud_inputs.iter() {
+            let crud_build = self.crud_builder(jclass.clone(), options.clone());
+            classes.push(crud_build.controller_test(&suite));
+            classes.push(crud_build.repository_test(&suite));
+            if suite.container_image.is_some() {
+                classes.push(crud_build.integration_test(&suite));
+            }
         }
+        classes.extend(suite.containers_configuration(self.pom_xml.get_root_package()));
+        classes
+    }
 
-        let in_package = self.dto_package();
-        for i in 0..self.dto_classes.len() {
-            let cls = &mut self.dto_classes[i];
-            cls.package_in_place(in_package.clone());
+    //what the generated tests run with, h2 stands in for the database without a container
+    fn add_test_libraries(&mut self) {
+        if self.test_classes().is_empty() {
+            return;
+        }
+        let suite = self.test_suite();
+        let mut libraries = vec![Library::new(
+            "org.springframework.boot".into(),
+            "spring-boot-starter-test".into(),
+        )];
+        match suite.container_image {
+            Some(_) => {
+                libraries.push(Library::new(
+                    "org.springframework.boot".into(),
+                    "spring-boot-testcontainers".into(),
+                ));
+                if suite.secured {
+                    libraries.push(Library::new(
+                        "org.springframework.security".into(),
+                        "spring-security-test".into(),
+                    ));
+                }
+            }
+            None => libraries.push(DatabaseKind::H2.driver()),
+        }
+        for lib in libraries {
+            if !self.pom_xml.has_dependency_that(|d| {
+                d.group_id() == lib.group_id() && d.artifact_id == lib.artifact_id
+            }) {
+                self.pom_xml = self.pom_xml.clone().dependency(lib.scope(Scope::Test));
+            }
         }
     }
-    pub fn generate_code(&mut self) {
-        self.create_initial_folders();
-        self.write_initial_files();
-        self.put_classes_in_packages();
-        println!("Generating code");
 
-        self.out_dirs.generate_classes_in(
-            &self.entities,
-            self.out_dirs.models_folder().to_str().unwrap(),
+    fn main_class_unit(&self) -> CompilationUnit {
+        let mut units = OutputDirs::class_units(
+            &[self.create_spring_main_class()],
+            self.dirs_for(Layer::Application).code_folder(),
         );
+        units.remove(0)
+    }
 
-        self.out_dirs.generate_interfaces_in(
-            &self.jpa_repos,
-            self.out_dirs.repos_folder().to_str().unwrap(),
-        );
+    //every generated class with the folder it is written to, the folder of its package
+    //in the module of its layer
+    fn placed_classes(&self) -> Vec<(JavaClass, PathBuf)> {
+        let layers = [
+            (self.entities.clone(), Layer::Models),
+            (self.services.clone(), Layer::Services),
+            (self.controller_classes.clone(), Layer::Controllers),
+            (self.dto_classes.clone(), Layer::Dtos),
+            (self.security_classes(), Layer::Controllers),
+            (self.error_classes(), Layer::Controllers),
+        ];
+        let mut placed = vec![];
+        for (classes, layer) in layers {
+            let dirs = self.dirs_for(layer);
+            for c in classes {
+                let folder = dirs.package_folder(&c.package);
+                placed.push((c, folder));
+            }
+        }
+        //the tests are next to the main class, in the web module of a multi-module project
+        let dirs = self.dirs_for(Layer::Application);
+        for test in self.test_classes() {
+            let folder = dirs.test_package_folder(&test.package);
+            placed.push((test, folder));
+        }
+        placed
+    }
 
-        self.out_dirs.generate_classes_in(
-            &self.services,
-            self.out_dirs.services_folder().to_str().unwrap(),
-        );
+    fn repository_folder(&self, repo: &Interface) -> PathBuf {
+        self.dirs_for(Layer::Repositories)
+            .package_folder(&repo.package)
+    }
 
-        self.out_dirs.generate_classes_in(
-            &self.controller_classes,
-            self.out_dirs.controllers_folder().to_str().unwrap(),
-        );
+    fn compilation_units(&self) -> Vec<CompilationUnit> {
+        let mut units = vec![];
+        for (cls, folder) in self.placed_classes() {
+            units.extend(OutputDirs::class_units(&[cls], &folder));
+        }
+        for repo in self.jpa_repos.iter() {
+            units.extend(OutputDirs::interface_units(
+                std::slice::from_ref(repo),
+                &self.repository_folder(repo),
+            ));
+        }
+        units
+    }
 
-        self.out_dirs.generate_classes_in(
-            &self.dto_classes,
-            self.out_dirs.dtos_folder().to_str().unwrap(),
-        );
+    //runs the Linter over every generated class and interface, against the file it is
+    //written to
+    pub fn lint(&mut self) -> Vec<Diagnostic> {
+        let classes = self.placed_classes();
+        let mut known_types: Vec<String> =
+            classes.iter().map(|(c, _)| c.class_name.clone()).collect();
+        known_types.extend(self.jpa_repos.iter().map(|i| i.name.clone()));
+        let linter = Linter::new().known_types(known_types);
+        let stem = |path: &Path| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        let mut diagnostics = vec![];
+        if !self.existing_project {
+            let main_class = self.create_spring_main_class();
+            let unit = self.main_class_unit();
+            diagnostics.extend(linter.lint_class(&main_class, &stem(&unit.path)));
+        }
+        for (cls, folder) in classes.iter() {
+            let path = OutputDirs::unit_path(folder, &cls.class_name);
+            diagnostics.extend(linter.lint_class(cls, &stem(&path)));
+        }
+        for repo in self.jpa_repos.iter() {
+            let path = OutputDirs::unit_path(&self.repository_folder(repo), &repo.name);
+            diagnostics.extend(linter.lint_interface(repo, &stem(&path)));
+        }
+        diagnostics
     }
 
-    pub fn extract_to_zip(&self) -> PathBuf {
+    fn check_units(&mut self, units: &[CompilationUnit]) -> ValidationReport {
+        let mut report = ValidationReport {
+            diagnostics: self.lint(),
+            ..Default::default()
+        };
+        report
+            .diagnostics
+            .extend(self.pom_xml.check_dependencies(&self.catalog));
+        if let Some(ref repository) = self.local_repository {
+            let resolved = self.catalog.resolve_missing_versions(self.pom_xml.clone());
+            report.diagnostics.extend(repository.check(&resolved));
+        }
+        if !self.existing_project {
+            report.check(&self.main_class_unit());
+        }
+        for unit in units.iter() {
+            report.check(unit);
+        }
+        report
+    }
+
+    //parses every file that is about to be written without touching the disk
+    pub fn validate(&mut self) -> ValidationReport {
+        let units = self.compilation_units();
+        self.check_units(&units)
+    }
+
+    //CodegenError::InvalidModel is returned when validation refused to write the project
+    pub fn generate_code(&mut self) -> Result<ValidationReport, CodegenError> {
+        self.add_test_libraries();
+        let units = self.compilation_units();
+        let report = match self.validation {
+            ValidationMode::Skip => ValidationReport::default(),
+            _ => self.check_units(&units),
+        };
+        if self.validation == ValidationMode::Refuse && !report.is_ok() {
+            return Err(CodegenError::InvalidModel(report));
+        }
+        for err in report.syntax_errors.iter() {
+            log::warn!("{}", err);
+        }
+        for d in report.diagnostics.iter() {
+            log::warn!("{}", d);
+        }
+
+        self.create_initial_folders()?;
+        self.write_initial_files()?;
+        log::info!("Generating code");
+        self.out_dirs.write_units(&units)?;
+        self.write_migrations()?;
+        Ok(report)
+    }
+
+    pub fn extract_to_zip(&self) -> Result<PathBuf, CodegenError> {
         self.out_dirs.extract_to_zip()
     }
 }
 
+//the folders of the layers follow the packages of the config
+fn layer_dirs(out_dirs: OutputDirs, output: &OutputConfig) -> OutputDirs {
+    out_dirs
+        .models(output.layer_folder(Layer::Models))
+        .repos(output.layer_folder(Layer::Repositories))
+        .services(output.layer_folder(Layer::Services))
+        .controllers(output.layer_folder(Layer::Controllers))
+        .dtos(output.layer_folder(Layer::Dtos))
+}
+
+//replaces the class with the same name, classes are generated again when a relationship
+//is added
+fn put_class(classes: &mut Vec<JavaClass>, jclass: JavaClass) {
+    match classes
+        .iter_mut()
+        .find(|c| c.class_name == jclass.class_name)
+    {
+        Some(c) => *c = jclass,
+        None => classes.push(jclass),
+    }
+}
+
 //https://nick.groenen.me/notes/capitalize-a-string-in-rust/
 pub fn capitalize(s: &str) -> String {
     let mut c = s.chars();
@@ -380,8 +1288,6 @@ pub fn capitalize(s: &str) -> String {
     }
 }
 
-pub fn cleanup_folder(dir: &str) {
-    if let Err(e) = remove_dir_all(dir) {
-        assert!(false, "Removing all files from folder failed");
-    }
+pub fn cleanup_folder(dir: &str) -> Result<(), CodegenError> {
+    remove_dir_all(dir).map_err(|e| CodegenError::io(dir, e))
 }
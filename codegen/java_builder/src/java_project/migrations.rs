@@ -0,0 +1,1070 @@
+//versioned schema migrations instead of ddl-auto. the tables the entities describe are
+//compared with the snapshot taken at the last generation and only the difference ends up
+//in a new flyway script or liquibase changelog
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{database::DatabaseKind, error::CodegenError, pom_xml::Library, xml::XmlElement};
+use crate::{
+    annotations::Annotation, classes::JavaClass, fields::Field, modifiers::AccessModifiers,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationTool {
+    //db/migration/V<n>__<description>.sql
+    Flyway,
+    //db/changelog/changes/<n>-<description>.xml, included by the master changelog
+    Liquibase,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub sql_type: String,
+    pub nullable: bool,
+    pub unique: bool,
+    //numbered by the database
+    pub identity: bool,
+    //from @ColumnDefault, lets a not null column be added to a table that has rows
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub name: String,
+    pub column: String,
+    pub references_table: String,
+    pub references_column: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+//numbers for the ids of a @SequenceGenerator
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sequence {
+    pub name: String,
+    //the allocation size, hibernate hands out that many ids per call
+    pub increment: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    //sorted by name so snapshots and scripts come out the same every time
+    pub tables: BTreeMap<String, Table>,
+    #[serde(default)]
+    pub sequences: BTreeMap<String, Sequence>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaChange {
+    //without its foreign keys, they are added once every table exists
+    CreateTable(Table),
+    DropTable(String),
+    AddColumn(String, Column),
+    DropColumn(String, Column),
+    //the column as it is and as it should be
+    AlterColumn(String, Column, Column),
+    AddForeignKey(String, ForeignKey),
+    DropForeignKey(String, ForeignKey),
+    CreateSequence(Sequence),
+    DropSequence(String),
+}
+
+//firstName -> first_name, the way spring boot names tables and columns
+pub fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            result.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        result.extend(c.to_lowercase());
+    }
+    result
+}
+
+fn find<'a>(annotations: &'a [Annotation], name: &str) -> Option<&'a Annotation> {
+    annotations.iter().find(|a| a.qualified_name == name)
+}
+
+//the value of a parameter without its quotes, e.g. name for @Column(name = "x")
+pub(crate) fn param(annotations: &[Annotation], annotation: &str, name: &str) -> Option<String> {
+    find(annotations, annotation)?
+        .params_list
+        .as_ref()?
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.trim().trim_matches('"').to_owned())
+}
+
+fn unique_name(table: &str, column: &str) -> String {
+    format!("uk_{}_{}", table, column)
+}
+
+//sql server keeps a default as a constraint, named so it can be dropped again
+fn default_name(table: &str, column: &str) -> String {
+    format!("df_{}_{}", table, column)
+}
+
+fn is_collection(field: &Field) -> bool {
+    matches!(
+        field.type_.name.as_str(),
+        "List" | "Set" | "Collection" | "Map"
+    )
+}
+
+fn generic_type(field: &Field) -> Option<&str> {
+    field
+        .type_
+        .generic_params
+        .as_ref()
+        .and_then(|g| g.generics.last())
+        .map(|g| g.as_str())
+}
+
+//what other tables need to know to reference an entity
+struct EntityKey {
+    table: String,
+    id_column: String,
+    id_type: String,
+}
+
+pub(crate) fn table_name(entity: &JavaClass) -> String {
+    param(&entity.class_annotations, "Table", "name").unwrap_or(snake_case(&entity.class_name))
+}
+
+fn column_name(field: &Field) -> String {
+    param(&field.annotation, "Column", "name").unwrap_or(snake_case(&field.name))
+}
+
+fn id_field(entity: &JavaClass) -> Option<&Field> {
+    entity
+        .fields
+        .iter()
+        .find(|f| find(&f.annotation, "Id").is_some())
+}
+
+//the columns of an @EmbeddedId are the fields of its @Embeddable, by name
+fn embedded_id_columns(
+    entity: &JavaClass,
+    entities: &[JavaClass],
+    kind: DatabaseKind,
+) -> Option<Vec<Column>> {
+    let id = entity
+        .fields
+        .iter()
+        .find(|f| find(&f.annotation, "EmbeddedId").is_some())?;
+    let key = entities.iter().find(|e| e.class_name == id.type_.name)?;
+    let mut fields: Vec<&Field> = key.fields.iter().collect();
+    fields.sort_by_key(|f| f.name.clone());
+    Some(
+        fields
+            .into_iter()
+            .map(|f| Column {
+                nullable: false,
+                ..basic_column(f, kind)
+            })
+            .collect(),
+    )
+}
+
+fn sequence(id: &Field) -> Option<Sequence> {
+    find(&id.annotation, "SequenceGenerator")?;
+    let name = param(&id.annotation, "SequenceGenerator", "sequenceName").or(param(
+        &id.annotation,
+        "SequenceGenerator",
+        "name",
+    ))?;
+    Some(Sequence {
+        name,
+        increment: param(&id.annotation, "SequenceGenerator", "allocationSize")
+            .and_then(|a| a.parse().ok())
+            .unwrap_or(50),
+    })
+}
+
+impl Schema {
+    pub fn from_entities(entities: &[JavaClass], kind: DatabaseKind) -> Self {
+        let keys: BTreeMap<String, EntityKey> = entities
+            .iter()
+            .filter_map(|e| {
+                let id = id_field(e)?;
+                Some((
+                    e.class_name.clone(),
+                    EntityKey {
+                        table: table_name(e),
+                        id_column: column_name(id),
+                        id_type: kind.column_type(&id.type_.name, None),
+                    },
+                ))
+            })
+            .collect();
+        let mut schema = Schema::default();
+        for entity in entities.iter() {
+            let mut table = match keys.get(&entity.class_name) {
+                Some(key) => Table {
+                    name: key.table.clone(),
+                    columns: vec![],
+                    primary_key: vec![key.id_column.clone()],
+                    foreign_keys: vec![],
+                },
+                None => {
+                    let Some(columns) = embedded_id_columns(entity, entities, kind) else {
+                        continue;
+                    };
+                    Table {
+                        name: table_name(entity),
+                        primary_key: columns.iter().map(|c| c.name.clone()).collect(),
+                        columns,
+                        foreign_keys: vec![],
+                    }
+                }
+            };
+            if let Some(sequence) = id_field(entity).and_then(sequence) {
+                schema.sequences.insert(sequence.name.clone(), sequence);
+            }
+            //the fields are a set, the id goes first and the rest by name
+            let mut fields: Vec<&Field> = entity.fields.iter().collect();
+            fields.sort_by_key(|f| (find(&f.annotation, "Id").is_none(), f.name.clone()));
+            for field in fields {
+                if field.modifiers.contains(&AccessModifiers::Static)
+                    || find(&field.annotation, "Transient").is_some()
+                    || find(&field.annotation, "EmbeddedId").is_some()
+                {
+                    continue;
+                }
+                let owner = keys.get(&entity.class_name);
+                //the tables of collections reference the owner by a single column
+                if owner.is_none()
+                    && (find(&field.annotation, "ElementCollection").is_some()
+                        || find(&field.annotation, "ManyToMany").is_some())
+                {
+                    log::warn!(
+                        "{}.{} is not migrated, {} has a composite id",
+                        entity.class_name,
+                        field.name,
+                        entity.class_name
+                    );
+                    continue;
+                }
+                if let (Some(key), true) = (
+                    owner,
+                    find(&field.annotation, "ElementCollection").is_some(),
+                ) {
+                    let collection = element_collection(entity, key, field, kind);
+                    schema.tables.insert(collection.name.clone(), collection);
+                    continue;
+                }
+                if let (Some(key), true) = (
+                    owner,
+                    find(&field.annotation, "ManyToMany").is_some()
+                        && param(&field.annotation, "ManyToMany", "mappedBy").is_none(),
+                ) {
+                    if let Some(target) = generic_type(field).and_then(|t| keys.get(t)) {
+                        let join = join_table(entity, key, target, field);
+                        schema.tables.insert(join.name.clone(), join);
+                    }
+                    continue;
+                }
+                //the other side of a relationship keeps the foreign key
+                if is_collection(field)
+                    || param(&field.annotation, "OneToOne", "mappedBy").is_some()
+                {
+                    continue;
+                }
+                match keys.get(&field.type_.name) {
+                    Some(target) => {
+                        let column = param(&field.annotation, "JoinColumn", "name")
+                            .unwrap_or(snake_case(&field.name) + "_id");
+                        table.foreign_keys.push(ForeignKey {
+                            name: format!("fk_{}_{}", table.name, column),
+                            column: column.clone(),
+                            references_table: target.table.clone(),
+                            references_column: target.id_column.clone(),
+                        });
+                        table.columns.push(Column {
+                            name: column,
+                            sql_type: target.id_type.clone(),
+                            nullable: param(&field.annotation, "JoinColumn", "nullable")
+                                .is_none_or(|n| n != "false"),
+                            unique: find(&field.annotation, "OneToOne").is_some(),
+                            identity: false,
+                            default: None,
+                        });
+                    }
+                    None => table.columns.push(basic_column(field, kind)),
+                }
+            }
+            schema.tables.insert(table.name.clone(), table);
+        }
+        schema
+    }
+
+    //a missing snapshot is an empty schema
+    pub fn load(path: &Path) -> Result<Self, CodegenError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = read_to_string(path).map_err(|e| CodegenError::io(path, e))?;
+        serde_json::from_str(&json).map_err(|e| CodegenError::Parse(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    //what has to run against a database at self to end up at target. primary keys and
+    //the increments of sequences are expected to stay the way they were created
+    pub fn diff(&self, target: &Schema) -> Vec<SchemaChange> {
+        let mut drop_foreign_keys = vec![];
+        let mut create_tables: Vec<SchemaChange> = target
+            .sequences
+            .values()
+            .filter(|s| !self.sequences.contains_key(&s.name))
+            .map(|s| SchemaChange::CreateSequence(s.clone()))
+            .collect();
+        let mut columns = vec![];
+        let mut drop_columns = vec![];
+        let mut drop_tables = vec![];
+        let mut add_foreign_keys = vec![];
+        for (name, table) in target.tables.iter() {
+            let Some(old) = self.tables.get(name) else {
+                create_tables.push(SchemaChange::CreateTable(Table {
+                    foreign_keys: vec![],
+                    ..table.clone()
+                }));
+                for fk in table.foreign_keys.iter() {
+                    add_foreign_keys.push(SchemaChange::AddForeignKey(name.clone(), fk.clone()));
+                }
+                continue;
+            };
+            for column in table.columns.iter() {
+                match old.columns.iter().find(|c| c.name == column.name) {
+                    None => columns.push(SchemaChange::AddColumn(name.clone(), column.clone())),
+                    Some(previous) if previous != column => columns.push(
+                        SchemaChange::AlterColumn(name.clone(), previous.clone(), column.clone()),
+                    ),
+                    _ => {}
+                }
+            }
+            for column in old.columns.iter() {
+                if !table.columns.iter().any(|c| c.name == column.name) {
+                    drop_columns.push(SchemaChange::DropColumn(name.clone(), column.clone()));
+                }
+            }
+            for fk in old.foreign_keys.iter() {
+                if !table.foreign_keys.contains(fk) {
+                    drop_foreign_keys.push(SchemaChange::DropForeignKey(name.clone(), fk.clone()));
+                }
+            }
+            for fk in table.foreign_keys.iter() {
+                if !old.foreign_keys.contains(fk) {
+                    add_foreign_keys.push(SchemaChange::AddForeignKey(name.clone(), fk.clone()));
+                }
+            }
+        }
+        for (name, old) in self.tables.iter() {
+            if target.tables.contains_key(name) {
+                continue;
+            }
+            for fk in old.foreign_keys.iter() {
+                drop_foreign_keys.push(SchemaChange::DropForeignKey(name.clone(), fk.clone()));
+            }
+            drop_tables.push(SchemaChange::DropTable(name.clone()));
+        }
+        for name in self.sequences.keys() {
+            if !target.sequences.contains_key(name) {
+                drop_tables.push(SchemaChange::DropSequence(name.clone()));
+            }
+        }
+        [
+            drop_foreign_keys,
+            create_tables,
+            columns,
+            drop_columns,
+            drop_tables,
+            add_foreign_keys,
+        ]
+        .concat()
+    }
+}
+
+fn basic_column(field: &Field, kind: DatabaseKind) -> Column {
+    let annotations = &field.annotation;
+    let is_id = find(annotations, "Id").is_some();
+    let primitive = field
+        .type_
+        .name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_lowercase())
+        && field.type_.name != "byte[]";
+    let required = ["NotNull", "NotBlank", "NotEmpty"]
+        .iter()
+        .any(|a| find(annotations, a).is_some())
+        || param(annotations, "Column", "nullable").is_some_and(|n| n == "false");
+    let length = param(annotations, "Column", "length").and_then(|l| l.parse().ok());
+    let identity = is_id
+        && find(annotations, "GeneratedValue").is_some()
+        && param(annotations, "GeneratedValue", "strategy")
+            .is_none_or(|s| s.ends_with("IDENTITY") || s.ends_with("AUTO"));
+    Column {
+        name: column_name(field),
+        sql_type: kind.column_type(&field.type_.name, length),
+        nullable: !(is_id || primitive || required),
+        unique: param(annotations, "Column", "unique").is_some_and(|u| u == "true"),
+        identity,
+        default: param(annotations, "ColumnDefault", "value"),
+    }
+}
+
+//the first quoted name in joinColumns = @JoinColumn(name = "...")
+fn join_column_name(annotations: &[Annotation], annotation: &str, columns: &str) -> Option<String> {
+    let join_columns = param(annotations, annotation, columns)?;
+    let start = join_columns.find('"')? + 1;
+    let len = join_columns[start..].find('"')?;
+    Some(join_columns[start..start + len].to_owned())
+}
+
+//values of an @ElementCollection live in their own table next to the owner
+fn element_collection(
+    entity: &JavaClass,
+    owner: &EntityKey,
+    field: &Field,
+    kind: DatabaseKind,
+) -> Table {
+    let annotations = &field.annotation;
+    let name = param(annotations, "CollectionTable", "name").unwrap_or(format!(
+        "{}_{}",
+        owner.table,
+        snake_case(&field.name)
+    ));
+    let join_column = join_column_name(annotations, "CollectionTable", "joinColumns")
+        .unwrap_or(snake_case(&entity.class_name) + "_id");
+    let value_column = column_name(field);
+    let value_type = kind.column_type(generic_type(field).unwrap_or("String"), None);
+    //a set cannot hold the same value twice
+    let primary_key = match field.type_.name.as_str() {
+        "Set" => vec![join_column.clone(), value_column.clone()],
+        _ => vec![],
+    };
+    Table {
+        foreign_keys: vec![ForeignKey {
+            name: format!("fk_{}_{}", name, join_column),
+            column: join_column.clone(),
+            references_table: owner.table.clone(),
+            references_column: owner.id_column.clone(),
+        }],
+        name,
+        columns: vec![
+            Column {
+                name: join_column,
+                sql_type: owner.id_type.clone(),
+                nullable: false,
+                unique: false,
+                identity: false,
+                default: None,
+            },
+            Column {
+                name: value_column,
+                sql_type: value_type,
+                nullable: primary_key.is_empty(),
+                unique: false,
+                identity: false,
+                default: None,
+            },
+        ],
+        primary_key,
+    }
+}
+
+//the owning side of a many to many, one row per pair
+fn join_table(entity: &JavaClass, owner: &EntityKey, target: &EntityKey, field: &Field) -> Table {
+    let annotations = &field.annotation;
+    let name = param(annotations, "JoinTable", "name").unwrap_or(format!(
+        "{}_{}",
+        owner.table,
+        snake_case(&field.name)
+    ));
+    let column = join_column_name(annotations, "JoinTable", "joinColumns")
+        .unwrap_or(snake_case(&entity.class_name) + "_id");
+    let inverse_column = join_column_name(annotations, "JoinTable", "inverseJoinColumns")
+        .unwrap_or(target.table.clone() + "_id");
+    let foreign_key = |column: &str, key: &EntityKey| ForeignKey {
+        name: format!("fk_{}_{}", name, column),
+        column: column.to_owned(),
+        references_table: key.table.clone(),
+        references_column: key.id_column.clone(),
+    };
+    let not_null = |column: &str, key: &EntityKey| Column {
+        name: column.to_owned(),
+        sql_type: key.id_type.clone(),
+        nullable: false,
+        unique: false,
+        identity: false,
+        default: None,
+    };
+    Table {
+        foreign_keys: vec![
+            foreign_key(&column, owner),
+            foreign_key(&inverse_column, target),
+        ],
+        columns: vec![not_null(&column, owner), not_null(&inverse_column, target)],
+        primary_key: vec![column, inverse_column],
+        name,
+    }
+}
+
+fn column_definition(table: &str, column: &Column, kind: DatabaseKind) -> String {
+    let sql_type = match column.identity {
+        true => kind.identity_column(&column.sql_type),
+        false => column.sql_type.clone(),
+    };
+    let default = match (&column.default, kind) {
+        (Some(value), DatabaseKind::SqlServer) => format!(
+            " CONSTRAINT {} DEFAULT {}",
+            default_name(table, &column.name),
+            default_literal(value, kind)
+        ),
+        (Some(value), _) => format!(" DEFAULT {}", default_literal(value, kind)),
+        (None, _) => "".to_owned(),
+    };
+    match column.nullable {
+        true => format!("{} {}{}", column.name, sql_type, default),
+        false => format!("{} {}{} NOT NULL", column.name, sql_type, default),
+    }
+}
+
+//booleans are numbers on oracle and sql server
+fn default_literal(value: &str, kind: DatabaseKind) -> String {
+    match (value, kind) {
+        ("true", DatabaseKind::Oracle | DatabaseKind::SqlServer) => "1".to_owned(),
+        ("false", DatabaseKind::Oracle | DatabaseKind::SqlServer) => "0".to_owned(),
+        _ => value.to_owned(),
+    }
+}
+
+fn drop_unique(table: &str, column: &str, kind: DatabaseKind) -> String {
+    match kind {
+        DatabaseKind::MySql | DatabaseKind::MariaDb => format!(
+            "ALTER TABLE {} DROP INDEX {};",
+            table,
+            unique_name(table, column)
+        ),
+        _ => format!(
+            "ALTER TABLE {} DROP CONSTRAINT {};",
+            table,
+            unique_name(table, column)
+        ),
+    }
+}
+
+fn add_unique(table: &str, column: &str) -> String {
+    format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} UNIQUE ({});",
+        table,
+        unique_name(table, column),
+        column
+    )
+}
+
+fn alter_default(table: &str, new: &Column, kind: DatabaseKind) -> Vec<String> {
+    let default = new.default.as_deref().map(|v| default_literal(v, kind));
+    match (kind, default) {
+        //the old constraint goes first, there is no changing it in place
+        (DatabaseKind::SqlServer, default) => {
+            let mut statements = vec![format!(
+                "ALTER TABLE {} DROP CONSTRAINT IF EXISTS {};",
+                table,
+                default_name(table, &new.name)
+            )];
+            if let Some(value) = default {
+                statements.push(format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} DEFAULT {} FOR {};",
+                    table,
+                    default_name(table, &new.name),
+                    value,
+                    new.name
+                ));
+            }
+            statements
+        }
+        (DatabaseKind::Oracle, default) => vec![format!(
+            "ALTER TABLE {} MODIFY ({} DEFAULT {});",
+            table,
+            new.name,
+            default.unwrap_or_else(|| "NULL".to_owned())
+        )],
+        (_, Some(value)) => vec![format!(
+            "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+            table, new.name, value
+        )],
+        (_, None) => vec![format!(
+            "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+            table, new.name
+        )],
+    }
+}
+
+fn alter_column(table: &str, old: &Column, new: &Column, kind: DatabaseKind) -> Vec<String> {
+    let mut statements = vec![];
+    let type_changed = old.sql_type != new.sql_type;
+    let null_changed = old.nullable != new.nullable;
+    let default_changed = old.default != new.default;
+    let null = match new.nullable {
+        true => "NULL",
+        false => "NOT NULL",
+    };
+    if old.unique && !new.unique {
+        statements.push(drop_unique(table, &new.name, kind));
+    }
+    match kind {
+        DatabaseKind::PostgreSql | DatabaseKind::H2 => {
+            let set_type = match kind {
+                DatabaseKind::H2 => "SET DATA TYPE",
+                _ => "TYPE",
+            };
+            if type_changed {
+                statements.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} {} {};",
+                    table, new.name, set_type, new.sql_type
+                ));
+            }
+            if null_changed {
+                let action = match new.nullable {
+                    true => "DROP NOT NULL",
+                    false => "SET NOT NULL",
+                };
+                statements.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} {};",
+                    table, new.name, action
+                ));
+            }
+        }
+        //the whole column is declared again
+        DatabaseKind::MySql | DatabaseKind::MariaDb if type_changed || null_changed => statements
+            .push(format!(
+                "ALTER TABLE {} MODIFY {};",
+                table,
+                column_definition(table, new, kind)
+            )),
+        DatabaseKind::SqlServer if type_changed || null_changed => statements.push(format!(
+            "ALTER TABLE {} ALTER COLUMN {} {} {};",
+            table, new.name, new.sql_type, null
+        )),
+        //oracle refuses a NOT NULL the column already has
+        DatabaseKind::Oracle if type_changed || null_changed => {
+            let null = match null_changed {
+                true => format!(" {}", null),
+                false => "".to_owned(),
+            };
+            statements.push(format!(
+                "ALTER TABLE {} MODIFY ({} {}{});",
+                table, new.name, new.sql_type, null
+            ))
+        }
+        _ => {}
+    }
+    //mysql declared the whole column again, default included
+    let redeclared = matches!(kind, DatabaseKind::MySql | DatabaseKind::MariaDb)
+        && (type_changed || null_changed);
+    if default_changed && !redeclared {
+        statements.extend(alter_default(table, new, kind));
+    }
+    if new.unique && !old.unique {
+        statements.push(add_unique(table, &new.name));
+    }
+    statements
+}
+
+//a flyway script, statements are separated by blank lines
+pub fn sql(changes: &[SchemaChange], kind: DatabaseKind) -> String {
+    let mut statements: Vec<String> = vec![];
+    for change in changes.iter() {
+        match change {
+            SchemaChange::CreateTable(table) => {
+                let mut lines: Vec<String> = table
+                    .columns
+                    .iter()
+                    .map(|c| column_definition(&table.name, c, kind))
+                    .collect();
+                if !table.primary_key.is_empty() {
+                    lines.push(format!("PRIMARY KEY ({})", table.primary_key.join(", ")));
+                }
+                for column in table.columns.iter().filter(|c| c.unique) {
+                    lines.push(format!(
+                        "CONSTRAINT {} UNIQUE ({})",
+                        unique_name(&table.name, &column.name),
+                        column.name
+                    ));
+                }
+                statements.push(format!(
+                    "CREATE TABLE {} (\n    {}\n);",
+                    table.name,
+                    lines.join(",\n    ")
+                ));
+            }
+            SchemaChange::DropTable(table) => statements.push(format!("DROP TABLE {};", table)),
+            SchemaChange::AddColumn(table, column) => {
+                statements.push(format!(
+                    "ALTER TABLE {} ADD {};",
+                    table,
+                    column_definition(table, column, kind)
+                ));
+                if column.unique {
+                    statements.push(add_unique(table, &column.name));
+                }
+            }
+            SchemaChange::DropColumn(table, column) => {
+                //sql server does not drop the constraints of a column with it
+                if column.unique {
+                    statements.push(drop_unique(table, &column.name, kind));
+                }
+                statements.push(format!(
+                    "ALTER TABLE {} DROP COLUMN {};",
+                    table, column.name
+                ));
+            }
+            SchemaChange::AlterColumn(table, old, new) => {
+                statements.extend(alter_column(table, old, new, kind))
+            }
+            SchemaChange::AddForeignKey(table, fk) => statements.push(format!(
+                "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({});",
+                table, fk.name, fk.column, fk.references_table, fk.references_column
+            )),
+            SchemaChange::DropForeignKey(table, fk) => {
+                let drop = match kind {
+                    DatabaseKind::MySql | DatabaseKind::MariaDb => "FOREIGN KEY",
+                    _ => "CONSTRAINT",
+                };
+                statements.push(format!("ALTER TABLE {} DROP {} {};", table, drop, fk.name))
+            }
+            //mysql has no sequences, hibernate keeps the next value in a table
+            SchemaChange::CreateSequence(sequence) => match kind {
+                DatabaseKind::MySql => {
+                    statements.push(format!("CREATE TABLE {} (next_val BIGINT);", sequence.name));
+                    statements.push(format!("INSERT INTO {} VALUES (1);", sequence.name));
+                }
+                _ => statements.push(format!(
+                    "CREATE SEQUENCE {} START WITH 1 INCREMENT BY {};",
+                    sequence.name, sequence.increment
+                )),
+            },
+            SchemaChange::DropSequence(name) => match kind {
+                DatabaseKind::MySql => statements.push(format!("DROP TABLE {};", name)),
+                _ => statements.push(format!("DROP SEQUENCE {};", name)),
+            },
+        }
+    }
+    statements.join("\n\n") + "\n"
+}
+
+fn default_attribute(value: &str) -> &'static str {
+    match value {
+        "true" | "false" => "defaultValueBoolean",
+        v if v.parse::<f64>().is_ok() => "defaultValueNumeric",
+        _ => "defaultValueComputed",
+    }
+}
+
+fn liquibase_column(column: &Column, table: &Table) -> XmlElement {
+    let mut element = XmlElement::new("column")
+        .attr("name", &column.name)
+        .attr("type", &column.sql_type);
+    if column.identity {
+        element = element.attr("autoIncrement", "true");
+    }
+    if let Some(value) = &column.default {
+        element = element.attr(default_attribute(value), value);
+    }
+    let mut constraints = XmlElement::new("constraints");
+    if table.primary_key.contains(&column.name) {
+        constraints = constraints
+            .attr("primaryKey", "true")
+            .attr("primaryKeyName", &format!("pk_{}", table.name));
+    }
+    if !column.nullable {
+        constraints = constraints.attr("nullable", "false");
+    }
+    if column.unique {
+        constraints = constraints.attr("unique", "true").attr(
+            "uniqueConstraintName",
+            &unique_name(&table.name, &column.name),
+        );
+    }
+    match constraints.attributes.is_empty() {
+        true => element,
+        false => element.child(constraints),
+    }
+}
+
+fn liquibase_changes(change: &SchemaChange) -> Vec<XmlElement> {
+    let unique = |table: &str, column: &str| {
+        XmlElement::new("addUniqueConstraint")
+            .attr("tableName", table)
+            .attr("columnNames", column)
+            .attr("constraintName", &unique_name(table, column))
+    };
+    let drop_unique = |table: &str, column: &str| {
+        XmlElement::new("dropUniqueConstraint")
+            .attr("tableName", table)
+            .attr("constraintName", &unique_name(table, column))
+    };
+    match change {
+        SchemaChange::CreateTable(table) => {
+            let mut element = XmlElement::new("createTable").attr("tableName", &table.name);
+            for column in table.columns.iter() {
+                element = element.child(liquibase_column(column, table));
+            }
+            vec![element]
+        }
+        SchemaChange::DropTable(table) => {
+            vec![XmlElement::new("dropTable").attr("tableName", table)]
+        }
+        SchemaChange::AddColumn(table, column) => {
+            let holder = Table {
+                name: table.clone(),
+                columns: vec![],
+                primary_key: vec![],
+                foreign_keys: vec![],
+            };
+            vec![XmlElement::new("addColumn")
+                .attr("tableName", table)
+                .child(liquibase_column(column, &holder))]
+        }
+        SchemaChange::DropColumn(table, column) => {
+            let mut elements = vec![];
+            if column.unique {
+                elements.push(drop_unique(table, &column.name));
+            }
+            elements.push(
+                XmlElement::new("dropColumn")
+                    .attr("tableName", table)
+                    .attr("columnName", &column.name),
+            );
+            elements
+        }
+        SchemaChange::AlterColumn(table, old, new) => {
+            let mut elements = vec![];
+            if old.unique && !new.unique {
+                elements.push(drop_unique(table, &new.name));
+            }
+            if old.sql_type != new.sql_type {
+                elements.push(
+                    XmlElement::new("modifyDataType")
+                        .attr("tableName", table)
+                        .attr("columnName", &new.name)
+                        .attr("newDataType", &new.sql_type),
+                );
+            }
+            if old.nullable != new.nullable {
+                let name = match new.nullable {
+                    true => "dropNotNullConstraint",
+                    false => "addNotNullConstraint",
+                };
+                elements.push(
+                    XmlElement::new(name)
+                        .attr("tableName", table)
+                        .attr("columnName", &new.name)
+                        .attr("columnDataType", &new.sql_type),
+                );
+            }
+            //sql server keeps the old default as a constraint, it is dropped first
+            if old.default.is_some() && old.default != new.default {
+                elements.push(
+                    XmlElement::new("dropDefaultValue")
+                        .attr("tableName", table)
+                        .attr("columnName", &new.name)
+                        .attr("columnDataType", &new.sql_type),
+                );
+            }
+            if let Some(value) = new.default.as_ref().filter(|_| old.default != new.default) {
+                elements.push(
+                    XmlElement::new("addDefaultValue")
+                        .attr("tableName", table)
+                        .attr("columnName", &new.name)
+                        .attr("columnDataType", &new.sql_type)
+                        .attr(default_attribute(value), value),
+                );
+            }
+            if new.unique && !old.unique {
+                elements.push(unique(table, &new.name));
+            }
+            elements
+        }
+        SchemaChange::AddForeignKey(table, fk) => vec![XmlElement::new("addForeignKeyConstraint")
+            .attr("constraintName", &fk.name)
+            .attr("baseTableName", table)
+            .attr("baseColumnNames", &fk.column)
+            .attr("referencedTableName", &fk.references_table)
+            .attr("referencedColumnNames", &fk.references_column)],
+        SchemaChange::DropForeignKey(table, fk) => {
+            vec![XmlElement::new("dropForeignKeyConstraint")
+                .attr("baseTableName", table)
+                .attr("constraintName", &fk.name)]
+        }
+        SchemaChange::CreateSequence(sequence) => vec![XmlElement::new("createSequence")
+            .attr("sequenceName", &sequence.name)
+            .attr("startValue", "1")
+            .attr("incrementBy", &sequence.increment.to_string())],
+        SchemaChange::DropSequence(name) => {
+            vec![XmlElement::new("dropSequence").attr("sequenceName", name)]
+        }
+    }
+}
+
+fn changelog_root() -> XmlElement {
+    XmlElement::new("databaseChangeLog")
+        .attr("xmlns", "http://www.liquibase.org/xml/ns/dbchangelog")
+        .attr("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance")
+        .attr(
+            "xsi:schemaLocation",
+            "http://www.liquibase.org/xml/ns/dbchangelog https://www.liquibase.org/xml/ns/dbchangelog/dbchangelog-latest.xsd",
+        )
+}
+
+fn xml_document(root: XmlElement) -> String {
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned() + "\n" + &root.render()
+}
+
+//one changeset per generation
+pub fn changelog(changes: &[SchemaChange], id: &str) -> String {
+    let mut change_set = XmlElement::new("changeSet")
+        .attr("id", id)
+        .attr("author", "codegen");
+    for change in changes.iter() {
+        for element in liquibase_changes(change) {
+            change_set = change_set.child(element);
+        }
+    }
+    xml_document(changelog_root().child(change_set))
+}
+
+//the number in front of the file names a tool has already written
+fn highest_version(dir: &Path, tool: MigrationTool) -> u32 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let digits = match tool {
+                MigrationTool::Flyway => name.strip_prefix('V')?.split("__").next()?.to_owned(),
+                MigrationTool::Liquibase => name.split('-').next()?.to_owned(),
+            };
+            digits.parse::<u32>().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+impl MigrationTool {
+    //spring boot runs the migrations on startup once these are on the classpath
+    pub fn dependencies(&self, kind: DatabaseKind) -> Vec<Library> {
+        match self {
+            MigrationTool::Flyway => {
+                let mut libraries = vec![Library::new(
+                    "org.flywaydb".to_owned(),
+                    "flyway-core".to_owned(),
+                )];
+                libraries.extend(kind.flyway_module());
+                libraries
+            }
+            MigrationTool::Liquibase => vec![Library::new(
+                "org.liquibase".to_owned(),
+                "liquibase-core".to_owned(),
+            )],
+        }
+    }
+
+    pub fn properties(&self) -> Vec<(String, String)> {
+        match self {
+            MigrationTool::Flyway => vec![],
+            MigrationTool::Liquibase => vec![(
+                "spring.liquibase.change-log".to_owned(),
+                "classpath:db/changelog/db.changelog-master.xml".to_owned(),
+            )],
+        }
+    }
+
+    fn migrations_dir(&self, resources: &Path) -> PathBuf {
+        match self {
+            MigrationTool::Flyway => resources.join("db").join("migration"),
+            MigrationTool::Liquibase => resources.join("db").join("changelog").join("changes"),
+        }
+    }
+
+    //the files that take a database from previous to current, nothing when they match.
+    //paths are inside the resources folder
+    pub fn migration_files(
+        &self,
+        resources: &Path,
+        previous: &Schema,
+        current: &Schema,
+        kind: DatabaseKind,
+    ) -> Vec<(PathBuf, String)> {
+        let changes = previous.diff(current);
+        if changes.is_empty() {
+            return vec![];
+        }
+        let dir = self.migrations_dir(resources);
+        let version = highest_version(&dir, *self) + 1;
+        let description = match previous.tables.is_empty() {
+            true => "create_schema",
+            false => "update_schema",
+        };
+        match self {
+            MigrationTool::Flyway => vec![(
+                dir.join(format!("V{}__{}.sql", version, description)),
+                sql(&changes, kind),
+            )],
+            MigrationTool::Liquibase => {
+                let mut files = vec![(
+                    dir.join(format!(
+                        "{:03}-{}.xml",
+                        version,
+                        description.replace('_', "-")
+                    )),
+                    changelog(&changes, &format!("{:03}-{}", version, description)),
+                )];
+                let master = resources
+                    .join("db")
+                    .join("changelog")
+                    .join("db.changelog-master.xml");
+                if !master.exists() {
+                    files.push((
+                        master,
+                        xml_document(
+                            changelog_root().child(
+                                XmlElement::new("includeAll")
+                                    .attr("path", "changes/")
+                                    .attr("relativeToChangelogFile", "true"),
+                            ),
+                        ),
+                    ));
+                }
+                files
+            }
+        }
+    }
+
+    //scripts written without a snapshot cannot be diffed against, the next
+    //generation would create the tables again
+    pub fn has_unknown_migrations(&self, resources: &Path) -> bool {
+        highest_version(&self.migrations_dir(resources), *self) > 0
+    }
+}
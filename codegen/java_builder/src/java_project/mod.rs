@@ -1,14 +1,33 @@
-//check what build systems are available
-//implement a java project builder (for now only maven with spring boot codegen)
+//build systems: maven (pom.xml) and gradle (build.gradle.kts or build.gradle)
+//implement a java project builder (for now only spring boot codegen)
 //select:
 // - libraries
 // - general options that have sensible defaults (model folder,docs folder, services folder etc. etc.)
 // - extras: CRUDs + Search
 mod spring_packages;
 
+pub mod catalog;
+pub mod ci;
+pub mod config;
 pub mod crud_builder;
+pub mod database;
+pub mod docker;
+pub mod error;
+pub mod exception_handling;
+pub mod gradle;
+pub mod gradle_builder;
+pub mod ids;
+pub mod local_repository;
 pub mod maven_builder;
+pub mod migrations;
+pub mod modules;
 pub mod output;
 pub mod pom_xml;
+pub mod queries;
+pub mod relationships;
+pub mod security;
+pub mod test_suites;
+pub mod validation;
+pub mod xml;
 
 // pub mod docs_builder {}
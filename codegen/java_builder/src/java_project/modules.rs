@@ -0,0 +1,137 @@
+use super::pom_xml::{Library, PomXml};
+
+//the libraries the non web modules need to compile entities, dtos and repositories
+const DATA_LIBRARIES: [&str; 3] = [
+    "spring-boot-starter-data-jpa",
+    "lombok",
+    "spring-boot-starter-validation",
+];
+
+//the mapstruct mappers are next to the dtos in the api module
+const MAPPER_LIBRARIES: [&str; 3] = [
+    "mapstruct",
+    "mapstruct-processor",
+    "lombok-mapstruct-binding",
+];
+
+//which module each generated layer ends up in
+//  api:         dtos and their mappers
+//  domain:      entities
+//  persistence: jpa repositories
+//  web:         services, controllers, the main class and application.properties
+#[derive(Clone, Debug)]
+pub struct ModuleLayout {
+    pub api: String,
+    pub domain: String,
+    pub persistence: String,
+    pub web: String,
+}
+
+impl Default for ModuleLayout {
+    fn default() -> Self {
+        Self {
+            api: "api".to_owned(),
+            domain: "domain".to_owned(),
+            persistence: "persistence".to_owned(),
+            web: "web".to_owned(),
+        }
+    }
+}
+
+impl ModuleLayout {
+    pub fn new(api: String, domain: String, persistence: String, web: String) -> Self {
+        Self {
+            api,
+            domain,
+            persistence,
+            web,
+        }
+    }
+
+    //in the order maven should build them
+    pub fn modules(&self) -> Vec<&String> {
+        vec![&self.domain, &self.api, &self.persistence, &self.web]
+    }
+
+    fn module_dependencies(&self, module: &str) -> Vec<&String> {
+        if module == self.api || module == self.persistence {
+            vec![&self.domain]
+        } else if module == self.web {
+            vec![&self.api, &self.persistence]
+        } else {
+            vec![]
+        }
+    }
+
+    pub fn module_artifact_id(&self, pom_xml: &PomXml, module: &str) -> String {
+        pom_xml.project_info.artifact_id.clone() + "-" + module
+    }
+
+    fn module_library(&self, pom_xml: &PomXml, module: &str) -> Library {
+        Library::new(
+            pom_xml.project_info.group_id.clone(),
+            self.module_artifact_id(pom_xml, module),
+        )
+    }
+
+    //the root pom only lists the modules and manages the versions they use
+    pub fn aggregator_pom(&self, pom_xml: &PomXml) -> PomXml {
+        let mut aggregator = pom_xml.clone();
+        aggregator.dependencies = vec![];
+        //a repackaging plugin in the root would turn every module into a fat jar
+        aggregator.plugins = vec![];
+        aggregator = aggregator.packaging("pom".to_owned());
+        for module in self.modules() {
+            aggregator = aggregator.module(module.clone());
+            aggregator = aggregator.managed_dependency(Library::new_with_version(
+                pom_xml.project_info.group_id.clone(),
+                self.module_artifact_id(pom_xml, module),
+                "${project.version}".to_owned(),
+            ));
+        }
+        for lib in pom_xml
+            .dependencies
+            .iter()
+            .filter(|l| l.version().is_some())
+        {
+            aggregator = aggregator.managed_dependency(lib.clone());
+        }
+        aggregator
+    }
+
+    pub fn module_pom(&self, pom_xml: &PomXml, module: &str) -> PomXml {
+        let aggregator = self.aggregator_pom(pom_xml);
+        let info = &pom_xml.project_info;
+        let mut child = PomXml::new(info.clone())
+            .java_version(pom_xml.java.clone())
+            .artifact(self.module_artifact_id(pom_xml, module))
+            .project_name(info.name.clone() + " " + module)
+            .parent(Library::new_with_version(
+                info.group_id.clone(),
+                info.artifact_id.clone(),
+                info.version_or_default().to_owned(),
+            ));
+        for dependency in self.module_dependencies(module) {
+            child
+                .dependencies
+                .push(self.module_library(pom_xml, dependency));
+        }
+        let libraries = pom_xml.dependencies.iter().filter(|l| {
+            module == self.web
+                || DATA_LIBRARIES.contains(&l.artifact_id.as_str())
+                || (module == self.api && MAPPER_LIBRARIES.contains(&l.artifact_id.as_str()))
+        });
+        for lib in libraries {
+            //versions come from the dependencyManagement of the parent
+            child.dependencies.push(if aggregator.is_managed(lib) {
+                lib.unversioned()
+            } else {
+                lib.clone()
+            });
+        }
+        if module == self.web {
+            child.plugins = pom_xml.plugins.clone();
+        }
+        child
+    }
+}
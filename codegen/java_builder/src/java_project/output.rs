@@ -1,13 +1,280 @@
 use std::{
-    fs::{self, create_dir, create_dir_all, DirEntry, File},
+    fs::{self, create_dir_all, DirEntry, File},
     io::{Read, Seek, Write},
     path::{Path, PathBuf},
 };
 
-use zip::{result::ZipError, write::SimpleFileOptions};
+use zip::write::SimpleFileOptions;
 
+use super::error::CodegenError;
 use crate::{classes::JavaClass, interfaces::Interface, Codegen};
 
+//the kind of classes that get generated, used to find out where they are written
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layer {
+    Models,
+    Repositories,
+    Services,
+    Controllers,
+    Dtos,
+    //main class, resources and tests
+    Application,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Packaging {
+    //controllers/CustomerController, repositories/CustomerRepository
+    #[default]
+    ByLayer,
+    //customer/CustomerController, customer/CustomerRepository
+    ByFeature,
+}
+
+//keywords and literals, java does not take them as a part of a package name
+const RESERVED_WORDS: [&str; 54] = [
+    "_",
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+//Package goes to package_, the way the java naming conventions suggest
+fn package_name(entity: &str) -> String {
+    let name = entity.to_lowercase();
+    if RESERVED_WORDS.contains(&name.as_str()) {
+        name + "_"
+    } else {
+        name
+    }
+}
+
+//where the generated classes go and what they are called
+#[derive(Clone, Debug)]
+pub struct OutputConfig {
+    pub packaging: Packaging,
+    //sub packages of the root package, only used when packaging by layer
+    pub models_package: String,
+    pub repositories_package: String,
+    pub services_package: String,
+    pub controllers_package: String,
+    pub dtos_package: String,
+    pub security_package: String,
+    pub errors_package: String,
+    //appended to the name of the entity
+    pub repository_suffix: String,
+    pub service_suffix: String,
+    pub controller_suffix: String,
+    pub request_suffix: String,
+    pub response_suffix: String,
+    pub mapper_suffix: String,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            packaging: Packaging::default(),
+            models_package: "models".to_owned(),
+            repositories_package: "repositories".to_owned(),
+            services_package: "services".to_owned(),
+            controllers_package: "controllers".to_owned(),
+            dtos_package: "dto".to_owned(),
+            security_package: "security".to_owned(),
+            errors_package: "errors".to_owned(),
+            repository_suffix: "Repository".to_owned(),
+            service_suffix: "Service".to_owned(),
+            controller_suffix: "Controller".to_owned(),
+            request_suffix: "Request".to_owned(),
+            response_suffix: "Response".to_owned(),
+            mapper_suffix: "Mapper".to_owned(),
+        }
+    }
+}
+
+impl OutputConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn packaging(mut self, packaging: Packaging) -> Self {
+        self.packaging = packaging;
+        self
+    }
+
+    pub fn models_package(mut self, package: String) -> Self {
+        self.models_package = package;
+        self
+    }
+
+    pub fn repositories_package(mut self, package: String) -> Self {
+        self.repositories_package = package;
+        self
+    }
+
+    pub fn services_package(mut self, package: String) -> Self {
+        self.services_package = package;
+        self
+    }
+
+    pub fn controllers_package(mut self, package: String) -> Self {
+        self.controllers_package = package;
+        self
+    }
+
+    pub fn dtos_package(mut self, package: String) -> Self {
+        self.dtos_package = package;
+        self
+    }
+
+    pub fn security_package(mut self, package: String) -> Self {
+        self.security_package = package;
+        self
+    }
+
+    pub fn errors_package(mut self, package: String) -> Self {
+        self.errors_package = package;
+        self
+    }
+
+    pub fn repository_suffix(mut self, suffix: String) -> Self {
+        self.repository_suffix = suffix;
+        self
+    }
+
+    pub fn service_suffix(mut self, suffix: String) -> Self {
+        self.service_suffix = suffix;
+        self
+    }
+
+    pub fn controller_suffix(mut self, suffix: String) -> Self {
+        self.controller_suffix = suffix;
+        self
+    }
+
+    pub fn request_suffix(mut self, suffix: String) -> Self {
+        self.request_suffix = suffix;
+        self
+    }
+
+    pub fn response_suffix(mut self, suffix: String) -> Self {
+        self.response_suffix = suffix;
+        self
+    }
+
+    pub fn mapper_suffix(mut self, suffix: String) -> Self {
+        self.mapper_suffix = suffix;
+        self
+    }
+
+    //the package of a class generated for the entity, e.g. org.example.app.customer
+    pub fn package_of(&self, root_package: &str, layer: Layer, entity: &str) -> String {
+        let sub_package = match (self.packaging, layer) {
+            (_, Layer::Application) => return root_package.to_owned(),
+            (Packaging::ByFeature, _) => package_name(entity),
+            (Packaging::ByLayer, Layer::Models) => self.models_package.clone(),
+            (Packaging::ByLayer, Layer::Repositories) => self.repositories_package.clone(),
+            (Packaging::ByLayer, Layer::Services) => self.services_package.clone(),
+            (Packaging::ByLayer, Layer::Controllers) => self.controllers_package.clone(),
+            (Packaging::ByLayer, Layer::Dtos) => self.dtos_package.clone(),
+        };
+        [root_package, &sub_package].join(".")
+    }
+
+    //the folder a layer is written to, the root of the sources when packaging by feature
+    pub fn layer_folder(&self, layer: Layer) -> String {
+        match (self.packaging, layer) {
+            (Packaging::ByFeature, _) | (_, Layer::Application) => "".to_owned(),
+            (_, Layer::Models) => self.models_package.replace('.', "/"),
+            (_, Layer::Repositories) => self.repositories_package.replace('.', "/"),
+            (_, Layer::Services) => self.services_package.replace('.', "/"),
+            (_, Layer::Controllers) => self.controllers_package.replace('.', "/"),
+            (_, Layer::Dtos) => self.dtos_package.replace('.', "/"),
+        }
+    }
+
+    pub fn repository_name(&self, entity: &str) -> String {
+        entity.to_owned() + &self.repository_suffix
+    }
+
+    pub fn service_name(&self, entity: &str) -> String {
+        entity.to_owned() + &self.service_suffix
+    }
+
+    pub fn controller_name(&self, entity: &str) -> String {
+        entity.to_owned() + &self.controller_suffix
+    }
+
+    pub fn request_name(&self, entity: &str) -> String {
+        entity.to_owned() + &self.request_suffix
+    }
+
+    pub fn response_name(&self, entity: &str) -> String {
+        entity.to_owned() + &self.response_suffix
+    }
+
+    pub fn mapper_name(&self, entity: &str) -> String {
+        entity.to_owned() + &self.mapper_suffix
+    }
+}
+
+//a java file that is about to be written
+pub struct CompilationUnit {
+    pub path: PathBuf,
+    //name of the top level type, used when reporting errors
+    pub element: String,
+    pub code: String,
+}
+
+#[derive(Clone)]
 pub struct OutputDirs {
     package_path: String,
     output_dir: String,
@@ -41,28 +308,74 @@ impl OutputDirs {
         }
     }
 
-    pub fn generate_classes_in(&self, classes: &[JavaClass], folder: &str) {
-        classes.iter().for_each(|cls| {
-            let mut path = PathBuf::from(folder);
-            path.push(&cls.class_name);
-            path.set_extension("java");
+    //same layout inside a child module of a multi-module project
+    pub fn in_module(&self, module: &str) -> OutputDirs {
+        let module_dir = Path::new(&self.output_dir).join(module);
+        OutputDirs::new(
+            module_dir.to_string_lossy().into_owned(),
+            self.package_path.clone(),
+        )
+        .models(self.models_suffix.clone())
+        .controllers(self.controllers_suffix.clone())
+        .services(self.services_suffix.clone())
+        .repos(self.repos_suffix.clone())
+        .dtos(self.dtos_suffix.clone())
+    }
 
-            match fs::write(path.as_path(), cls.generate_code()) {
-                Ok(r) => println!("Entities were successfully generated"),
-                Err(e) => println!("An error occurred when generating entities {}", e),
-            }
-        });
-    }
-    pub fn generate_interfaces_in(&self, interfaces: &[Interface], folder: &str) {
-        interfaces.into_iter().for_each(|cls| {
-            let mut path = PathBuf::from(folder);
-            path.push(&cls.name);
-            path.set_extension("java");
-            match fs::write(path, cls.generate_code()) {
-                Ok(r) => println!("Entities were successfully generated"),
-                Err(e) => println!("An error occurred when generating entities {}", e),
+    pub fn generate_classes_in(
+        &self,
+        classes: &[JavaClass],
+        folder: &str,
+    ) -> Result<(), CodegenError> {
+        self.write_units(&Self::class_units(classes, Path::new(folder)))
+    }
+    pub fn generate_interfaces_in(
+        &self,
+        interfaces: &[Interface],
+        folder: &str,
+    ) -> Result<(), CodegenError> {
+        self.write_units(&Self::interface_units(interfaces, Path::new(folder)))
+    }
+
+    //<folder>/<name>.java
+    pub fn unit_path(folder: &Path, name: &str) -> PathBuf {
+        let mut path = folder.to_path_buf();
+        path.push(name);
+        path.set_extension("java");
+        path
+    }
+
+    pub fn class_units(classes: &[JavaClass], folder: &Path) -> Vec<CompilationUnit> {
+        classes
+            .iter()
+            .map(|cls| CompilationUnit {
+                path: Self::unit_path(folder, &cls.class_name),
+                element: cls.class_name.clone(),
+                code: cls.generate_code(),
+            })
+            .collect()
+    }
+
+    pub fn interface_units(interfaces: &[Interface], folder: &Path) -> Vec<CompilationUnit> {
+        interfaces
+            .iter()
+            .map(|i| CompilationUnit {
+                path: Self::unit_path(folder, &i.name),
+                element: i.name.clone(),
+                code: i.generate_code(),
+            })
+            .collect()
+    }
+
+    pub fn write_units(&self, units: &[CompilationUnit]) -> Result<(), CodegenError> {
+        for unit in units.iter() {
+            if let Some(parent) = unit.path.parent() {
+                create_dir_all(parent).map_err(|e| CodegenError::io(parent, e))?;
             }
-        });
+            fs::write(&unit.path, &unit.code).map_err(|e| CodegenError::io(&unit.path, e))?;
+            log::debug!("{} was successfully generated", unit.element);
+        }
+        Ok(())
     }
     pub fn dtos(mut self, suffix: String) -> Self {
         self.dtos_suffix = suffix;
@@ -89,31 +402,49 @@ impl OutputDirs {
         &self.code_folder
     }
 
-    fn code_folder_str(&self) -> &str {
-        self.code_folder.to_str().unwrap()
+    //the folder of a package under the root package, e.g. org.example.app.customer
+    pub fn package_folder(&self, package: &str) -> PathBuf {
+        self.sub_package_folder(self.code_folder.clone(), package)
+    }
+
+    //the same package under src/test/java
+    pub fn test_package_folder(&self, package: &str) -> PathBuf {
+        self.sub_package_folder(self.tests_folder(), package)
+    }
+
+    fn sub_package_folder(&self, mut folder: PathBuf, package: &str) -> PathBuf {
+        let sub_package = package
+            .strip_prefix(&self.package_path)
+            .unwrap_or(package)
+            .trim_start_matches('.');
+        for pth in sub_package.split('.').filter(|p| !p.is_empty()) {
+            folder.push(pth);
+        }
+        folder
     }
+
     pub fn controllers_folder(&self) -> PathBuf {
-        let mut controllers_folder = PathBuf::from(self.code_folder_str());
+        let mut controllers_folder = self.code_folder.clone();
         controllers_folder.push(&self.controllers_suffix);
         controllers_folder
     }
     pub fn models_folder(&self) -> PathBuf {
-        let mut controllers_folder = PathBuf::from(self.code_folder_str());
+        let mut controllers_folder = self.code_folder.clone();
         controllers_folder.push(&self.models_suffix);
         controllers_folder
     }
     pub fn services_folder(&self) -> PathBuf {
-        let mut controllers_folder = PathBuf::from(self.code_folder_str());
+        let mut controllers_folder = self.code_folder.clone();
         controllers_folder.push(&self.services_suffix);
         controllers_folder
     }
     pub fn repos_folder(&self) -> PathBuf {
-        let mut controllers_folder = PathBuf::from(self.code_folder_str());
+        let mut controllers_folder = self.code_folder.clone();
         controllers_folder.push(&self.repos_suffix);
         controllers_folder
     }
     pub fn dtos_folder(&self) -> PathBuf {
-        let mut controllers_folder = PathBuf::from(self.code_folder_str());
+        let mut controllers_folder = self.code_folder.clone();
         controllers_folder.push(&self.dtos_suffix);
         controllers_folder
     }
@@ -139,46 +470,36 @@ impl OutputDirs {
         res_folder.push("resources");
         res_folder
     }
-    pub fn extract_to_zip(&self) -> PathBuf {
+    pub fn extract_to_zip(&self) -> Result<PathBuf, CodegenError> {
         let output_path = Path::new("generated-new.zip");
         let files = find_files_in_dir_recursive(Path::new(&self.output_dir));
-        let new_file = File::create(output_path).unwrap();
-        let _ = zip_dir(
+        let new_file = File::create(output_path).map_err(|e| CodegenError::io(output_path, e))?;
+        zip_dir(
             &mut files.into_iter(),
             &self.output_dir,
             new_file,
             zip::CompressionMethod::Bzip2,
-        );
-        output_path.to_owned()
-    }
-
-    pub fn create_folders(&self) {
-        //the order those folders are being created matters,
-        //create_dir_all fails if any of the parents exist
-        match create_dir_all(&self.code_folder()) {
-            Ok(r) => println!("Created {:?} folder successfully", &self.code_folder()),
-            Err(e) => assert!(
-                false,
-                "Failed to create {:?} folder\nError: {e},exiting",
-                &self.code_folder()
-            ),
-        };
+        )?;
+        Ok(output_path.to_owned())
+    }
+
+    //existing folders are left as they are so a project can be regenerated in place
+    pub fn create_folders(&self) -> Result<(), CodegenError> {
         create_dirs_for(vec![
+            self.code_folder(),
             &self.repos_folder(),
             &self.models_folder(),
             &self.services_folder(),
             &self.controllers_folder(),
             &self.dtos_folder(),
             &self.resources_folder(),
-        ]);
-        match create_dir_all(&self.tests_folder()) {
-            Ok(r) => println!("Created {:?} folder successfully", &self.tests_folder()),
-            Err(e) => assert!(
-                false,
-                "Failed to create {:?} folder\nError: {e},exiting",
-                &self.tests_folder()
-            ),
-        };
+            &self.tests_folder(),
+        ])
+    }
+
+    //only the source roots, the layer folders are created when something is written in them
+    pub fn create_source_folders(&self) -> Result<(), CodegenError> {
+        create_dirs_for(vec![self.code_folder(), &self.tests_folder()])
     }
 
     fn controllers_suffix(&self) -> &str {
@@ -202,10 +523,21 @@ impl OutputDirs {
     }
 }
 
-fn create_dirs_for(dirs: Vec<&PathBuf>) {
-    dirs.iter().for_each(|d| {
-        create_dir_of(d);
-    });
+//writes a file that is not java code e.g. pom.xml, creating the missing parent folders
+pub fn write_file(path: &Path, contents: &str) -> Result<(), CodegenError> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|e| CodegenError::io(parent, e))?;
+    }
+    fs::write(path, contents).map_err(|e| CodegenError::io(path, e))?;
+    log::debug!("Wrote {:?}", path);
+    Ok(())
+}
+
+fn create_dirs_for(dirs: Vec<&PathBuf>) -> Result<(), CodegenError> {
+    for d in dirs {
+        create_dir_of(d)?;
+    }
+    Ok(())
 }
 fn find_files_in_dir_recursive(path: impl AsRef<Path>) -> Vec<DirEntry> {
     let Ok(entries) = fs::read_dir(path) else {
@@ -233,7 +565,7 @@ fn zip_dir<T, U: Iterator<Item = DirEntry>>(
     prefix: &str,
     writer: T,
     method: zip::CompressionMethod,
-) -> Result<(), ZipError>
+) -> Result<(), CodegenError>
 where
     T: std::io::Write + Seek,
 {
@@ -246,18 +578,31 @@ where
     let prefix = Path::new(prefix);
     for entry in it {
         let path = entry.path();
-        let name = path.strip_prefix(prefix).unwrap();
-        let path_as_str = name.to_str().map(str::to_owned).unwrap();
+        let Ok(name) = path.strip_prefix(prefix) else {
+            return Err(CodegenError::Unsupported(format!(
+                "{} is not inside {}",
+                path.display(),
+                prefix.display()
+            )));
+        };
+        let Some(path_as_str) = name.to_str().map(str::to_owned) else {
+            return Err(CodegenError::Unsupported(format!(
+                "non UTF-8 path {}",
+                path.display()
+            )));
+        };
         if path.is_file() {
-            println!("adding file {:?} as {:?}", path, name);
+            log::debug!("adding file {:?} as {:?}", path, name);
             zip.start_file_from_path(name, options)?;
-            let mut f = File::open(path)?;
+            let mut f = File::open(&path).map_err(|e| CodegenError::io(&path, e))?;
 
-            f.read_to_end(&mut buffer)?;
-            zip.write(&buffer)?;
+            f.read_to_end(&mut buffer)
+                .map_err(|e| CodegenError::io(&path, e))?;
+            zip.write_all(&buffer)
+                .map_err(|e| CodegenError::io(&path, e))?;
             buffer.clear();
         } else if !name.as_os_str().is_empty() {
-            println!("adding dir {path_as_str:?} as {name:?} ...");
+            log::debug!("adding dir {path_as_str:?} as {name:?} ...");
             zip.add_directory(path_as_str, options)?;
         }
     }
@@ -265,13 +610,8 @@ where
     Ok(())
 }
 
-fn create_dir_of(folder: &PathBuf) {
-    match create_dir(folder) {
-        Ok(r) => println!("Created {:?} folder successfully", folder),
-        Err(e) => assert!(
-            false,
-            "Failed to create {:?} folder\nError: {e},exiting",
-            folder
-        ),
-    }
+fn create_dir_of(folder: &PathBuf) -> Result<(), CodegenError> {
+    create_dir_all(folder).map_err(|e| CodegenError::io(folder, e))?;
+    log::debug!("Created {:?} folder successfully", folder);
+    Ok(())
 }
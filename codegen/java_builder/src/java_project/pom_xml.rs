@@ -1,3 +1,12 @@
+use crate::java_structs::diagnostics::Diagnostic;
+
+use super::{
+    catalog::{self, Catalog},
+    error::CodegenError,
+    xml::{Comments, XmlElement, XmlNode},
+};
+
+#[derive(Clone)]
 pub struct PomXml {
     pub java: String,
     pub dependencies: Vec<Library>,
@@ -5,12 +14,128 @@ pub struct PomXml {
     //It has the same attributes thats why
     //+ a relative path ofc
     pub parent_pom: Library,
+    //jar when empty
+    pub packaging: String,
+    //child modules of an aggregator pom
+    pub modules: Vec<String>,
+    pub dependency_management: Vec<Library>,
+    //rendered as <relativePath/> when empty, spring boot parents come from the repository
+    pub parent_relative_path: Option<String>,
+    //java.version is rendered first, these follow in insertion order
+    pub properties: Vec<(String, String)>,
+    pub plugins: Vec<Plugin>,
+    pub profiles: Vec<Profile>,
+    pub repositories: Vec<Repository>,
+    pub plugin_repositories: Vec<Repository>,
+    //children of <build> besides the plugins, e.g. finalName or resources of a parsed pom
+    pub build_extra: Vec<XmlNode>,
+    //elements (and comments) of a parsed pom the model does not know about, written back as is
+    //after the known element they followed ("" for the start of the project)
+    pub extra: Vec<(String, XmlNode)>,
+    //comments inside the parent and the lists of a parsed pom
+    pub comments: Comments,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Compile,
+    Provided,
+    Runtime,
+    Test,
+    System,
+    //only valid for pom dependencies inside dependencyManagement
+    Import,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Compile => "compile",
+            Scope::Provided => "provided",
+            Scope::Runtime => "runtime",
+            Scope::Test => "test",
+            Scope::System => "system",
+            Scope::Import => "import",
+        }
+    }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exclusion {
+    pub group_id: String,
+    pub artifact_id: String,
+}
+
+#[derive(Clone)]
 pub struct Library {
     group_id: String,
     pub artifact_id: String,
     version: Option<String>,
+    pub scope: Option<Scope>,
+    //jar when empty
+    pub type_: Option<String>,
+    pub classifier: Option<String>,
+    pub optional: bool,
+    pub exclusions: Vec<Exclusion>,
+    //unknown children of a parsed dependency, e.g. systemPath
+    pub extra: Vec<XmlNode>,
+    pub comments: Comments,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PluginExecution {
+    pub id: Option<String>,
+    pub phase: Option<String>,
+    pub goals: Vec<String>,
+    pub configuration: Vec<XmlNode>,
+    //unknown children of a parsed execution, e.g. inherited
+    pub extra: Vec<XmlNode>,
+    pub comments: Comments,
+}
+
+#[derive(Clone)]
+pub struct Plugin {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: Option<String>,
+    //the children of <configuration>, plugins define their own elements
+    pub configuration: Vec<XmlNode>,
+    pub executions: Vec<PluginExecution>,
+    pub dependencies: Vec<Library>,
+    //unknown children of a parsed plugin, e.g. extensions or inherited
+    pub extra: Vec<XmlNode>,
+    pub comments: Comments,
+}
+
+#[derive(Clone)]
+pub struct Profile {
+    pub id: String,
+    pub active_by_default: bool,
+    //the other activation conditions of a parsed profile, e.g. jdk, os, property or file
+    pub activation: Vec<XmlNode>,
+    pub properties: Vec<(String, String)>,
+    pub dependencies: Vec<Library>,
+    pub plugins: Vec<Plugin>,
+    //children of <build> besides the plugins
+    pub build_extra: Vec<XmlNode>,
+    //unknown children of a parsed profile, e.g. modules, dependencyManagement or repositories
+    pub extra: Vec<XmlNode>,
+    pub comments: Comments,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Repository {
+    pub id: String,
+    pub name: Option<String>,
+    pub url: String,
+    pub releases: bool,
+    pub snapshots: bool,
+    //the rest of the policies of a parsed repository, e.g. updatePolicy or checksumPolicy
+    pub releases_extra: Vec<XmlNode>,
+    pub snapshots_extra: Vec<XmlNode>,
+    //unknown children of a parsed repository, e.g. layout
+    pub extra: Vec<XmlNode>,
+    pub comments: Comments,
 }
 
 #[derive(Clone)]
@@ -22,6 +147,16 @@ pub struct ProjectInfo {
     pub version: String,
 }
 
+impl ProjectInfo {
+    pub fn version_or_default(&self) -> &str {
+        if self.version.is_empty() {
+            "0.0.1-SNAPSHOT"
+        } else {
+            &self.version
+        }
+    }
+}
+
 impl PartialEq for Library {
     fn eq(&self, other: &Self) -> bool {
         self.artifact_id.eq(&other.artifact_id)
@@ -30,57 +165,394 @@ impl PartialEq for Library {
 
 impl Default for Library {
     fn default() -> Self {
-        Self {
-            group_id: "".to_owned(),
-            artifact_id: "".to_owned(),
-            version: None,
-        }
+        Self::new("".to_owned(), "".to_owned())
     }
 }
 
 impl Library {
+    pub fn group_id(&self) -> &str {
+        &self.group_id
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
     pub fn new(group_id: String, artifact_id: String) -> Self {
         Self {
             group_id,
             artifact_id,
             version: None,
+            scope: None,
+            type_: None,
+            classifier: None,
+            optional: false,
+            exclusions: vec![],
+            extra: vec![],
+            comments: Comments::default(),
         }
     }
 
     pub fn new_with_version(group_id: String, artifact_id: String, version: String) -> Self {
         Self {
+            version: Some(version),
+            ..Self::new(group_id, artifact_id)
+        }
+    }
+
+    //a bill of materials, imported into dependencyManagement
+    pub fn bom(group_id: String, artifact_id: String, version: String) -> Self {
+        Self::new_with_version(group_id, artifact_id, version)
+            .type_("pom".to_owned())
+            .scope(Scope::Import)
+    }
+
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    pub fn type_(mut self, type_: String) -> Self {
+        self.type_ = Some(type_);
+        self
+    }
+
+    pub fn classifier(mut self, classifier: String) -> Self {
+        self.classifier = Some(classifier);
+        self
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    pub fn exclusion(mut self, group_id: String, artifact_id: String) -> Self {
+        self.exclusions.push(Exclusion {
             group_id,
             artifact_id,
-            version: Some(version),
+        });
+        self
+    }
+
+    pub fn with_version(mut self, version: String) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    //same library, the version is left to dependencyManagement
+    pub fn unversioned(&self) -> Self {
+        Self {
+            version: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_test(&self) -> bool {
+        self.scope == Some(Scope::Test)
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        let mut exclusions = XmlElement::new("exclusions");
+        for e in self.exclusions.iter() {
+            exclusions = exclusions.child(
+                XmlElement::new("exclusion")
+                    .child(XmlElement::text("groupId", &e.group_id))
+                    .child(XmlElement::text("artifactId", &e.artifact_id)),
+            );
+        }
+        let mut dependency = XmlElement::new("dependency")
+            .child(XmlElement::text("groupId", &self.group_id))
+            .child(XmlElement::text("artifactId", &self.artifact_id))
+            .text_if_some("version", self.version.as_deref())
+            .text_if_some("type", self.type_.as_deref())
+            .text_if_some("classifier", self.classifier.as_deref())
+            .text_if_some("scope", self.scope.map(|s| s.as_str()));
+        if self.optional {
+            dependency = dependency.child(XmlElement::text("optional", "true"));
         }
+        let dependency = dependency
+            .child_if_not_empty(self.comments.apply("exclusions", exclusions))
+            .children(self.extra.clone());
+        self.comments.apply("", dependency)
     }
 }
 
 impl Generate for Library {
     fn generate(&self) -> String {
-        let mut result = "".to_owned();
-        result += "<dependency>\n";
-        result += &("<groupId>".to_owned() + &self.group_id + "</groupId>");
-        result += &("<artifactId>".to_owned() + &self.artifact_id + "</artifactId>");
-        if let Some(ref v) = &self.version {
-            result += &("<version>".to_owned() + v + "</version>");
-        }
-        result += "\n</dependency>";
-        result
+        self.to_xml().render()
+    }
+}
+
+fn dependencies_xml(libraries: &[Library]) -> XmlElement {
+    let mut dependencies = XmlElement::new("dependencies");
+    for lib in libraries.iter() {
+        dependencies = dependencies.child(lib.to_xml());
     }
+    dependencies
 }
 
 impl Generate for Vec<Library> {
     fn generate(&self) -> String {
-        let mut result = "".to_owned();
-        result += "<dependencies>\n";
-        for lib in self.iter() {
-            result += &lib.generate();
+        dependencies_xml(self).render()
+    }
+}
+
+fn properties_xml(properties: &[(String, String)]) -> XmlElement {
+    let mut result = XmlElement::new("properties");
+    for (key, value) in properties.iter() {
+        result = result.child(XmlElement::text(key, value));
+    }
+    result
+}
+
+fn plugins_xml(plugins: &[Plugin]) -> XmlElement {
+    let mut result = XmlElement::new("plugins");
+    for plugin in plugins.iter() {
+        result = result.child(plugin.to_xml());
+    }
+    result
+}
+
+impl PluginExecution {
+    pub fn new(goals: Vec<String>) -> Self {
+        Self {
+            id: None,
+            phase: None,
+            goals,
+            configuration: vec![],
+            extra: vec![],
+            comments: Comments::default(),
         }
-        result += "\n</dependencies>";
-        result
+    }
+
+    pub fn id(mut self, id: String) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn phase(mut self, phase: String) -> Self {
+        self.phase = Some(phase);
+        self
+    }
+
+    pub fn configuration(mut self, element: XmlElement) -> Self {
+        self.configuration.push(element.into());
+        self
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        let mut goals = XmlElement::new("goals");
+        for g in self.goals.iter() {
+            goals = goals.child(XmlElement::text("goal", g));
+        }
+        let execution = XmlElement::new("execution")
+            .text_if_some("id", self.id.as_deref())
+            .text_if_some("phase", self.phase.as_deref())
+            .child_if_not_empty(self.comments.apply("goals", goals))
+            .child_if_not_empty(
+                XmlElement::new("configuration").children(self.configuration.clone()),
+            )
+            .children(self.extra.clone());
+        self.comments.apply("", execution)
     }
 }
+
+impl Plugin {
+    pub fn new(group_id: String, artifact_id: String) -> Self {
+        Self {
+            group_id,
+            artifact_id,
+            version: None,
+            configuration: vec![],
+            executions: vec![],
+            dependencies: vec![],
+            extra: vec![],
+            comments: Comments::default(),
+        }
+    }
+
+    //builds fat jars and runs the application, its version comes from the boot parent
+    pub fn spring_boot_maven_plugin() -> Self {
+        Self::new(
+            "org.springframework.boot".to_owned(),
+            "spring-boot-maven-plugin".to_owned(),
+        )
+    }
+
+    pub fn version(mut self, version: String) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn configuration(mut self, element: XmlElement) -> Self {
+        self.configuration.push(element.into());
+        self
+    }
+
+    pub fn execution(mut self, execution: PluginExecution) -> Self {
+        self.executions.push(execution);
+        self
+    }
+
+    pub fn dependency(mut self, lib: Library) -> Self {
+        self.dependencies.push(lib);
+        self
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        let mut executions = XmlElement::new("executions");
+        for e in self.executions.iter() {
+            executions = executions.child(e.to_xml());
+        }
+        let plugin = XmlElement::new("plugin")
+            .child(XmlElement::text("groupId", &self.group_id))
+            .child(XmlElement::text("artifactId", &self.artifact_id))
+            .text_if_some("version", self.version.as_deref())
+            .child_if_not_empty(self.comments.apply("executions", executions))
+            .child_if_not_empty(
+                self.comments
+                    .apply("dependencies", dependencies_xml(&self.dependencies)),
+            )
+            .child_if_not_empty(
+                XmlElement::new("configuration").children(self.configuration.clone()),
+            )
+            .children(self.extra.clone());
+        self.comments.apply("", plugin)
+    }
+}
+
+impl Profile {
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            active_by_default: false,
+            activation: vec![],
+            properties: vec![],
+            dependencies: vec![],
+            plugins: vec![],
+            build_extra: vec![],
+            extra: vec![],
+            comments: Comments::default(),
+        }
+    }
+
+    pub fn active_by_default(mut self) -> Self {
+        self.active_by_default = true;
+        self
+    }
+
+    pub fn property(mut self, key: String, value: String) -> Self {
+        set_property(&mut self.properties, key, value);
+        self
+    }
+
+    pub fn dependency(mut self, lib: Library) -> Self {
+        self.dependencies.push(lib);
+        self
+    }
+
+    pub fn plugin(mut self, plugin: Plugin) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        let mut activation = XmlElement::new("activation");
+        if self.active_by_default {
+            activation = activation.child(XmlElement::text("activeByDefault", "true"));
+        }
+        let profile = XmlElement::new("profile")
+            .child(XmlElement::text("id", &self.id))
+            .child_if_not_empty(activation.children(self.activation.clone()))
+            .child_if_not_empty(
+                self.comments
+                    .apply("properties", properties_xml(&self.properties)),
+            )
+            .child_if_not_empty(
+                self.comments
+                    .apply("dependencies", dependencies_xml(&self.dependencies)),
+            )
+            .child_if_not_empty(
+                XmlElement::new("build")
+                    .children(self.build_extra.clone())
+                    .child_if_not_empty(
+                        self.comments
+                            .apply("build/plugins", plugins_xml(&self.plugins)),
+                    ),
+            )
+            .children(self.extra.clone());
+        self.comments.apply("", profile)
+    }
+}
+
+impl Repository {
+    //releases only, the way most third party repositories are declared
+    pub fn new(id: String, url: String) -> Self {
+        Self {
+            id,
+            name: None,
+            url,
+            releases: true,
+            snapshots: false,
+            releases_extra: vec![],
+            snapshots_extra: vec![],
+            extra: vec![],
+            comments: Comments::default(),
+        }
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn snapshots(mut self, enabled: bool) -> Self {
+        self.snapshots = enabled;
+        self
+    }
+
+    pub fn releases(mut self, enabled: bool) -> Self {
+        self.releases = enabled;
+        self
+    }
+
+    pub fn to_xml(&self, tag: &str) -> XmlElement {
+        let repository = XmlElement::new(tag)
+            .child(XmlElement::text("id", &self.id))
+            .text_if_some("name", self.name.as_deref())
+            .child(XmlElement::text("url", &self.url))
+            .child(
+                XmlElement::new("releases")
+                    .child(XmlElement::text("enabled", &self.releases.to_string()))
+                    .children(self.releases_extra.clone()),
+            )
+            .child(
+                XmlElement::new("snapshots")
+                    .child(XmlElement::text("enabled", &self.snapshots.to_string()))
+                    .children(self.snapshots_extra.clone()),
+            )
+            .children(self.extra.clone());
+        self.comments.apply("", repository)
+    }
+}
+
+fn repositories_xml(list_tag: &str, tag: &str, repositories: &[Repository]) -> XmlElement {
+    let mut result = XmlElement::new(list_tag);
+    for r in repositories.iter() {
+        result = result.child(r.to_xml(tag));
+    }
+    result
+}
+
+//keeps the first position of a key when it is overwritten
+fn set_property(properties: &mut Vec<(String, String)>, key: String, value: String) {
+    match properties.iter_mut().find(|(k, _)| *k == key) {
+        Some(existing) => existing.1 = value,
+        None => properties.push((key, value)),
+    }
+}
+
 impl PomXml {
     pub fn get_root_package(&self) -> String {
         self.project_info.group_id.to_owned() + "." + &self.project_info.artifact_id
@@ -96,8 +568,82 @@ impl PomXml {
             java: "".to_owned(),
             dependencies: vec![],
             parent_pom: Library::default(),
+            packaging: "".to_owned(),
+            modules: vec![],
+            dependency_management: vec![],
+            parent_relative_path: None,
+            build_extra: vec![],
+            extra: vec![],
+            properties: vec![],
+            plugins: vec![],
+            profiles: vec![],
+            repositories: vec![],
+            plugin_repositories: vec![],
+            comments: Comments::default(),
         }
     }
+
+    pub fn parent(mut self, parent: Library) -> Self {
+        self.parent_pom = parent;
+        self
+    }
+
+    pub fn packaging(mut self, packaging: String) -> Self {
+        self.packaging = packaging;
+        self
+    }
+
+    pub fn module(mut self, name: String) -> Self {
+        self.modules.push(name);
+        self
+    }
+
+    pub fn managed_dependency(mut self, lib: Library) -> Self {
+        self.dependency_management.push(lib);
+        self
+    }
+
+    //imports the dependencyManagement of another project, e.g. spring-cloud-dependencies
+    pub fn bom(mut self, group_id: String, artifact_id: String, version: String) -> Self {
+        self.dependency_management
+            .push(Library::bom(group_id, artifact_id, version));
+        self
+    }
+
+    pub fn property(mut self, key: String, value: String) -> Self {
+        set_property(&mut self.properties, key, value);
+        self
+    }
+
+    pub fn plugin(mut self, plugin: Plugin) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profiles.push(profile);
+        self
+    }
+
+    pub fn repository(mut self, repository: Repository) -> Self {
+        self.repositories.push(repository);
+        self
+    }
+
+    pub fn plugin_repository(mut self, repository: Repository) -> Self {
+        self.plugin_repositories.push(repository);
+        self
+    }
+
+    pub fn has_plugin(&self, artifact_id: &str) -> bool {
+        self.plugins.iter().any(|p| p.artifact_id == artifact_id)
+    }
+
+    pub fn is_managed(&self, lib: &Library) -> bool {
+        self.dependency_management
+            .iter()
+            .any(|m| m.group_id == lib.group_id && m.artifact_id == lib.artifact_id)
+    }
     pub fn java_version(mut self, version: String) -> Self {
         self.java = version;
         self
@@ -112,15 +658,25 @@ impl PomXml {
         let spring_parent = Library::new_with_version(
             "org.springframework.boot".to_owned(),
             "spring-boot-starter-parent".to_owned(),
-            "3.4.1".to_owned(),
+            catalog::bundled().latest_spring_boot().to_owned(),
         );
         self.parent_pom = spring_parent;
+        self.parent_relative_path = Some("".to_owned());
 
         self = self.spring_boot_starter_web();
         self = self.lombok();
         self = self.spring_boot_starter_data_jpa();
         self = self.openapi();
-        self
+        //lombok is only needed at compile time, keep it out of the fat jar
+        self.plugin(
+            Plugin::spring_boot_maven_plugin().configuration(
+                XmlElement::new("excludes").child(
+                    XmlElement::new("exclude")
+                        .child(XmlElement::text("groupId", "org.projectlombok"))
+                        .child(XmlElement::text("artifactId", "lombok")),
+                ),
+            ),
+        )
     }
 
     pub fn description(mut self, descr: String) -> Self {
@@ -149,61 +705,549 @@ impl PomXml {
         self
     }
 
+    pub fn dependency(mut self, lib: Library) -> Self {
+        self.dependencies.push(lib);
+        self
+    }
+
     pub fn add_library(mut self, group_id: String, artifact_id: String) -> Self {
         self.dependencies.push(Library::new(group_id, artifact_id));
         self
     }
 
-    fn check_dependencies_exist() {
-        todo!("implement a way to check if dependencies exist with their related versions");
+    //duplicates, clashes and versions that do not fit the spring boot version, all offline
+    pub fn check_dependencies(&self, catalog: &Catalog) -> Vec<Diagnostic> {
+        catalog.check(self)
+    }
+
+    //from the spring boot parent or an imported spring-boot-dependencies bom
+    pub fn spring_boot_version(&self) -> Option<&str> {
+        if self.parent_pom.artifact_id == "spring-boot-starter-parent" {
+            return self.parent_pom.version();
+        }
+        self.dependency_management
+            .iter()
+            .find(|l| l.artifact_id == "spring-boot-dependencies")
+            .and_then(|l| l.version())
     }
 
     pub fn openapi(mut self) -> PomXml {
-        self.dependencies.push(Library::new_with_version(
-            "org.springdoc".into(),
-            "springdoc-openapi-starter-webmvc-ui".into(),
-            "2.7.0".into(),
-        ));
+        let catalog = catalog::bundled();
+        let boot_version = self
+            .spring_boot_version()
+            .unwrap_or(catalog.latest_spring_boot())
+            .to_owned();
+        for artifact in [
+            "springdoc-openapi-starter-webmvc-ui",
+            "springdoc-openapi-starter-webmvc-api",
+        ] {
+            let mut lib = Library::new("org.springdoc".into(), artifact.into());
+            if let Some(version) = catalog.version_for("org.springdoc", artifact, &boot_version) {
+                lib = lib.with_version(version.to_owned());
+            }
+            self.dependencies.push(lib);
+        }
+        self
+    }
 
-        self.dependencies.push(Library::new_with_version(
-            "org.springdoc".into(),
-            "springdoc-openapi-starter-webmvc-api".into(),
-            "2.7.0".into(),
-        ));
+    //the processor runs after lombok's, the binding makes sure of it
+    pub fn mapstruct(mut self) -> PomXml {
+        let catalog = catalog::bundled();
+        let boot_version = self
+            .spring_boot_version()
+            .unwrap_or(catalog.latest_spring_boot())
+            .to_owned();
+        for (group, artifact, scope) in [
+            ("org.mapstruct", "mapstruct", None),
+            (
+                "org.mapstruct",
+                "mapstruct-processor",
+                Some(Scope::Provided),
+            ),
+            (
+                "org.projectlombok",
+                "lombok-mapstruct-binding",
+                Some(Scope::Provided),
+            ),
+        ] {
+            let mut lib = Library::new(group.into(), artifact.into());
+            if let Some(version) = catalog.version_for(group, artifact, &boot_version) {
+                lib = lib.with_version(version.to_owned());
+            }
+            if let Some(scope) = scope {
+                lib = lib.scope(scope);
+            }
+            self.dependencies.push(lib);
+        }
         self
     }
 }
 
+impl PomXml {
+    pub fn to_xml(&self) -> XmlElement {
+        let info = &self.project_info;
+        let mut project = XmlElement::new("project")
+            .attr("xmlns", "http://maven.apache.org/POM/4.0.0")
+            .attr("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance")
+            .attr(
+                "xsi:schemaLocation",
+                "http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd",
+            )
+            .child(XmlElement::text("modelVersion", "4.0.0"));
+        if !self.parent_pom.artifact_id.is_empty() {
+            let mut parent = XmlElement::new("parent")
+                .child(XmlElement::text("groupId", &self.parent_pom.group_id))
+                .child(XmlElement::text("artifactId", &self.parent_pom.artifact_id))
+                .text_if_some("version", self.parent_pom.version());
+            if let Some(ref path) = self.parent_relative_path {
+                parent = parent.child(if path.is_empty() {
+                    XmlElement::new("relativePath")
+                } else {
+                    XmlElement::text("relativePath", path)
+                });
+            }
+            project = project.child(self.parent_pom.comments.apply("", parent));
+        }
+        project = project
+            .child(XmlElement::text("groupId", &info.group_id))
+            .child(XmlElement::text("artifactId", &info.artifact_id))
+            .child(XmlElement::text("version", info.version_or_default()));
+        if !self.packaging.is_empty() {
+            project = project.child(XmlElement::text("packaging", &self.packaging));
+        }
+        project = project
+            .text_if_some("name", Some(info.name.as_str()).filter(|n| !n.is_empty()))
+            .text_if_some(
+                "description",
+                Some(info.description.as_str()).filter(|d| !d.is_empty()),
+            );
+
+        let mut modules = XmlElement::new("modules");
+        for m in self.modules.iter() {
+            modules = modules.child(XmlElement::text("module", m));
+        }
+        project = project.child_if_not_empty(self.comments.apply("modules", modules));
+
+        let mut properties = vec![];
+        if !self.java.is_empty() {
+            properties.push(("java.version".to_owned(), self.java.clone()));
+        }
+        properties.extend(self.properties.iter().cloned());
+        project = project.child_if_not_empty(
+            self.comments
+                .apply("properties", properties_xml(&properties)),
+        );
+
+        if !self.dependency_management.is_empty() {
+            project = project.child(self.comments.apply(
+                "dependencyManagement",
+                XmlElement::new("dependencyManagement").child(self.comments.apply(
+                    "dependencyManagement/dependencies",
+                    dependencies_xml(&self.dependency_management),
+                )),
+            ));
+        }
+        let mut profiles = XmlElement::new("profiles");
+        for p in self.profiles.iter() {
+            profiles = profiles.child(p.to_xml());
+        }
+        project = project
+            .child_if_not_empty(
+                self.comments
+                    .apply("dependencies", dependencies_xml(&self.dependencies)),
+            )
+            .child_if_not_empty(self.comments.apply(
+                "repositories",
+                repositories_xml("repositories", "repository", &self.repositories),
+            ))
+            .child_if_not_empty(self.comments.apply(
+                "pluginRepositories",
+                repositories_xml(
+                    "pluginRepositories",
+                    "pluginRepository",
+                    &self.plugin_repositories,
+                ),
+            ))
+            .child_if_not_empty(
+                XmlElement::new("build")
+                    .children(self.build_extra.clone())
+                    .child_if_not_empty(
+                        self.comments
+                            .apply("build/plugins", plugins_xml(&self.plugins)),
+                    ),
+            )
+            .child_if_not_empty(self.comments.apply("profiles", profiles));
+
+        //the unknown elements go back in front of the first known element that came after them
+        let mut extra = self.extra.iter().peekable();
+        let mut children = vec![];
+        for child in project.children.drain(..) {
+            if let XmlNode::Element(ref e) = child {
+                while let Some((_, node)) =
+                    extra.next_if(|(after, _)| position(after) < position(&e.name))
+                {
+                    children.push(node.clone());
+                }
+            }
+            children.push(child);
+        }
+        children.extend(extra.map(|(_, node)| node.clone()));
+        project.children = children;
+        project
+    }
+
+    //reads an existing pom.xml, what the model does not cover ends up in the extra fields
+    //and the comments next to the elements they were found with
+    pub fn parse(xml: &str) -> Result<PomXml, CodegenError> {
+        let project = XmlElement::parse(xml)?;
+        if project.name != "project" {
+            return Err(CodegenError::Parse(format!(
+                "expected a <project> root element, found <{}>",
+                project.name
+            )));
+        }
+        let parent = project
+            .find("parent")
+            .map(parse_library)
+            .unwrap_or_default();
+        //groupId and version are inherited from the parent when missing
+        let info = ProjectInfo {
+            group_id: project
+                .find_text("groupId")
+                .unwrap_or_else(|| parent.group_id.clone()),
+            artifact_id: project.find_text("artifactId").unwrap_or_default(),
+            name: project.find_text("name").unwrap_or_default(),
+            description: project.find_text("description").unwrap_or_default(),
+            version: project
+                .find_text("version")
+                .or_else(|| parent.version.clone())
+                .unwrap_or_default(),
+        };
+        let mut pom_xml = PomXml::new(info).parent(parent);
+        pom_xml.parent_relative_path = project
+            .find("parent")
+            .and_then(|p| p.find("relativePath"))
+            .map(|p| p.text_content());
+
+        //the last known element, the unknown ones are written back after it
+        let mut after = "".to_owned();
+        for child in project.children.iter() {
+            let element = match child {
+                XmlNode::Element(e) if is_list(&e.name) => {
+                    pom_xml.comments.collect(&e.name, e);
+                    e
+                }
+                XmlNode::Element(e) => e,
+                XmlNode::Comment(_) | XmlNode::InlineComment(_) => {
+                    pom_xml.extra.push((after.clone(), child.clone()));
+                    continue;
+                }
+                XmlNode::Text(_) => continue,
+            };
+            match element.name.as_str() {
+                "modelVersion" | "parent" | "groupId" | "artifactId" | "version" | "name"
+                | "description" => {}
+                "packaging" => pom_xml.packaging = element.text_content(),
+                "modules" => {
+                    pom_xml.modules = element
+                        .element_children()
+                        .map(|m| m.text_content())
+                        .collect()
+                }
+                "properties" => {
+                    for (key, value) in parse_properties(element) {
+                        if key == "java.version" {
+                            pom_xml.java = value;
+                        } else {
+                            pom_xml.properties.push((key, value));
+                        }
+                    }
+                }
+                "dependencyManagement" => {
+                    pom_xml.comments.collect("dependencyManagement", element);
+                    if let Some(dependencies) = element.find("dependencies") {
+                        pom_xml
+                            .comments
+                            .collect("dependencyManagement/dependencies", dependencies);
+                        pom_xml.dependency_management = parse_dependencies(dependencies);
+                    }
+                }
+                "dependencies" => pom_xml.dependencies = parse_dependencies(element),
+                "repositories" => pom_xml.repositories = parse_repositories(element),
+                "pluginRepositories" => pom_xml.plugin_repositories = parse_repositories(element),
+                "build" => {
+                    for build_child in element.children.iter() {
+                        match build_child {
+                            XmlNode::Element(e) if e.name == "plugins" => {
+                                pom_xml.comments.collect("build/plugins", e);
+                                pom_xml.plugins = parse_plugins(e)
+                            }
+                            _ => pom_xml.build_extra.push(build_child.clone()),
+                        }
+                    }
+                }
+                "profiles" => {
+                    pom_xml.profiles = element.element_children().map(parse_profile).collect()
+                }
+                _ => {
+                    pom_xml.extra.push((after.clone(), child.clone()));
+                    continue;
+                }
+            }
+            after = element.name.clone();
+        }
+        Ok(pom_xml)
+    }
+}
+
+//the elements of a project the model knows about, in the order they are written
+const PROJECT_ELEMENTS: [&str; 16] = [
+    "modelVersion",
+    "parent",
+    "groupId",
+    "artifactId",
+    "version",
+    "packaging",
+    "name",
+    "description",
+    "modules",
+    "properties",
+    "dependencyManagement",
+    "dependencies",
+    "repositories",
+    "pluginRepositories",
+    "build",
+    "profiles",
+];
+
+//0 for the start of the project
+fn position(name: &str) -> usize {
+    PROJECT_ELEMENTS
+        .iter()
+        .position(|e| *e == name)
+        .map_or(0, |i| i + 1)
+}
+
+//the elements of a pom that hold a list of entries, the comments between them are kept
+fn is_list(name: &str) -> bool {
+    matches!(
+        name,
+        "modules"
+            | "properties"
+            | "dependencies"
+            | "repositories"
+            | "pluginRepositories"
+            | "profiles"
+    )
+}
+
+//the children of a parsed element the model does not know about
+fn unknown_children(element: &XmlElement, known: &[&str]) -> Vec<XmlNode> {
+    element
+        .children
+        .iter()
+        .filter(|c| matches!(c, XmlNode::Element(e) if !known.contains(&e.name.as_str())))
+        .cloned()
+        .collect()
+}
+
+fn parse_library(element: &XmlElement) -> Library {
+    let mut lib = Library::new(
+        element.find_text("groupId").unwrap_or_default(),
+        element.find_text("artifactId").unwrap_or_default(),
+    );
+    lib.version = element.find_text("version");
+    lib.type_ = element.find_text("type");
+    lib.classifier = element.find_text("classifier");
+    lib.scope = element.find_text("scope").and_then(|s| match s.as_str() {
+        "compile" => Some(Scope::Compile),
+        "provided" => Some(Scope::Provided),
+        "runtime" => Some(Scope::Runtime),
+        "test" => Some(Scope::Test),
+        "system" => Some(Scope::System),
+        "import" => Some(Scope::Import),
+        _ => None,
+    });
+    lib.optional = element.find_text("optional").is_some_and(|o| o == "true");
+    //relativePath belongs to the parent, the pom renders it itself
+    lib.extra = unknown_children(
+        element,
+        &[
+            "groupId",
+            "artifactId",
+            "version",
+            "type",
+            "classifier",
+            "scope",
+            "optional",
+            "exclusions",
+            "relativePath",
+        ],
+    );
+    lib.comments.collect("", element);
+    if let Some(exclusions) = element.find("exclusions") {
+        lib.comments.collect("exclusions", exclusions);
+        for e in exclusions.element_children() {
+            lib = lib.exclusion(
+                e.find_text("groupId").unwrap_or_default(),
+                e.find_text("artifactId").unwrap_or_default(),
+            );
+        }
+    }
+    lib
+}
+
+fn parse_dependencies(element: &XmlElement) -> Vec<Library> {
+    element.element_children().map(parse_library).collect()
+}
+
+fn parse_properties(element: &XmlElement) -> Vec<(String, String)> {
+    element
+        .element_children()
+        .map(|p| (p.name.clone(), p.text_content()))
+        .collect()
+}
+
+//the children of a <configuration>, kept as they are
+fn configuration_of(element: &XmlElement) -> Vec<XmlNode> {
+    element
+        .find("configuration")
+        .map(|c| c.children.clone())
+        .unwrap_or_default()
+}
+
+fn parse_plugins(element: &XmlElement) -> Vec<Plugin> {
+    element
+        .element_children()
+        .map(|p| {
+            let mut plugin = Plugin::new(
+                //maven assumes org.apache.maven.plugins when there is no groupId
+                p.find_text("groupId")
+                    .unwrap_or_else(|| "org.apache.maven.plugins".to_owned()),
+                p.find_text("artifactId").unwrap_or_default(),
+            );
+            plugin.version = p.find_text("version");
+            plugin.configuration = configuration_of(p);
+            plugin.extra = unknown_children(
+                p,
+                &[
+                    "groupId",
+                    "artifactId",
+                    "version",
+                    "configuration",
+                    "dependencies",
+                    "executions",
+                ],
+            );
+            plugin.comments.collect("", p);
+            if let Some(dependencies) = p.find("dependencies") {
+                plugin.comments.collect("dependencies", dependencies);
+                plugin.dependencies = parse_dependencies(dependencies);
+            }
+            if let Some(executions) = p.find("executions") {
+                plugin.comments.collect("executions", executions);
+                plugin.executions = executions.element_children().map(parse_execution).collect();
+            }
+            plugin
+        })
+        .collect()
+}
+
+fn parse_execution(element: &XmlElement) -> PluginExecution {
+    let mut execution = PluginExecution::new(vec![]);
+    execution.id = element.find_text("id");
+    execution.phase = element.find_text("phase");
+    execution.configuration = configuration_of(element);
+    execution.extra = unknown_children(element, &["id", "phase", "goals", "configuration"]);
+    execution.comments.collect("", element);
+    if let Some(goals) = element.find("goals") {
+        execution.comments.collect("goals", goals);
+        execution.goals = goals.element_children().map(|g| g.text_content()).collect();
+    }
+    execution
+}
+
+fn parse_repositories(element: &XmlElement) -> Vec<Repository> {
+    //both are enabled unless the pom says otherwise
+    let enabled = |r: &XmlElement, policy: &str| {
+        r.find(policy)
+            .and_then(|p| p.find_text("enabled"))
+            .is_none_or(|e| e != "false")
+    };
+    //updatePolicy, checksumPolicy and the comments of a policy
+    let policy_extra = |r: &XmlElement, policy: &str| {
+        r.find(policy)
+            .map(|p| {
+                p.children
+                    .iter()
+                    .filter(|c| !matches!(c, XmlNode::Element(e) if e.name == "enabled"))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    element
+        .element_children()
+        .map(|r| {
+            let mut repository = Repository::new(
+                r.find_text("id").unwrap_or_default(),
+                r.find_text("url").unwrap_or_default(),
+            );
+            repository.name = r.find_text("name");
+            repository.releases = enabled(r, "releases");
+            repository.snapshots = enabled(r, "snapshots");
+            repository.releases_extra = policy_extra(r, "releases");
+            repository.snapshots_extra = policy_extra(r, "snapshots");
+            repository.extra = unknown_children(r, &["id", "name", "url", "releases", "snapshots"]);
+            repository.comments.collect("", r);
+            repository
+        })
+        .collect()
+}
+
+fn parse_profile(element: &XmlElement) -> Profile {
+    let mut profile = Profile::new(element.find_text("id").unwrap_or_default());
+    if let Some(activation) = element.find("activation") {
+        profile.active_by_default = activation
+            .find_text("activeByDefault")
+            .is_some_and(|a| a == "true");
+        profile.activation = activation
+            .children
+            .iter()
+            .filter(|c| !matches!(c, XmlNode::Element(e) if e.name == "activeByDefault"))
+            .cloned()
+            .collect();
+    }
+    profile.extra = unknown_children(
+        element,
+        &["id", "activation", "properties", "dependencies", "build"],
+    );
+    profile.comments.collect("", element);
+    if let Some(properties) = element.find("properties") {
+        profile.comments.collect("properties", properties);
+        profile.properties = parse_properties(properties);
+    }
+    if let Some(dependencies) = element.find("dependencies") {
+        profile.comments.collect("dependencies", dependencies);
+        profile.dependencies = parse_dependencies(dependencies);
+    }
+    if let Some(build) = element.find("build") {
+        for child in build.children.iter() {
+            match child {
+                XmlNode::Element(e) if e.name == "plugins" => {
+                    profile.comments.collect("build/plugins", e);
+                    profile.plugins = parse_plugins(e)
+                }
+                _ => profile.build_extra.push(child.clone()),
+            }
+        }
+    }
+    profile
+}
+
 impl Generate for PomXml {
     fn generate(&self) -> String {
-        let mut result = "".to_owned();
-        result += r#"<?xml version="1.0" encoding="UTF-8"?>"#;
-        result += r#"<project xmlns="http://maven.apache.org/POM/4.0.0" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
-         xsi:schemaLocation="http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd">"#;
-        result += &("<modelVersion>4.0.0</modelVersion>");
-        result += "<parent>\n";
-        result += &("<groupId>".to_owned() + &self.parent_pom.group_id + "</groupId>");
-        result += &("<artifactId>".to_owned() + &self.parent_pom.artifact_id + "</artifactId>");
-        if let Some(ref v) = &self.parent_pom.version {
-            result += &("<version>".to_owned() + v + "</version>");
-        }
-        result += "\n</parent>";
-
-        result +=
-            &(r#"<description>"#.to_owned() + &self.project_info.description + &"</description>\n");
-        result += &(r#"<name>"#.to_owned() + &self.project_info.name + &"</name>\n");
-        result += &("<groupId>".to_owned() + &self.project_info.group_id + &"</groupId>\n");
-
-        result += &("<artifactId>".to_owned() + &self.project_info.artifact_id + &"</artifactId>");
-        result += &("<version>".to_owned() + &"0.0.1-SNAPSHOT" + &"</version>");
-        result += "<properties>\n";
-        result += &("<java.version>".to_owned() + &self.java + &"</java.version>");
-        result += "\n</properties>";
-
-        result += &self.dependencies.generate();
-        result += r#"</project>"#;
-        //println!("Xml result {}", result);
-        result
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned() + "\n" + &self.to_xml().render()
     }
 }
 
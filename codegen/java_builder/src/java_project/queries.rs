@@ -0,0 +1,251 @@
+//queries declared per entity, written to its repository either as spring data derived
+//methods (the name says what to do) or as methods with a @Query
+use super::maven_builder::capitalize;
+use crate::{
+    annotations::Annotation,
+    fields::Field,
+    imports::Import,
+    java_structs::VariableParam,
+    methods::Method,
+    types::{GenericParams, TypeName},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QueryResult {
+    //Optional<Entity>
+    One,
+    //List<Entity>
+    List,
+    //Page<Entity>, takes a Pageable as last parameter
+    Page,
+    //boolean, derived methods start with exists
+    Exists,
+    //long, derived methods start with count
+    Count,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomQuery {
+    //the method name, spring data does not read it
+    pub name: String,
+    pub query: String,
+    //sql on the tables instead of jpql on the entities
+    pub native: bool,
+    //bound with @Param, the query refers to them as :name
+    pub params: Vec<(TypeName, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryIntent {
+    pub result: QueryResult,
+    //fields the entity has to match, all of them
+    pub by: Vec<String>,
+    //findTop10By... when set
+    pub limit: Option<u32>,
+    pub order_by: Option<String>,
+    pub descending: bool,
+    //a @Query instead of a derived name
+    pub custom: Option<CustomQuery>,
+}
+
+impl QueryIntent {
+    fn derived(result: QueryResult, by: Vec<String>) -> Self {
+        Self {
+            result,
+            by,
+            limit: None,
+            order_by: None,
+            descending: false,
+            custom: None,
+        }
+    }
+
+    //Optional<Entity> findByEmail(String email)
+    pub fn find_by(by: Vec<String>) -> Self {
+        Self::derived(QueryResult::One, by)
+    }
+
+    //List<Entity> findByStatus(Status status)
+    pub fn find_all_by(by: Vec<String>) -> Self {
+        Self::derived(QueryResult::List, by)
+    }
+
+    //boolean existsByUsername(String username)
+    pub fn exists_by(by: Vec<String>) -> Self {
+        Self::derived(QueryResult::Exists, by)
+    }
+
+    //long countByStatus(Status status)
+    pub fn count_by(by: Vec<String>) -> Self {
+        Self::derived(QueryResult::Count, by)
+    }
+
+    //List<Entity> findTop10ByOrderByCreatedAtDesc()
+    pub fn top(limit: u32, order_by: String) -> Self {
+        let mut intent = Self::derived(QueryResult::List, vec![]);
+        intent.limit = Some(limit);
+        intent.order_by = Some(order_by);
+        intent.descending = true;
+        intent
+    }
+
+    pub fn jpql(name: String, query: String, result: QueryResult) -> Self {
+        let mut intent = Self::derived(result, vec![]);
+        intent.custom = Some(CustomQuery {
+            name,
+            query,
+            native: false,
+            params: vec![],
+        });
+        intent
+    }
+
+    pub fn native(name: String, query: String, result: QueryResult) -> Self {
+        let mut intent = Self::jpql(name, query, result);
+        if let Some(ref mut custom) = intent.custom {
+            custom.native = true;
+        }
+        intent
+    }
+
+    pub fn by(mut self, by: Vec<String>) -> Self {
+        self.by = by;
+        self
+    }
+
+    pub fn order_by(mut self, field: String) -> Self {
+        self.order_by = Some(field);
+        self
+    }
+
+    pub fn ascending(mut self) -> Self {
+        self.descending = false;
+        self
+    }
+
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    pub fn paged(mut self) -> Self {
+        self.result = QueryResult::Page;
+        self
+    }
+
+    //a parameter of a @Query
+    pub fn param(mut self, type_: TypeName, name: String) -> Self {
+        if let Some(ref mut custom) = self.custom {
+            custom.params.push((type_, name));
+        }
+        self
+    }
+
+    //findTop10ByStatusAndTypeOrderByCreatedAtDesc
+    pub fn method_name(&self) -> String {
+        if let Some(ref custom) = self.custom {
+            return custom.name.clone();
+        }
+        let mut name = match self.result {
+            QueryResult::Exists => "exists".to_owned(),
+            QueryResult::Count => "count".to_owned(),
+            _ => "find".to_owned(),
+        };
+        if let Some(limit) = self.limit {
+            name.push_str(&format!("Top{}", limit));
+        }
+        name.push_str("By");
+        let by: Vec<String> = self.by.iter().map(|f| capitalize(f)).collect();
+        name.push_str(&by.join("And"));
+        if let Some(ref order_by) = self.order_by {
+            name.push_str("OrderBy");
+            name.push_str(&capitalize(order_by));
+            name.push_str(if self.descending { "Desc" } else { "Asc" });
+        }
+        name
+    }
+
+    fn return_type(&self, entity: &str) -> TypeName {
+        let generic = |name: &str| {
+            TypeName::new_with_generics(name.into(), GenericParams::new(vec![entity.to_owned()]))
+        };
+        match self.result {
+            QueryResult::One => generic("Optional"),
+            QueryResult::List => generic("List"),
+            QueryResult::Page => generic("Page"),
+            QueryResult::Exists => "boolean".into(),
+            QueryResult::Count => "long".into(),
+        }
+    }
+
+    //the repository method, None when a field it names is not a property of the entity
+    pub fn method(&self, entity: &str, properties: &[Field]) -> Option<Method> {
+        let mut method = Method::new(self.return_type(entity), self.method_name());
+        match self.custom {
+            Some(ref custom) => {
+                let escaped = custom.query.replace('\\', "\\\\").replace('"', "\\\"");
+                let mut query = Annotation::new("Query".into())
+                    .param("value".into(), format!("\"{}\"", escaped));
+                if custom.native {
+                    query = query.param("nativeQuery".into(), "true".into());
+                }
+                method = method.annotation(query);
+                for (type_, name) in custom.params.iter() {
+                    method = method.param(
+                        VariableParam::new(type_.clone(), name.clone()).annotation(
+                            Annotation::new("Param".into())
+                                .param("value".into(), format!("\"{}\"", name)),
+                        ),
+                    );
+                }
+            }
+            None => {
+                let mut named = self.by.iter().chain(self.order_by.iter());
+                if let Some(missing) =
+                    named.find(|name| !properties.iter().any(|p| &&p.name == name))
+                {
+                    log::warn!(
+                        "{} has no property {}, {} is left out",
+                        entity,
+                        missing,
+                        self.method_name()
+                    );
+                    return None;
+                }
+                for name in self.by.iter() {
+                    let property = properties.iter().find(|p| &p.name == name)?;
+                    method = method.param(VariableParam::new(property.type_.clone(), name.clone()));
+                }
+            }
+        }
+        if self.result == QueryResult::Page {
+            method = method.param(VariableParam::new("Pageable".into(), "pageable".into()));
+        }
+        Some(method)
+    }
+
+    pub fn imports(&self) -> Vec<Import> {
+        let mut imports = match self.result {
+            QueryResult::One => vec![Import::new("java.util".into(), "Optional".into())],
+            QueryResult::List => vec![Import::new("java.util".into(), "List".into())],
+            QueryResult::Page => vec![
+                Import::new("org.springframework.data.domain".into(), "Page".into()),
+                Import::new("org.springframework.data.domain".into(), "Pageable".into()),
+            ],
+            QueryResult::Exists | QueryResult::Count => vec![],
+        };
+        if let Some(ref custom) = self.custom {
+            imports.push(Import::new(
+                "org.springframework.data.jpa.repository".into(),
+                "Query".into(),
+            ));
+            if !custom.params.is_empty() {
+                imports.push(Import::new(
+                    "org.springframework.data.repository.query".into(),
+                    "Param".into(),
+                ));
+            }
+        }
+        imports
+    }
+}
@@ -0,0 +1,435 @@
+//relationships between the entities of a codebase. each one adds the mapped fields to the
+//entities on both sides and a nested route that lists the children of a parent
+use super::{
+    crud_builder::{boxed, lower_first},
+    maven_builder::capitalize,
+    migrations::snake_case,
+    queries::{QueryIntent, QueryResult},
+};
+use crate::{
+    annotations::Annotation,
+    fields::Field,
+    imports::Import,
+    methods::Method,
+    types::{GenericParams, TypeName},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RelationKind {
+    OneToMany,
+    ManyToOne,
+    ManyToMany,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cascade {
+    All,
+    Persist,
+    Merge,
+    Remove,
+    Refresh,
+    Detach,
+}
+
+impl Cascade {
+    fn code(&self) -> &'static str {
+        match self {
+            Cascade::All => "CascadeType.ALL",
+            Cascade::Persist => "CascadeType.PERSIST",
+            Cascade::Merge => "CascadeType.MERGE",
+            Cascade::Remove => "CascadeType.REMOVE",
+            Cascade::Refresh => "CascadeType.REFRESH",
+            Cascade::Detach => "CascadeType.DETACH",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Fetch {
+    #[default]
+    Lazy,
+    Eager,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relationship {
+    pub kind: RelationKind,
+    //the entity that declares the field
+    pub entity: String,
+    pub field: String,
+    pub target: String,
+    //the field of the target pointing back, one to many always has one
+    pub inverse: Option<String>,
+    //set on the field of the entity, the inverse side never cascades
+    pub cascade: Vec<Cascade>,
+    pub fetch: Fetch,
+    //the foreign key column, {field}_id of the many to one side when None
+    pub join_column: Option<String>,
+    //the table of a many to many, {entity}_{field} when None
+    pub join_table: Option<String>,
+    //GET /{parent}/{id}/{children} on the controller of the parent
+    pub nested_route: bool,
+}
+
+//the children of a parent, listed by the controller of the parent
+#[derive(Clone, Debug, PartialEq)]
+pub struct NestedRoute {
+    pub parent: String,
+    pub child: String,
+    //the path after /{id}/
+    pub segment: String,
+    //the repository method of the child, it takes the id of the parent and a pageable
+    pub query: QueryIntent,
+    //the property a derived query goes through, {field}{IdName}, e.g. customerId
+    property: Option<String>,
+    //the type of the id of the parent, what the repository method takes
+    pub parent_id: String,
+}
+
+impl NestedRoute {
+    pub fn finder(&self) -> String {
+        self.query.method_name()
+    }
+
+    pub fn repository_method(&self) -> Option<Method> {
+        let properties: Vec<Field> = self
+            .property
+            .iter()
+            .map(|p| Field::n(p.clone(), self.parent_id.as_str().into()))
+            .collect();
+        match self.property {
+            Some(_) => self.query.method(&self.child, &properties),
+            None => self
+                .query
+                .clone()
+                .param(self.parent_id.as_str().into(), "id".into())
+                .method(&self.child, &properties),
+        }
+    }
+}
+
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value)
+}
+
+//lombok would follow the relationship back and forth in toString, equals and hashCode
+fn excluded_from_lombok(field: Field) -> Field {
+    field
+        .annotation("ToString.Exclude".into())
+        .annotation("EqualsAndHashCode.Exclude".into())
+}
+
+impl Relationship {
+    fn new(kind: RelationKind, entity: String, field: String, target: String) -> Self {
+        Self {
+            kind,
+            entity,
+            field,
+            target,
+            inverse: None,
+            cascade: vec![],
+            fetch: Fetch::Lazy,
+            join_column: None,
+            join_table: None,
+            nested_route: true,
+        }
+    }
+
+    //PurchaseOrder.customer, the foreign key is in the table of the entity
+    pub fn many_to_one(entity: String, field: String, target: String) -> Self {
+        Self::new(RelationKind::ManyToOne, entity, field, target)
+    }
+
+    //Customer.orders mapped by PurchaseOrder.customer, both fields are generated
+    pub fn one_to_many(entity: String, field: String, target: String, mapped_by: String) -> Self {
+        Self::new(RelationKind::OneToMany, entity, field, target).inverse(mapped_by)
+    }
+
+    //Student.courses, the entity owns the join table
+    pub fn many_to_many(entity: String, field: String, target: String) -> Self {
+        Self::new(RelationKind::ManyToMany, entity, field, target)
+    }
+
+    pub fn inverse(mut self, field: String) -> Self {
+        self.inverse = Some(field);
+        self
+    }
+
+    pub fn cascade(mut self, cascade: Vec<Cascade>) -> Self {
+        self.cascade = cascade;
+        self
+    }
+
+    pub fn fetch(mut self, fetch: Fetch) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    pub fn join_column(mut self, column: String) -> Self {
+        self.join_column = Some(column);
+        self
+    }
+
+    pub fn join_table(mut self, table: String) -> Self {
+        self.join_table = Some(table);
+        self
+    }
+
+    pub fn nested_route(mut self, nested_route: bool) -> Self {
+        self.nested_route = nested_route;
+        self
+    }
+
+    pub fn involves(&self, entity: &str) -> bool {
+        self.entity == entity || self.target == entity
+    }
+
+    //the field of the other side, one to many needs it to know its foreign key
+    fn inverse_field(&self) -> Option<String> {
+        match self.kind {
+            RelationKind::OneToMany => Some(
+                self.inverse
+                    .clone()
+                    .unwrap_or_else(|| lower_first(&self.entity)),
+            ),
+            _ => self.inverse.clone(),
+        }
+    }
+
+    fn cascade_and_fetch(&self, mut annotation: Annotation, to_one: bool) -> Annotation {
+        match self.cascade.as_slice() {
+            [] => {}
+            [one] => annotation = annotation.param("cascade".into(), one.code().into()),
+            many => {
+                let codes: Vec<&str> = many.iter().map(|c| c.code()).collect();
+                annotation =
+                    annotation.param("cascade".into(), format!("{{{}}}", codes.join(", ")));
+            }
+        }
+        //to one is eager unless told otherwise, collections are lazy
+        match (self.fetch, to_one) {
+            (Fetch::Lazy, true) => annotation.param("fetch".into(), "FetchType.LAZY".into()),
+            (Fetch::Eager, false) => annotation.param("fetch".into(), "FetchType.EAGER".into()),
+            _ => annotation,
+        }
+    }
+
+    fn to_one(&self, name: &str, target: &str, owning: bool) -> Field {
+        let mut many_to_one = Annotation::new("ManyToOne".into());
+        if owning {
+            many_to_one = self.cascade_and_fetch(many_to_one, true);
+        } else {
+            many_to_one = many_to_one.param("fetch".into(), "FetchType.LAZY".into());
+        }
+        let column = self.join_column.clone().unwrap_or(snake_case(name) + "_id");
+        excluded_from_lombok(
+            Field::n(name.to_owned(), TypeName::new(target.to_owned()))
+                .annotation(many_to_one)
+                .annotation(
+                    Annotation::new("JoinColumn".into()).param("name".into(), quoted(&column)),
+                ),
+        )
+    }
+
+    fn collection(&self, annotation: Annotation, name: &str, of: &str) -> Field {
+        let (collection, implementation) = match self.kind {
+            RelationKind::ManyToMany => ("Set", "HashSet"),
+            _ => ("List", "ArrayList"),
+        };
+        let mut field = Field::n(
+            name.to_owned(),
+            TypeName::new_with_generics(
+                collection.to_owned(),
+                GenericParams::new(vec![of.to_owned()]),
+            ),
+        )
+        .annotation(annotation);
+        field.initializer = Some(format!("new {}<>()", implementation));
+        excluded_from_lombok(field)
+    }
+
+    //the table name and both columns of a many to many
+    fn join_table_names(&self) -> (String, String, String) {
+        let table = self.join_table.clone().unwrap_or(format!(
+            "{}_{}",
+            snake_case(&self.entity),
+            snake_case(&self.field)
+        ));
+        let column = snake_case(&self.entity) + "_id";
+        let inverse_column = match self.entity == self.target {
+            true => snake_case(&self.field) + "_id",
+            false => snake_case(&self.target) + "_id",
+        };
+        (table, column, inverse_column)
+    }
+
+    //the mapped fields the entity gets, a relationship of an entity with itself gives both
+    pub fn fields_of(&self, entity: &str) -> Vec<Field> {
+        let mut fields = vec![];
+        let inverse = self.inverse_field();
+        if self.entity == entity {
+            let field = match self.kind {
+                RelationKind::ManyToOne => self.to_one(&self.field, &self.target, true),
+                RelationKind::OneToMany => self.collection(
+                    self.cascade_and_fetch(
+                        Annotation::new("OneToMany".into())
+                            .param("mappedBy".into(), quoted(inverse.as_deref().unwrap_or(""))),
+                        false,
+                    ),
+                    &self.field,
+                    &self.target,
+                ),
+                RelationKind::ManyToMany => {
+                    let (table, column, inverse_column) = self.join_table_names();
+                    let join_table = Annotation::new("JoinTable".into())
+                        .param("name".into(), quoted(&table))
+                        .param(
+                            "joinColumns".into(),
+                            format!("@JoinColumn(name = {})", quoted(&column)),
+                        )
+                        .param(
+                            "inverseJoinColumns".into(),
+                            format!("@JoinColumn(name = {})", quoted(&inverse_column)),
+                        );
+                    let mut field = self.collection(
+                        self.cascade_and_fetch(Annotation::new("ManyToMany".into()), false),
+                        &self.field,
+                        &self.target,
+                    );
+                    field.annotation.push(join_table);
+                    field
+                }
+            };
+            fields.push(field);
+        }
+        if let (true, Some(inverse)) = (self.target == entity, inverse) {
+            let field = match self.kind {
+                RelationKind::ManyToOne => self.collection(
+                    Annotation::new("OneToMany".into())
+                        .param("mappedBy".into(), quoted(&self.field)),
+                    &inverse,
+                    &self.entity,
+                ),
+                RelationKind::OneToMany => self.to_one(&inverse, &self.entity, false),
+                RelationKind::ManyToMany => self.collection(
+                    Annotation::new("ManyToMany".into())
+                        .param("mappedBy".into(), quoted(&self.field)),
+                    &inverse,
+                    &self.entity,
+                ),
+            };
+            fields.push(field);
+        }
+        fields
+    }
+
+    //the entity the children are listed under
+    pub fn route_parent(&self) -> &str {
+        match self.kind {
+            RelationKind::ManyToOne => &self.target,
+            _ => &self.entity,
+        }
+    }
+
+    //the queries go through the id of the parent, whatever it is called
+    pub fn route(&self, parent_id: &Field) -> Option<NestedRoute> {
+        if !self.nested_route {
+            return None;
+        }
+        let id_name = capitalize(&parent_id.name);
+        let id_type = boxed(&parent_id.type_.name);
+        let route = match self.kind {
+            RelationKind::ManyToOne => NestedRoute {
+                parent: self.target.clone(),
+                child: self.entity.clone(),
+                segment: self
+                    .inverse
+                    .clone()
+                    .unwrap_or(self.entity.to_lowercase() + "s"),
+                query: QueryIntent::find_all_by(vec![self.field.clone() + &id_name]).paged(),
+                property: Some(self.field.clone() + &id_name),
+                parent_id: id_type,
+            },
+            RelationKind::OneToMany => {
+                let inverse = self.inverse_field().unwrap_or_default();
+                NestedRoute {
+                    parent: self.entity.clone(),
+                    child: self.target.clone(),
+                    segment: self.field.clone(),
+                    query: QueryIntent::find_all_by(vec![inverse.clone() + &id_name]).paged(),
+                    property: Some(inverse + &id_name),
+                    parent_id: id_type,
+                }
+            }
+            //the target does not always know the entity, the join goes from the owner
+            RelationKind::ManyToMany => NestedRoute {
+                parent: self.entity.clone(),
+                child: self.target.clone(),
+                segment: self.field.clone(),
+                query: QueryIntent::jpql(
+                    format!("find{}Of{}", capitalize(&self.field), self.entity),
+                    format!(
+                        "select t from {} e join e.{} t where e.{} = :id",
+                        self.entity, self.field, parent_id.name
+                    ),
+                    QueryResult::Page,
+                ),
+                property: None,
+                parent_id: id_type,
+            },
+        };
+        Some(route)
+    }
+}
+
+//a many to one field, the dto carries the id of the other side instead
+pub fn is_to_one(field: &Field) -> bool {
+    field
+        .annotation
+        .iter()
+        .any(|a| a.qualified_name == "ManyToOne")
+}
+
+//what the mapped fields need in the entity
+pub fn imports_for(field: &Field) -> Vec<Import> {
+    let mut imports = vec![];
+    for annotation in field.annotation.iter() {
+        let name = annotation.qualified_name.as_str();
+        match name {
+            "ToString.Exclude" => imports.push(Import::new("lombok".into(), "ToString".into())),
+            "EqualsAndHashCode.Exclude" => {
+                imports.push(Import::new("lombok".into(), "EqualsAndHashCode".into()))
+            }
+            _ => imports.push(Import::new("jakarta.persistence".into(), name.into())),
+        }
+        let params = annotation.params_list.iter().flatten();
+        for (_, value) in params {
+            if value.contains("CascadeType.") {
+                imports.push(Import::new(
+                    "jakarta.persistence".into(),
+                    "CascadeType".into(),
+                ));
+            }
+            if value.contains("FetchType.") {
+                imports.push(Import::new(
+                    "jakarta.persistence".into(),
+                    "FetchType".into(),
+                ));
+            }
+            if value.contains("@JoinColumn") {
+                imports.push(Import::new(
+                    "jakarta.persistence".into(),
+                    "JoinColumn".into(),
+                ));
+            }
+        }
+    }
+    if let Some(ref initializer) = field.initializer {
+        imports.push(Import::new("java.util".into(), field.type_.name.clone()));
+        let implementation = initializer
+            .trim_start_matches("new ")
+            .trim_end_matches("<>()");
+        imports.push(Import::new("java.util".into(), implementation.to_owned()));
+    }
+    imports
+}
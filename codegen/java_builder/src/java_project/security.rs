@@ -0,0 +1,801 @@
+//opt-in spring security: users with roles stored next to the entities, a login endpoint
+//and method security on the generated controllers
+use rand::{distributions::Alphanumeric, Rng};
+
+use super::{
+    config::SpringProfile, crud_builder::CrudBuilder, error::CodegenError, output::OutputConfig,
+};
+use crate::{
+    annotations::Annotation,
+    classes::JavaClass,
+    fields::Field,
+    imports::Import,
+    interfaces::Interface,
+    java_structs::VariableParam,
+    methods::Method,
+    types::{GenericParams, TypeName},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AuthMode {
+    //the login endpoint stores the authentication in the http session
+    #[default]
+    Session,
+    //the login endpoint hands out a signed jwt, the api is a stateless resource server
+    Jwt,
+}
+
+#[derive(Clone, Debug)]
+pub struct SecurityConfig {
+    pub mode: AuthMode,
+    pub login_path: String,
+    //roles allowed to call the GET endpoints of the generated controllers
+    pub read_roles: Vec<String>,
+    //roles allowed to create, update and delete
+    pub write_roles: Vec<String>,
+    //reachable without logging in, the login path is always public
+    pub public_paths: Vec<String>,
+    //signing key for local runs, only the dev profile falls back to it
+    jwt_secret: String,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self::new(AuthMode::default())
+    }
+}
+
+fn quoted(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("\"{}\"", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+//hasRole('ADMIN') or hasAnyRole('USER', 'ADMIN')
+fn role_expression(roles: &[String]) -> String {
+    let roles: Vec<String> = roles.iter().map(|r| format!("'{}'", r)).collect();
+    match roles.len() {
+        1 => format!("hasRole({})", roles[0]),
+        _ => format!("hasAnyRole({})", roles.join(", ")),
+    }
+}
+
+fn imports(package: &str, names: &[&str]) -> Vec<Import> {
+    names
+        .iter()
+        .map(|n| Import::new(package.to_owned(), (*n).to_owned()))
+        .collect()
+}
+
+fn constructor(class_name: &str, params: &[(&str, &str)]) -> Method {
+    let mut constructor = Method::new(TypeName::new("".into()), class_name.to_owned()).public();
+    let mut code = "".to_owned();
+    for (type_, name) in params {
+        constructor = constructor.param(VariableParam::new((*type_).into(), (*name).to_owned()));
+        code.push_str(&format!("this.{} = {};\n", name, name));
+    }
+    constructor.code(code)
+}
+
+fn value_field(name: &str, type_: &str, property: &str) -> Field {
+    Field::n(name.to_owned(), type_.into()).annotation(
+        Annotation::new("Value".into()).param("value".into(), format!("\"${{{}}}\"", property)),
+    )
+}
+
+impl SecurityConfig {
+    pub fn new(mode: AuthMode) -> Self {
+        let jwt_secret = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(64)
+            .map(char::from)
+            .collect();
+        Self {
+            mode,
+            login_path: "/login".to_owned(),
+            read_roles: vec!["USER".to_owned(), "ADMIN".to_owned()],
+            write_roles: vec!["ADMIN".to_owned()],
+            public_paths: vec!["/error".to_owned()],
+            jwt_secret,
+        }
+    }
+
+    //picks the mode from the securitySchemes of an openapi document, None when it declares none
+    //bearer tokens, oauth2 and openid connect become a resource server, anything else a session
+    pub fn from_openapi(spec: &str) -> Result<Option<Self>, CodegenError> {
+        let spec: serde_json::Value =
+            serde_json::from_str(spec).map_err(|e| CodegenError::Parse(e.to_string()))?;
+        let Some(schemes) = spec
+            .pointer("/components/securitySchemes")
+            .and_then(|s| s.as_object())
+            .filter(|s| !s.is_empty())
+        else {
+            return Ok(None);
+        };
+        let uses_tokens = schemes.values().any(|scheme| {
+            let field = |name: &str| {
+                scheme
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_ascii_lowercase()
+            };
+            match field("type").as_str() {
+                "oauth2" | "openidconnect" => true,
+                "http" => field("scheme") == "bearer",
+                _ => false,
+            }
+        });
+        let mode = if uses_tokens {
+            AuthMode::Jwt
+        } else {
+            AuthMode::Session
+        };
+        Ok(Some(Self::new(mode)))
+    }
+
+    pub fn login_path(mut self, path: String) -> Self {
+        self.login_path = path;
+        self
+    }
+
+    pub fn read_roles(mut self, roles: Vec<String>) -> Self {
+        self.read_roles = roles;
+        self
+    }
+
+    pub fn write_roles(mut self, roles: Vec<String>) -> Self {
+        self.write_roles = roles;
+        self
+    }
+
+    pub fn public_path(mut self, path: String) -> Self {
+        if !self.public_paths.contains(&path) {
+            self.public_paths.push(path);
+        }
+        self
+    }
+
+    //every role that shows up in a rule, the admin created on startup gets all of them
+    pub fn roles(&self) -> Vec<String> {
+        let mut roles = self.read_roles.clone();
+        for role in self.write_roles.iter() {
+            if !roles.contains(role) {
+                roles.push(role.clone());
+            }
+        }
+        roles
+    }
+
+    //the keys the generated classes read
+    pub fn properties(&self) -> Vec<(String, String)> {
+        let mut properties = vec![
+            (
+                "security.admin.username".to_owned(),
+                "${ADMIN_USERNAME:admin}".to_owned(),
+            ),
+            //no admin is created until a password is given
+            (
+                "security.admin.password".to_owned(),
+                "${ADMIN_PASSWORD:}".to_owned(),
+            ),
+        ];
+        if self.mode == AuthMode::Jwt {
+            //no fallback, an application without JWT_SECRET must not sign with a committed key
+            properties.push(("security.jwt.secret".to_owned(), "${JWT_SECRET}".to_owned()));
+            properties.push(("security.jwt.expiration".to_owned(), "3600".to_owned()));
+        }
+        properties
+    }
+
+    //what a profile changes on top of the shared keys
+    pub fn profile_properties(&self, profile: SpringProfile) -> Vec<(String, String)> {
+        match profile {
+            SpringProfile::Dev if self.mode == AuthMode::Jwt => vec![(
+                "security.jwt.secret".to_owned(),
+                format!("${{JWT_SECRET:{}}}", self.jwt_secret),
+            )],
+            _ => vec![],
+        }
+    }
+
+    //the table is called users, user is reserved in most databases
+    pub fn user_entity(&self, output: &OutputConfig, root_package: &str) -> JavaClass {
+        let roles = Field::n(
+            "roles".into(),
+            TypeName::new_with_generics("Set".into(), GenericParams::new(vec!["String".into()])),
+        )
+        .annotation(
+            Annotation::new("ElementCollection".into())
+                .param("fetch".into(), "FetchType.EAGER".into()),
+        )
+        .annotation(Annotation::new("CollectionTable".into()).params(vec![
+            ("name".into(), "\"user_roles\"".into()),
+            (
+                "joinColumns".into(),
+                "@JoinColumn(name = \"user_id\")".into(),
+            ),
+        ]))
+        .annotation(Annotation::new("Column".into()).param("name".into(), "\"role\"".into()));
+        let user = JavaClass::new("User".into(), "".into())
+            .public()
+            .annotation(Annotation::new("Table".into()).param("name".into(), "\"users\"".into()))
+            .imports(imports(
+                "jakarta.persistence",
+                &[
+                    "CollectionTable",
+                    "Column",
+                    "ElementCollection",
+                    "FetchType",
+                    "JoinColumn",
+                    "Table",
+                ],
+            ))
+            .import(Import::new("java.util".into(), "Set".into()))
+            .field(Field::n("username".into(), "String".into()).annotation(
+                Annotation::new("Column".into()).params(vec![
+                    ("nullable".into(), "false".into()),
+                    ("unique".into(), "true".into()),
+                ]),
+            ))
+            .field(Field::n("password".into(), "String".into()).annotation(
+                Annotation::new("Column".into()).param("nullable".into(), "false".into()),
+            ))
+            .field(roles);
+        CrudBuilder::new(user)
+            .output(output.clone())
+            .root_package(root_package.to_owned())
+            .spring_boot_entity()
+    }
+
+    pub fn user_repository(&self, output: &OutputConfig, root_package: &str) -> Interface {
+        CrudBuilder::new(JavaClass::new("User".into(), "".into()))
+            .output(output.clone())
+            .root_package(root_package.to_owned())
+            .jpa_repository_of()
+            .import(Import::new("java.util".into(), "Optional".into()))
+            .method(
+                Method::new(
+                    TypeName::new_with_generics(
+                        "Optional".into(),
+                        GenericParams::new(vec!["User".into()]),
+                    ),
+                    "findByUsername".into(),
+                )
+                .param(VariableParam::new("String".into(), "username".into())),
+            )
+    }
+
+    //GET endpoints need one of the read roles, everything else one of the write roles
+    pub fn secure_controller(&self, mut controller: JavaClass) -> JavaClass {
+        for method in controller.methods.iter_mut() {
+            let roles = match method.annotations.iter().find(|a| {
+                a.qualified_name.ends_with("Mapping") && a.qualified_name != "RequestMapping"
+            }) {
+                Some(a) if a.qualified_name == "GetMapping" => &self.read_roles,
+                Some(_) => &self.write_roles,
+                None => continue,
+            };
+            if roles.is_empty()
+                || method
+                    .annotations
+                    .iter()
+                    .any(|a| a.qualified_name == "PreAuthorize")
+            {
+                continue;
+            }
+            method.annotations.push(
+                Annotation::new("PreAuthorize".into())
+                    .param("value".into(), format!("\"{}\"", role_expression(roles))),
+            );
+        }
+        controller.import(Import::new(
+            "org.springframework.security.access.prepost".into(),
+            "PreAuthorize".into(),
+        ))
+    }
+
+    //the configuration, login endpoint and user lookup, all in the given package
+    pub fn classes(
+        &self,
+        package: String,
+        user_import: Import,
+        repository_import: Import,
+    ) -> Vec<JavaClass> {
+        vec![
+            self.configuration_class(package.clone()),
+            self.user_details_service(
+                package.clone(),
+                user_import.clone(),
+                repository_import.clone(),
+            ),
+            self.admin_initializer(package.clone(), user_import, repository_import),
+            self.auth_controller(package.clone()),
+            self.login_request(package.clone()),
+            self.login_response(package),
+        ]
+    }
+
+    fn public_paths(&self) -> Vec<String> {
+        let mut paths = vec![self.login_path.clone()];
+        paths.extend(
+            self.public_paths
+                .iter()
+                .filter(|p| **p != self.login_path)
+                .cloned(),
+        );
+        paths
+    }
+
+    fn configuration_class(&self, package: String) -> JavaClass {
+        let mut filter_chain = format!(
+            "http\n    .authorizeHttpRequests(auth -> auth\n        .requestMatchers({}).permitAll()\n        .anyRequest().authenticated())\n",
+            quoted(&self.public_paths())
+        );
+        let mut class = JavaClass::new("SecurityConfiguration".into(), package)
+            .public()
+            .annotation("Configuration".into())
+            .annotation("EnableWebSecurity".into())
+            .annotation("EnableMethodSecurity".into())
+            .import(Import::new(
+                "org.springframework.context.annotation".into(),
+                "Bean".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.context.annotation".into(),
+                "Configuration".into(),
+            ))
+            .imports(imports(
+                "org.springframework.security.config.annotation.web.configuration",
+                &["EnableWebSecurity"],
+            ))
+            .imports(imports(
+                "org.springframework.security.config.annotation.method.configuration",
+                &["EnableMethodSecurity"],
+            ))
+            .imports(imports(
+                "org.springframework.security.config.annotation.authentication.configuration",
+                &["AuthenticationConfiguration"],
+            ))
+            .imports(imports(
+                "org.springframework.security.config.annotation.web.builders",
+                &["HttpSecurity"],
+            ))
+            .imports(imports(
+                "org.springframework.security.config.http",
+                &["SessionCreationPolicy"],
+            ))
+            .imports(imports(
+                "org.springframework.security.authentication",
+                &["AuthenticationManager"],
+            ))
+            .imports(imports(
+                "org.springframework.security.crypto.factory",
+                &["PasswordEncoderFactories"],
+            ))
+            .imports(imports(
+                "org.springframework.security.crypto.password",
+                &["PasswordEncoder"],
+            ))
+            .imports(imports(
+                "org.springframework.security.web",
+                &["SecurityFilterChain"],
+            ))
+            .method(
+                Method::new("PasswordEncoder".into(), "passwordEncoder".into())
+                    .public()
+                    .annotation("Bean".into())
+                    .code(
+                        "return PasswordEncoderFactories.createDelegatingPasswordEncoder();".into(),
+                    ),
+            )
+            .method(
+                Method::new(
+                    "AuthenticationManager".into(),
+                    "authenticationManager".into(),
+                )
+                .public()
+                .annotation("Bean".into())
+                .param(VariableParam::new(
+                    "AuthenticationConfiguration".into(),
+                    "configuration".into(),
+                ))
+                .throws("Exception".into())
+                .code("return configuration.getAuthenticationManager();".into()),
+            );
+
+        match self.mode {
+            AuthMode::Session => {
+                filter_chain.push_str(&format!(
+                    "    .securityContext(context -> context.securityContextRepository(securityContextRepository()))\n    .sessionManagement(session -> session.sessionCreationPolicy(SessionCreationPolicy.IF_REQUIRED))\n    .csrf(csrf -> csrf\n        .csrfTokenRepository(CookieCsrfTokenRepository.withHttpOnlyFalse())\n        .csrfTokenRequestHandler(new CsrfTokenRequestAttributeHandler())\n        .ignoringRequestMatchers({}))\n    .logout(logout -> logout.logoutSuccessHandler(new HttpStatusReturningLogoutSuccessHandler()))\n    .exceptionHandling(exceptions -> exceptions.authenticationEntryPoint(new HttpStatusEntryPoint(HttpStatus.UNAUTHORIZED)));\n",
+                    quoted(std::slice::from_ref(&self.login_path))
+                ));
+                class = class
+                    .import(Import::new(
+                        "org.springframework.http".into(),
+                        "HttpStatus".into(),
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.web.authentication",
+                        &["HttpStatusEntryPoint"],
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.web.authentication.logout",
+                        &["HttpStatusReturningLogoutSuccessHandler"],
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.web.context",
+                        &[
+                            "HttpSessionSecurityContextRepository",
+                            "SecurityContextRepository",
+                        ],
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.web.csrf",
+                        &[
+                            "CookieCsrfTokenRepository",
+                            "CsrfTokenRequestAttributeHandler",
+                        ],
+                    ))
+                    .method(
+                        Method::new(
+                            "SecurityContextRepository".into(),
+                            "securityContextRepository".into(),
+                        )
+                        .public()
+                        .annotation("Bean".into())
+                        .code("return new HttpSessionSecurityContextRepository();".into()),
+                    );
+            }
+            AuthMode::Jwt => {
+                filter_chain.push_str("    .sessionManagement(session -> session.sessionCreationPolicy(SessionCreationPolicy.STATELESS))\n    .csrf(csrf -> csrf.disable())\n    .oauth2ResourceServer(oauth2 -> oauth2.jwt(jwt -> jwt.jwtAuthenticationConverter(jwtAuthenticationConverter())));\n");
+                class = class
+                    .field(value_field("secret", "String", "security.jwt.secret"))
+                    .import(Import::new(
+                        "com.nimbusds.jose.jwk.source".into(),
+                        "ImmutableSecret".into(),
+                    ))
+                    .import(Import::new("java.nio.charset".into(), "StandardCharsets".into()))
+                    .import(Import::new("javax.crypto".into(), "SecretKey".into()))
+                    .import(Import::new("javax.crypto.spec".into(), "SecretKeySpec".into()))
+                    .import(Import::new(
+                        "org.springframework.beans.factory.annotation".into(),
+                        "Value".into(),
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.oauth2.jose.jws",
+                        &["MacAlgorithm"],
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.oauth2.jwt",
+                        &["JwtDecoder", "JwtEncoder", "NimbusJwtDecoder", "NimbusJwtEncoder"],
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.oauth2.server.resource.authentication",
+                        &["JwtAuthenticationConverter", "JwtGrantedAuthoritiesConverter"],
+                    ))
+                    .method(
+                        Method::new("JwtDecoder".into(), "jwtDecoder".into())
+                            .public()
+                            .annotation("Bean".into())
+                            .code("return NimbusJwtDecoder.withSecretKey(secretKey()).macAlgorithm(MacAlgorithm.HS256).build();".into()),
+                    )
+                    .method(
+                        Method::new("JwtEncoder".into(), "jwtEncoder".into())
+                            .public()
+                            .annotation("Bean".into())
+                            .code("return new NimbusJwtEncoder(new ImmutableSecret<>(secretKey()));".into()),
+                    )
+                    //the roles claim written by the login endpoint becomes ROLE_ authorities
+                    .method(
+                        Method::new(
+                            "JwtAuthenticationConverter".into(),
+                            "jwtAuthenticationConverter".into(),
+                        )
+                        .public()
+                        .annotation("Bean".into())
+                        .code("JwtGrantedAuthoritiesConverter authorities = new JwtGrantedAuthoritiesConverter();\nauthorities.setAuthoritiesClaimName(\"roles\");\nauthorities.setAuthorityPrefix(\"ROLE_\");\nJwtAuthenticationConverter converter = new JwtAuthenticationConverter();\nconverter.setJwtGrantedAuthoritiesConverter(authorities);\nreturn converter;".into()),
+                    )
+                    .method(
+                        Method::new("SecretKey".into(), "secretKey".into())
+                            .private()
+                            .code("return new SecretKeySpec(secret.getBytes(StandardCharsets.UTF_8), \"HmacSHA256\");".into()),
+                    );
+            }
+        }
+        filter_chain.push_str("return http.build();");
+        class.method(
+            Method::new("SecurityFilterChain".into(), "securityFilterChain".into())
+                .public()
+                .annotation("Bean".into())
+                .param(VariableParam::new("HttpSecurity".into(), "http".into()))
+                .throws("Exception".into())
+                .code(filter_chain),
+        )
+    }
+
+    fn user_details_service(
+        &self,
+        package: String,
+        user_import: Import,
+        repository_import: Import,
+    ) -> JavaClass {
+        let repository = repository_import.class_name.clone();
+        //the entity shares its name with the spring security user, that one is spelled out
+        let load = "User user = repository.findByUsername(username)\n    .orElseThrow(() -> new UsernameNotFoundException(username));\nreturn org.springframework.security.core.userdetails.User.withUsername(user.getUsername())\n    .password(user.getPassword())\n    .roles(user.getRoles().toArray(new String[0]))\n    .build();";
+        JavaClass::new("DatabaseUserDetailsService".into(), package)
+            .public()
+            .annotation("Service".into())
+            .implements("UserDetailsService".into())
+            .import(user_import)
+            .import(repository_import)
+            .import(Import::new(
+                "org.springframework.stereotype".into(),
+                "Service".into(),
+            ))
+            .imports(imports(
+                "org.springframework.security.core.userdetails",
+                &[
+                    "UserDetails",
+                    "UserDetailsService",
+                    "UsernameNotFoundException",
+                ],
+            ))
+            .field(Field::n("repository".into(), repository.clone().into()))
+            .method(constructor(
+                "DatabaseUserDetailsService",
+                &[(&repository, "repository")],
+            ))
+            .method(
+                Method::new("UserDetails".into(), "loadUserByUsername".into())
+                    .public()
+                    .annotation("Override".into())
+                    .param(VariableParam::new("String".into(), "username".into()))
+                    .throws("UsernameNotFoundException".into())
+                    .code(load.into()),
+            )
+    }
+
+    //creates the first admin from security.admin.* so there is someone who can log in
+    fn admin_initializer(
+        &self,
+        package: String,
+        user_import: Import,
+        repository_import: Import,
+    ) -> JavaClass {
+        let run = format!(
+            "if (username.isBlank() || password.isBlank() || repository.findByUsername(username).isPresent()) {{\n    return;\n}}\nUser admin = new User();\nadmin.setUsername(username);\nadmin.setPassword(passwordEncoder.encode(password));\nadmin.setRoles(new HashSet<>(List.of({})));\nrepository.save(admin);",
+            quoted(&self.roles())
+        );
+        let repository = repository_import.class_name.clone();
+        JavaClass::new("AdminInitializer".into(), package)
+            .public()
+            .annotation("Component".into())
+            .implements("CommandLineRunner".into())
+            .import(user_import)
+            .import(repository_import)
+            .import(Import::new("java.util".into(), "HashSet".into()))
+            .import(Import::new("java.util".into(), "List".into()))
+            .import(Import::new(
+                "org.springframework.beans.factory.annotation".into(),
+                "Value".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.boot".into(),
+                "CommandLineRunner".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.security.crypto.password".into(),
+                "PasswordEncoder".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.stereotype".into(),
+                "Component".into(),
+            ))
+            .field(Field::n("repository".into(), repository.clone().into()))
+            .field(Field::n("passwordEncoder".into(), "PasswordEncoder".into()))
+            .field(value_field("username", "String", "security.admin.username"))
+            .field(value_field("password", "String", "security.admin.password"))
+            .method(constructor(
+                "AdminInitializer",
+                &[
+                    (&repository, "repository"),
+                    ("PasswordEncoder", "passwordEncoder"),
+                ],
+            ))
+            .method(
+                Method::new("void".into(), "run".into())
+                    .public()
+                    .annotation("Override".into())
+                    .param(VariableParam::new("String...".into(), "args".into()))
+                    .code(run),
+            )
+    }
+
+    fn auth_controller(&self, package: String) -> JavaClass {
+        let authenticate = "Authentication authentication = authenticationManager.authenticate(\n    UsernamePasswordAuthenticationToken.unauthenticated(login.getUsername(), login.getPassword()));\nList<String> roles = authentication.getAuthorities().stream()\n    .map(GrantedAuthority::getAuthority)\n    .map(authority -> authority.replaceFirst(\"^ROLE_\", \"\"))\n    .toList();\n";
+        let mut login = Method::new(
+            TypeName::new_with_generics(
+                "ResponseEntity".into(),
+                GenericParams::new(vec!["LoginResponse".into()]),
+            ),
+            "login".into(),
+        )
+        .public()
+        .annotation(
+            Annotation::new("PostMapping".into())
+                .param("value".into(), format!("\"{}\"", self.login_path)),
+        )
+        .param(
+            VariableParam::new("LoginRequest".into(), "login".into())
+                .annotation("RequestBody".into()),
+        );
+        let mut controller = JavaClass::new("AuthController".into(), package)
+            .public()
+            .annotation("RestController".into())
+            .import(Import::new("java.util".into(), "List".into()))
+            .import(Import::new(
+                "org.springframework.http".into(),
+                "HttpStatus".into(),
+            ))
+            .import(Import::new(
+                "org.springframework.http".into(),
+                "ResponseEntity".into(),
+            ))
+            .imports(imports(
+                "org.springframework.security.authentication",
+                &[
+                    "AuthenticationManager",
+                    "UsernamePasswordAuthenticationToken",
+                ],
+            ))
+            .imports(imports(
+                "org.springframework.security.core",
+                &[
+                    "Authentication",
+                    "AuthenticationException",
+                    "GrantedAuthority",
+                ],
+            ))
+            .imports(imports(
+                "org.springframework.web.bind.annotation",
+                &["PostMapping", "RequestBody", "RestController"],
+            ))
+            .field(Field::n(
+                "authenticationManager".into(),
+                "AuthenticationManager".into(),
+            ));
+
+        let respond = match self.mode {
+            AuthMode::Session => {
+                controller = controller
+                    .import(Import::new(
+                        "jakarta.servlet.http".into(),
+                        "HttpServletRequest".into(),
+                    ))
+                    .import(Import::new(
+                        "jakarta.servlet.http".into(),
+                        "HttpServletResponse".into(),
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.core.context",
+                        &["SecurityContext", "SecurityContextHolder"],
+                    ))
+                    .import(Import::new(
+                        "org.springframework.security.web.context".into(),
+                        "SecurityContextRepository".into(),
+                    ))
+                    .field(Field::n(
+                        "securityContextRepository".into(),
+                        "SecurityContextRepository".into(),
+                    ))
+                    .method(constructor(
+                        "AuthController",
+                        &[
+                            ("AuthenticationManager", "authenticationManager"),
+                            ("SecurityContextRepository", "securityContextRepository"),
+                        ],
+                    ));
+                login = login
+                    .param(VariableParam::new(
+                        "HttpServletRequest".into(),
+                        "request".into(),
+                    ))
+                    .param(VariableParam::new(
+                        "HttpServletResponse".into(),
+                        "response".into(),
+                    ));
+                "SecurityContext context = SecurityContextHolder.createEmptyContext();\ncontext.setAuthentication(authentication);\nSecurityContextHolder.setContext(context);\nsecurityContextRepository.saveContext(context, request, response);\nreturn ResponseEntity.ok(new LoginResponse(authentication.getName(), roles));"
+            }
+            AuthMode::Jwt => {
+                controller = controller
+                    .import(Import::new("java.time".into(), "Instant".into()))
+                    .import(Import::new(
+                        "org.springframework.beans.factory.annotation".into(),
+                        "Value".into(),
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.oauth2.jose.jws",
+                        &["MacAlgorithm"],
+                    ))
+                    .imports(imports(
+                        "org.springframework.security.oauth2.jwt",
+                        &[
+                            "JwsHeader",
+                            "JwtClaimsSet",
+                            "JwtEncoder",
+                            "JwtEncoderParameters",
+                        ],
+                    ))
+                    .field(Field::n("jwtEncoder".into(), "JwtEncoder".into()))
+                    .field(value_field("expiration", "long", "security.jwt.expiration"))
+                    .method(constructor(
+                        "AuthController",
+                        &[
+                            ("AuthenticationManager", "authenticationManager"),
+                            ("JwtEncoder", "jwtEncoder"),
+                        ],
+                    ));
+                "Instant now = Instant.now();\nJwtClaimsSet claims = JwtClaimsSet.builder()\n    .issuedAt(now)\n    .expiresAt(now.plusSeconds(expiration))\n    .subject(authentication.getName())\n    .claim(\"roles\", roles)\n    .build();\nJwsHeader header = JwsHeader.with(MacAlgorithm.HS256).build();\nString token = jwtEncoder.encode(JwtEncoderParameters.from(header, claims)).getTokenValue();\nreturn ResponseEntity.ok(new LoginResponse(authentication.getName(), roles, token, expiration));"
+            }
+        };
+        let indent = |code: &str| {
+            code.lines()
+                .map(|l| format!("    {}\n", l))
+                .collect::<String>()
+        };
+        login = login.code(format!(
+            "try {{\n{}{}}} catch (AuthenticationException e) {{\n    return ResponseEntity.status(HttpStatus.UNAUTHORIZED).build();\n}}",
+            indent(authenticate),
+            indent(respond)
+        ));
+        controller.method(login)
+    }
+
+    fn lombok_dto(name: &str, package: String) -> JavaClass {
+        JavaClass::new(name.to_owned(), package)
+            .public()
+            .annotations(vec!["Data".into(), "NoArgsConstructor".into()])
+            .imports(imports("lombok", &["Data", "NoArgsConstructor"]))
+    }
+
+    fn login_request(&self, package: String) -> JavaClass {
+        Self::lombok_dto("LoginRequest", package)
+            .field(Field::n("username".into(), "String".into()))
+            .field(Field::n("password".into(), "String".into()))
+    }
+
+    fn login_response(&self, package: String) -> JavaClass {
+        let response = Self::lombok_dto("LoginResponse", package)
+            .import(Import::new("java.util".into(), "List".into()))
+            .field(Field::n("username".into(), "String".into()))
+            .field(Field::n(
+                "roles".into(),
+                TypeName::new_with_generics(
+                    "List".into(),
+                    GenericParams::new(vec!["String".into()]),
+                ),
+            ));
+        //fields are not kept in order, so the constructor is written out instead of lombok's
+        match self.mode {
+            AuthMode::Session => response.method(constructor(
+                "LoginResponse",
+                &[("String", "username"), ("List<String>", "roles")],
+            )),
+            AuthMode::Jwt => response
+                .field(Field::n("token".into(), "String".into()))
+                .field(Field::n("expiresIn".into(), "long".into()))
+                .method(constructor(
+                    "LoginResponse",
+                    &[
+                        ("String", "username"),
+                        ("List<String>", "roles"),
+                        ("String", "token"),
+                        ("long", "expiresIn"),
+                    ],
+                )),
+        }
+    }
+}
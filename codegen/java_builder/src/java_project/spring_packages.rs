@@ -1,5 +1,5 @@
 pub mod spring_packages {
-    use crate::java_project::pom_xml::PomXml;
+    use crate::java_project::pom_xml::{Library, PomXml, Scope};
     impl PomXml {
         pub fn spring_boot_starter_actuator(mut self) -> Self {
             self = self.add_library(
@@ -73,6 +73,22 @@ pub mod spring_packages {
             self
         }
 
+        pub fn spring_boot_starter_oauth2_resource_server(mut self) -> Self {
+            self = self.add_library(
+                "org.springframework.boot".into(),
+                "spring-boot-starter-oauth2-resource-server".into(),
+            );
+            self
+        }
+
+        pub fn spring_boot_starter_security(mut self) -> Self {
+            self = self.add_library(
+                "org.springframework.boot".into(),
+                "spring-boot-starter-security".into(),
+            );
+            self
+        }
+
         pub fn spring_boot_starter_thymeleaf(mut self) -> Self {
             self = self.add_library(
                 "org.springframework.boot".into(),
@@ -81,6 +97,14 @@ pub mod spring_packages {
             self
         }
 
+        pub fn spring_boot_starter_validation(mut self) -> Self {
+            self = self.add_library(
+                "org.springframework.boot".into(),
+                "spring-boot-starter-validation".into(),
+            );
+            self
+        }
+
         pub fn spring_boot_starter_web(mut self) -> Self {
             self = self.add_library(
                 "org.springframework.boot".into(),
@@ -103,91 +127,127 @@ pub mod spring_packages {
         }
 
         pub fn spring_boot_devtools(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.boot".into(),
-                "spring-boot-devtools".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.boot".into(),
+                    "spring-boot-devtools".into(),
+                )
+                .scope(Scope::Runtime)
+                .optional(),
             );
             self
         }
 
         pub fn spring_boot_docker_compose(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.boot".into(),
-                "spring-boot-docker-compose".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.boot".into(),
+                    "spring-boot-docker-compose".into(),
+                )
+                .scope(Scope::Runtime)
+                .optional(),
             );
             self
         }
 
         pub fn postgresql(mut self) -> Self {
-            self = self.add_library("org.postgresql".into(), "postgresql".into());
+            self = self.dependency(
+                Library::new("org.postgresql".into(), "postgresql".into()).scope(Scope::Runtime),
+            );
             self
         }
 
         pub fn lombok(mut self) -> Self {
-            self = self.add_library("org.projectlombok".into(), "lombok".into());
+            self = self
+                .dependency(Library::new("org.projectlombok".into(), "lombok".into()).optional());
             self
         }
 
         pub fn spring_boot_starter_test(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.boot".into(),
-                "spring-boot-starter-test".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.boot".into(),
+                    "spring-boot-starter-test".into(),
+                )
+                .scope(Scope::Test),
             );
             self
         }
 
         pub fn spring_boot_testcontainers(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.boot".into(),
-                "spring-boot-testcontainers".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.boot".into(),
+                    "spring-boot-testcontainers".into(),
+                )
+                .scope(Scope::Test),
             );
             self
         }
 
         pub fn spring_batch_test(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.batch".into(),
-                "spring-batch-test".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.batch".into(),
+                    "spring-batch-test".into(),
+                )
+                .scope(Scope::Test),
             );
             self
         }
 
         pub fn spring_kafka_test(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.kafka".into(),
-                "spring-kafka-test".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.kafka".into(),
+                    "spring-kafka-test".into(),
+                )
+                .scope(Scope::Test),
             );
             self
         }
 
         pub fn spring_restdocs_mockmvc(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.restdocs".into(),
-                "spring-restdocs-mockmvc".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.restdocs".into(),
+                    "spring-restdocs-mockmvc".into(),
+                )
+                .scope(Scope::Test),
             );
             self
         }
 
         pub fn spring_security_test(mut self) -> Self {
-            self = self.add_library(
-                "org.springframework.security".into(),
-                "spring-security-test".into(),
+            self = self.dependency(
+                Library::new(
+                    "org.springframework.security".into(),
+                    "spring-security-test".into(),
+                )
+                .scope(Scope::Test),
             );
             self
         }
 
         pub fn junit_jupiter(mut self) -> Self {
-            self = self.add_library("org.testcontainers".into(), "junit-jupiter".into());
+            self = self.dependency(
+                Library::new("org.testcontainers".into(), "junit-jupiter".into())
+                    .scope(Scope::Test),
+            );
             self
         }
 
         pub fn kafka(mut self) -> Self {
-            self = self.add_library("org.testcontainers".into(), "kafka".into());
+            self = self.dependency(
+                Library::new("org.testcontainers".into(), "kafka".into()).scope(Scope::Test),
+            );
             self
         }
 
         pub fn testcontainers_postgresql(mut self) -> Self {
-            self = self.add_library("org.testcontainers".into(), "postgresql".into());
+            self = self.dependency(
+                Library::new("org.testcontainers".into(), "postgresql".into()).scope(Scope::Test),
+            );
             self
         }
     }
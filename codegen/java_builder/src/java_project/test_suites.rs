@@ -0,0 +1,381 @@
+//the junit 5 tests generated with the crud classes: a @WebMvcTest per controller with its
+//services mocked, a @DataJpaTest per repository and, when the pom has testcontainers for
+//postgresql, a @SpringBootTest per entity against a real database
+use super::migrations::{param, MigrationTool};
+use crate::{
+    annotations::Annotation, classes::JavaClass, fields::Field, imports::Import, methods::Method,
+    types::GenericParams, types::TypeName,
+};
+
+pub const CONTAINERS_CONFIGURATION: &str = "TestcontainersConfiguration";
+
+//what the tests of every entity have in common, it comes from the codebase
+#[derive(Clone, Debug, Default)]
+pub struct TestSuite {
+    //the controller tests leave the security filters out, the integration tests log in
+    pub secured: bool,
+    //the integration tests bring their own key, JWT_SECRET is not set where they run
+    pub signs_tokens: bool,
+    //of the mock user of the integration tests, any logged in user when empty
+    pub read_roles: Vec<String>,
+    //the main class has @EnableJpaAuditing, the web slice has no jpa to audit with
+    pub audited: bool,
+    //@MockitoBean from spring boot 3.4 on, @MockBean before
+    pub mockito_bean: bool,
+    //the postgresql image the repository and integration tests run against, h2 without it
+    pub container_image: Option<String>,
+    //turned off on h2, hibernate creates the schema there
+    pub migrations: Option<MigrationTool>,
+}
+
+fn imports(package: &str, names: &[&str]) -> Vec<Import> {
+    names
+        .iter()
+        .map(|n| Import::new(package.to_owned(), (*n).to_owned()))
+        .collect()
+}
+
+//without the package, @jakarta.validation.constraints.NotNull counts as @NotNull
+fn has(field: &Field, name: &str) -> bool {
+    field
+        .annotation
+        .iter()
+        .any(|a| a.qualified_name.rsplit('.').next() == Some(name))
+}
+
+fn number_param(field: &Field, annotation: &str, name: &str) -> Option<i64> {
+    param(&field.annotation, annotation, name).and_then(|v| v.trim_end_matches('L').parse().ok())
+}
+
+//a field that can not be left null
+pub fn is_required(field: &Field) -> bool {
+    has(field, "NotNull")
+        || has(field, "NotBlank")
+        || has(field, "NotEmpty")
+        || param(&field.annotation, "Column", "nullable").as_deref() == Some("false")
+        || param(&field.annotation, "JoinColumn", "nullable").as_deref() == Some("false")
+        || param(&field.annotation, "ManyToOne", "optional").as_deref() == Some("false")
+        || param(&field.annotation, "OneToOne", "optional").as_deref() == Some("false")
+}
+
+//a java expression the constraints of the field accept, none for the types and constraints
+//that are not known, e.g. an enum or a @Pattern
+pub fn sample_value(field: &Field) -> Option<(String, Option<Import>)> {
+    if has(field, "Pattern") || has(field, "Null") {
+        return None;
+    }
+    let java_time = |name: &str| Some(Import::new("java.time".into(), name.into()));
+    let (past, future) = (
+        has(field, "Past") || has(field, "PastOrPresent"),
+        has(field, "Future") || has(field, "FutureOrPresent"),
+    );
+    let number = || {
+        let min = number_param(field, "Min", "value").unwrap_or(1).max(1);
+        match number_param(field, "Max", "value") {
+            Some(max) if max < min => max,
+            _ => min,
+        }
+    };
+    let value = match field.type_.name.as_str() {
+        "String" if has(field, "Email") => ("\"sample@example.com\"".to_owned(), None),
+        "String" => {
+            let min = number_param(field, "Size", "min").unwrap_or(0).max(1) as usize;
+            let max = number_param(field, "Size", "max")
+                .unwrap_or(i64::MAX)
+                .max(1) as usize;
+            let length = 6.clamp(min, max.max(min));
+            let text: String = "sample".chars().cycle().take(length).collect();
+            (format!("\"{}\"", text), None)
+        }
+        "int" | "Integer" => (number().to_string(), None),
+        "long" | "Long" => (format!("{}L", number()), None),
+        "short" | "Short" => (format!("(short) {}", number()), None),
+        "byte" | "Byte" => (format!("(byte) {}", number()), None),
+        "double" | "Double" => (format!("{}.0", number()), None),
+        "float" | "Float" => (format!("{}.0f", number()), None),
+        "boolean" | "Boolean" => ((!has(field, "AssertFalse")).to_string(), None),
+        "char" | "Character" => ("'s'".to_owned(), None),
+        "BigDecimal" => (
+            format!("BigDecimal.valueOf({})", number()),
+            Some(Import::new("java.math".into(), "BigDecimal".into())),
+        ),
+        "UUID" => (
+            "UUID.randomUUID()".to_owned(),
+            Some(Import::new("java.util".into(), "UUID".into())),
+        ),
+        type_ @ ("LocalDate" | "LocalDateTime") => {
+            let shift = match (past, future) {
+                (true, _) => ".minusDays(1)",
+                (_, true) => ".plusDays(1)",
+                _ => "",
+            };
+            (format!("{}.now(){}", type_, shift), java_time(type_))
+        }
+        "Instant" => {
+            let shift = match (past, future) {
+                (true, _) => ".minusSeconds(60)",
+                (_, true) => ".plusSeconds(3600)",
+                _ => "",
+            };
+            (format!("Instant.now(){}", shift), java_time("Instant"))
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+//an id nothing was stored with
+pub fn unknown_value(type_: &str) -> Option<(String, Option<Import>)> {
+    let value = match type_ {
+        "int" | "Integer" => "Integer.MAX_VALUE".to_owned(),
+        "long" | "Long" => "Long.MAX_VALUE".to_owned(),
+        "String" => "\"missing\"".to_owned(),
+        "UUID" => {
+            return Some((
+                "UUID.randomUUID()".to_owned(),
+                Some(Import::new("java.util".into(), "UUID".into())),
+            ))
+        }
+        _ => return None,
+    };
+    Some((value, None))
+}
+
+impl TestSuite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn mock_bean(&self) -> Import {
+        match self.mockito_bean {
+            true => Import::new(
+                "org.springframework.test.context.bean.override.mockito".into(),
+                "MockitoBean".into(),
+            ),
+            false => Import::new(
+                "org.springframework.boot.test.mock.mockito".into(),
+                "MockBean".into(),
+            ),
+        }
+    }
+
+    pub fn containers_import(root_package: &str) -> Import {
+        Import::new(root_package.to_owned(), CONTAINERS_CONFIGURATION.into())
+    }
+
+    pub fn test_class(name: String, package: String) -> JavaClass {
+        JavaClass::new(name, package)
+            .public()
+            .import(Import::new("org.junit.jupiter.api".into(), "Test".into()))
+            .import(Import::new(
+                "org.springframework.beans.factory.annotation".into(),
+                "Autowired".into(),
+            ))
+    }
+
+    //junit runs it, anything the request builders throw fails the test
+    pub fn test_method(name: String, code: String) -> Method {
+        Method::new("void".into(), name)
+            .public()
+            .annotation("Test".into())
+            .throws("Exception".into())
+            .code(code)
+    }
+
+    pub fn autowired(name: &str, type_: &str) -> Field {
+        Field::n(name.to_owned(), type_.into()).annotation(Annotation::autowired())
+    }
+
+    //a bean of the context replaced with a mockito mock
+    pub fn mocked(&self, jclass: JavaClass, name: &str, type_: &str) -> JavaClass {
+        let mock_bean = self.mock_bean();
+        let annotation = mock_bean.class_name.clone();
+        let jclass = match jclass.imports.contains(&mock_bean) {
+            true => jclass,
+            false => jclass.import(mock_bean),
+        };
+        jclass.field(Field::n(name.to_owned(), type_.into()).annotation(annotation.into()))
+    }
+
+    //the annotations every controller test has, the security filters are left out so the
+    //endpoints are called as they are
+    pub fn web_slice(&self, jclass: JavaClass, controller: &str) -> JavaClass {
+        let mut jclass = jclass
+            .annotation(
+                Annotation::new("WebMvcTest".into())
+                    .param("controllers".into(), format!("{}.class", controller)),
+            )
+            .imports(imports(
+                "org.springframework.boot.test.autoconfigure.web.servlet",
+                &["WebMvcTest"],
+            ))
+            .imports(imports(
+                "org.springframework.test.web.servlet",
+                &["MockMvc"],
+            ))
+            .field(Self::autowired("mockMvc", "MockMvc"));
+        if self.secured {
+            jclass = jclass
+                .annotation(
+                    Annotation::new("AutoConfigureMockMvc".into())
+                        .param("addFilters".into(), "false".into()),
+                )
+                .imports(imports(
+                    "org.springframework.boot.test.autoconfigure.web.servlet",
+                    &["AutoConfigureMockMvc"],
+                ));
+        }
+        //@EnableJpaAuditing of the main class looks for the jpa metamodel
+        if self.audited {
+            jclass = self
+                .mocked(
+                    jclass,
+                    "jpaMetamodelMappingContext",
+                    "JpaMetamodelMappingContext",
+                )
+                .imports(imports(
+                    "org.springframework.data.jpa.mapping",
+                    &["JpaMetamodelMappingContext"],
+                ));
+        }
+        jclass
+    }
+
+    //on h2 hibernate creates the tables, the migrations are written for the real database
+    pub fn jpa_slice(&self, jclass: JavaClass, root_package: &str) -> JavaClass {
+        let data_jpa_test = Annotation::new("DataJpaTest".into());
+        let jclass = jclass.imports(imports(
+            "org.springframework.boot.test.autoconfigure.orm.jpa",
+            &["DataJpaTest"],
+        ));
+        if self.container_image.is_some() {
+            let jclass = jclass
+                .annotation(data_jpa_test)
+                .annotation(Annotation::new("AutoConfigureTestDatabase".into()).param(
+                    "replace".into(),
+                    "AutoConfigureTestDatabase.Replace.NONE".into(),
+                ))
+                .imports(imports(
+                    "org.springframework.boot.test.autoconfigure.jdbc",
+                    &["AutoConfigureTestDatabase"],
+                ));
+            return with_containers(jclass, root_package);
+        }
+        let mut properties = vec![
+            "spring.jpa.hibernate.ddl-auto=create-drop",
+            "spring.jpa.database-platform=org.hibernate.dialect.H2Dialect",
+        ];
+        match self.migrations {
+            Some(MigrationTool::Flyway) => properties.push("spring.flyway.enabled=false"),
+            Some(MigrationTool::Liquibase) => properties.push("spring.liquibase.enabled=false"),
+            None => {}
+        }
+        let properties: Vec<String> = properties.iter().map(|p| format!("\"{}\"", p)).collect();
+        jclass.annotation(data_jpa_test.param(
+            "properties".into(),
+            format!("{{{}}}", properties.join(", ")),
+        ))
+    }
+
+    //the whole application with mock mvc, logged in when the endpoints are secured
+    pub fn application(&self, jclass: JavaClass, root_package: &str) -> JavaClass {
+        let mut spring_boot_test = Annotation::new("SpringBootTest".into());
+        if self.signs_tokens {
+            spring_boot_test = spring_boot_test.param(
+                "properties".into(),
+                "\"security.jwt.secret=${random.value}\"".into(),
+            );
+        }
+        let jclass = jclass
+            .annotation(spring_boot_test)
+            .annotation("AutoConfigureMockMvc".into())
+            .imports(imports(
+                "org.springframework.boot.test.context",
+                &["SpringBootTest"],
+            ))
+            .imports(imports(
+                "org.springframework.boot.test.autoconfigure.web.servlet",
+                &["AutoConfigureMockMvc"],
+            ))
+            .imports(imports(
+                "org.springframework.test.web.servlet",
+                &["MockMvc"],
+            ))
+            .field(Self::autowired("mockMvc", "MockMvc"));
+        let mut jclass = with_containers(jclass, root_package);
+        if self.secured {
+            let mut mock_user = Annotation::new("WithMockUser".into());
+            if !self.read_roles.is_empty() {
+                let roles: Vec<String> = self
+                    .read_roles
+                    .iter()
+                    .map(|r| format!("\"{}\"", r))
+                    .collect();
+                mock_user = mock_user.param("roles".into(), format!("{{{}}}", roles.join(", ")));
+            }
+            jclass = jclass.annotation(mock_user).imports(imports(
+                "org.springframework.security.test.context.support",
+                &["WithMockUser"],
+            ));
+        }
+        jclass
+    }
+
+    //the container is a bean, every test context that imports it starts one
+    pub fn containers_configuration(&self, root_package: String) -> Option<JavaClass> {
+        let image = self.container_image.as_ref()?;
+        let container = TypeName::new_with_generics(
+            "PostgreSQLContainer".into(),
+            GenericParams::new(vec!["?".into()]),
+        );
+        let configuration = JavaClass::new(CONTAINERS_CONFIGURATION.into(), root_package)
+            .public()
+            .annotation(
+                Annotation::new("TestConfiguration".into())
+                    .param("proxyBeanMethods".into(), "false".into()),
+            )
+            .imports(imports(
+                "org.springframework.boot.test.context",
+                &["TestConfiguration"],
+            ))
+            .imports(imports(
+                "org.springframework.boot.testcontainers.service.connection",
+                &["ServiceConnection"],
+            ))
+            .imports(imports("org.springframework.context.annotation", &["Bean"]))
+            .imports(imports(
+                "org.testcontainers.containers",
+                &["PostgreSQLContainer"],
+            ))
+            .imports(imports("org.testcontainers.utility", &["DockerImageName"]))
+            .method(
+                Method::new(container, "postgresContainer".into())
+                    .public()
+                    .annotation("Bean".into())
+                    .annotation("ServiceConnection".into())
+                    .code(format!(
+                        "return new PostgreSQLContainer<>(DockerImageName.parse(\"{}\"));",
+                        image
+                    )),
+            );
+        Some(configuration)
+    }
+}
+
+//the database of the test comes from the container configuration
+fn with_containers(jclass: JavaClass, root_package: &str) -> JavaClass {
+    let configuration = TestSuite::containers_import(root_package);
+    let jclass = jclass
+        .annotation(Annotation::new("Import".into()).param(
+            "value".into(),
+            format!("{}.class", CONTAINERS_CONFIGURATION),
+        ))
+        .imports(imports(
+            "org.springframework.context.annotation",
+            &["Import"],
+        ));
+    match configuration.package_name == jclass.package {
+        true => jclass,
+        false => jclass.import(configuration),
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use tree_sitter::{Node, Parser, Point};
+
+use super::output::CompilationUnit;
+use crate::diagnostics::Diagnostic;
+
+//what MavenCodebase::generate_code does when a compilation unit does not parse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    //nothing gets written if a single file is broken
+    #[default]
+    Refuse,
+    //files are written anyway, the errors end up in the report
+    WriteAndReport,
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyntaxError {
+    pub file: PathBuf,
+    //the closest declaration that contains the error e.g. CustomerController::createCustomer
+    pub element: String,
+    //tree-sitter positions, rows and columns are 0-based
+    pub start: Point,
+    pub end: Point,
+    //tree-sitter inserted a node that should have been there (e.g. a ';')
+    pub missing: bool,
+    pub snippet: String,
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.missing {
+            "missing"
+        } else {
+            "unexpected"
+        };
+        write!(
+            f,
+            "{}:{}:{} in {}: {} `{}`",
+            self.file.display(),
+            self.start.row + 1,
+            self.start.column + 1,
+            self.element,
+            kind,
+            self.snippet
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub files_checked: usize,
+    pub syntax_errors: Vec<SyntaxError>,
+    //results of the Linter over the model the files were generated from
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    //warnings do not make a report fail
+    pub fn is_ok(&self) -> bool {
+        self.syntax_errors.is_empty() && !self.diagnostics.iter().any(|d| d.is_error())
+    }
+
+    pub fn check(&mut self, unit: &CompilationUnit) {
+        self.files_checked += 1;
+        self.syntax_errors
+            .extend(check_syntax(&unit.path, &unit.code));
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Checked {} files, found {} syntax errors and {} diagnostics",
+            self.files_checked,
+            self.syntax_errors.len(),
+            self.diagnostics.len()
+        )?;
+        for err in self.syntax_errors.iter() {
+            writeln!(f, "{}", err)?;
+        }
+        for d in self.diagnostics.iter() {
+            writeln!(f, "{}", d)?;
+        }
+        Ok(())
+    }
+}
+
+fn make_java_parser() -> Parser {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_java::LANGUAGE.into())
+        .expect("Error loading java grammar");
+    parser
+}
+
+pub fn check_syntax(file: &Path, java_src: &str) -> Vec<SyntaxError> {
+    let mut parser = make_java_parser();
+    let Some(tree) = parser.parse(java_src, None) else {
+        return vec![];
+    };
+    let mut errors = vec![];
+    collect_errors(tree.root_node(), java_src.as_bytes(), file, &mut errors);
+    errors
+}
+
+fn collect_errors(node: Node, src: &[u8], file: &Path, errors: &mut Vec<SyntaxError>) {
+    if node.is_error() || node.is_missing() {
+        let snippet = if node.is_missing() {
+            node.kind().to_owned()
+        } else {
+            node.utf8_text(src)
+                .unwrap_or_default()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_owned()
+        };
+        errors.push(SyntaxError {
+            file: file.to_path_buf(),
+            element: enclosing_element(node, src, file),
+            start: node.start_position(),
+            end: node.end_position(),
+            missing: node.is_missing(),
+            snippet,
+        });
+        //the children of an ERROR node are part of the same error
+        return;
+    }
+    if !node.has_error() {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_errors(child, src, file, errors);
+    }
+}
+
+fn enclosing_element(node: Node, src: &[u8], file: &Path) -> String {
+    let mut names = vec![];
+    let mut current = node.parent();
+    while let Some(n) = current {
+        let name = match n.kind() {
+            "class_declaration"
+            | "interface_declaration"
+            | "enum_declaration"
+            | "record_declaration"
+            | "method_declaration"
+            | "constructor_declaration" => n.child_by_field_name("name"),
+            "field_declaration" => n
+                .child_by_field_name("declarator")
+                .and_then(|d| d.child_by_field_name("name")),
+            _ => None,
+        };
+        if let Some(text) = name.and_then(|name| name.utf8_text(src).ok()) {
+            names.push(text.to_owned());
+        }
+        current = n.parent();
+    }
+    if names.is_empty() {
+        //the error is outside of any declaration, e.g. a broken class header
+        return file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_owned();
+    }
+    names.reverse();
+    names.join("::")
+}
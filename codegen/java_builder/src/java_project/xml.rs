@@ -0,0 +1,266 @@
+//a tiny xml tree, enough to render build files with proper escaping and indentation
+//and to read existing ones back without losing comments or unknown elements
+use super::error::CodegenError;
+
+const INDENT: &str = "    ";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+    Comment(String),
+    //a comment on the same line as the element before it, e.g. after <relativePath/>
+    InlineComment(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+impl From<XmlElement> for XmlNode {
+    fn from(e: XmlElement) -> Self {
+        XmlNode::Element(e)
+    }
+}
+
+impl XmlElement {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+
+    //<name>value</name>
+    pub fn text(name: &str, value: &str) -> Self {
+        Self::new(name).child(XmlNode::Text(value.to_owned()))
+    }
+
+    pub fn attr(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn child(mut self, node: impl Into<XmlNode>) -> Self {
+        self.children.push(node.into());
+        self
+    }
+
+    pub fn children(mut self, nodes: Vec<XmlNode>) -> Self {
+        self.children.extend(nodes);
+        self
+    }
+
+    //skips the element when there is nothing to put in it
+    pub fn text_if_some(self, name: &str, value: Option<&str>) -> Self {
+        match value {
+            Some(v) => self.child(XmlElement::text(name, v)),
+            None => self,
+        }
+    }
+
+    pub fn child_if_not_empty(self, element: XmlElement) -> Self {
+        if element.children.is_empty() {
+            self
+        } else {
+            self.child(element)
+        }
+    }
+
+    pub fn element_children(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|c| match c {
+            XmlNode::Element(e) => Some(e),
+            _ => None,
+        })
+    }
+
+    pub fn find(&self, name: &str) -> Option<&XmlElement> {
+        self.element_children().find(|e| e.name == name)
+    }
+
+    //the text content of the element, trimmed
+    pub fn text_content(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|c| match c {
+                XmlNode::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect::<String>()
+            .trim()
+            .to_owned()
+    }
+
+    pub fn find_text(&self, name: &str) -> Option<String> {
+        self.find(name).map(|e| e.text_content())
+    }
+
+    //the xml declaration and anything outside of the root element are dropped
+    pub fn parse(xml: &str) -> Result<XmlElement, CodegenError> {
+        let document =
+            roxmltree::Document::parse(xml).map_err(|e| CodegenError::Parse(e.to_string()))?;
+        Ok(from_node(document.root_element()))
+    }
+
+    pub fn render(&self) -> String {
+        let mut result = "".to_owned();
+        self.render_into(&mut result, 0);
+        result
+    }
+
+    fn render_into(&self, result: &mut String, depth: usize) {
+        let indent = INDENT.repeat(depth);
+        result.push_str(&indent);
+        result.push('<');
+        result.push_str(&self.name);
+        for (name, value) in self.attributes.iter() {
+            result.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+        }
+        if self.children.is_empty() {
+            result.push_str("/>\n");
+            return;
+        }
+        result.push('>');
+        if self.children.iter().all(|c| matches!(c, XmlNode::Text(_))) {
+            result.push_str(&escape(&self.text_content()));
+        } else {
+            result.push('\n');
+            for child in self.children.iter() {
+                match child {
+                    XmlNode::Element(e) => e.render_into(result, depth + 1),
+                    XmlNode::Comment(c) => {
+                        result.push_str(&format!("{}{}<!--{}-->\n", indent, INDENT, c))
+                    }
+                    XmlNode::InlineComment(c) => {
+                        result.pop();
+                        result.push_str(&format!(" <!--{}-->\n", c))
+                    }
+                    XmlNode::Text(t) if t.trim().is_empty() => {}
+                    XmlNode::Text(t) => {
+                        result.push_str(&format!("{}{}{}\n", indent, INDENT, escape(t.trim())))
+                    }
+                }
+            }
+            result.push_str(&indent);
+        }
+        result.push_str(&format!("</{}>\n", self.name));
+    }
+}
+
+//comments of a parsed element and its lists, by path ("" for the element itself)
+//and the number of elements they came after, so they can be put back when rendering
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comments(Vec<(String, usize, XmlNode)>);
+
+impl Comments {
+    pub fn collect(&mut self, path: &str, element: &XmlElement) {
+        let mut index = 0;
+        for child in element.children.iter() {
+            match child {
+                XmlNode::Element(_) => index += 1,
+                XmlNode::Comment(_) | XmlNode::InlineComment(_) => {
+                    self.0.push((path.to_owned(), index, child.clone()))
+                }
+                XmlNode::Text(_) => {}
+            }
+        }
+    }
+
+    //the comments go in front of the element at their index, the rest at the end
+    pub fn apply(&self, path: &str, mut element: XmlElement) -> XmlElement {
+        let mut comments = self.0.iter().filter(|(p, _, _)| p == path).peekable();
+        if comments.peek().is_none() {
+            return element;
+        }
+        let mut children = vec![];
+        let mut index = 0;
+        for child in element.children.drain(..) {
+            if let XmlNode::Element(_) = child {
+                while let Some((_, _, comment)) = comments.next_if(|(_, i, _)| *i <= index) {
+                    children.push(comment.clone());
+                }
+                index += 1;
+            }
+            children.push(child);
+        }
+        children.extend(comments.map(|(_, _, comment)| comment.clone()));
+        element.children = children;
+        element
+    }
+}
+
+fn from_node(node: roxmltree::Node) -> XmlElement {
+    let mut element = XmlElement::new(&qualified_name(
+        node,
+        node.tag_name().namespace(),
+        node.tag_name().name(),
+    ));
+    //namespaces are declared once on the root, roxmltree repeats them on every node
+    if node.parent().is_some_and(|p| p.is_root()) {
+        for ns in node.namespaces() {
+            match ns.name() {
+                Some(prefix) => element = element.attr(&format!("xmlns:{}", prefix), ns.uri()),
+                None => element = element.attr("xmlns", ns.uri()),
+            }
+        }
+    }
+    for a in node.attributes() {
+        element = element.attr(&qualified_name(node, a.namespace(), a.name()), a.value());
+    }
+    for child in node.children() {
+        match child.node_type() {
+            roxmltree::NodeType::Element => element = element.child(from_node(child)),
+            roxmltree::NodeType::Comment => {
+                let text = child.text().unwrap_or_default().to_owned();
+                element = element.child(if follows_on_same_line(child) {
+                    XmlNode::InlineComment(text)
+                } else {
+                    XmlNode::Comment(text)
+                })
+            }
+            roxmltree::NodeType::Text if !child.text().unwrap_or_default().trim().is_empty() => {
+                element = element.child(XmlNode::Text(child.text().unwrap_or_default().to_owned()))
+            }
+            _ => {}
+        }
+    }
+    element
+}
+
+//an element right before the node, with no line break in between
+fn follows_on_same_line(node: roxmltree::Node) -> bool {
+    match node.prev_sibling() {
+        Some(prev) if prev.is_text() => {
+            !prev.text().unwrap_or_default().contains('\n')
+                && prev.prev_sibling().is_some_and(|p| p.is_element())
+        }
+        Some(prev) => prev.is_element(),
+        None => false,
+    }
+}
+
+fn qualified_name(node: roxmltree::Node, namespace: Option<&str>, name: &str) -> String {
+    match namespace.and_then(|uri| node.lookup_prefix(uri)) {
+        Some(prefix) if !prefix.is_empty() => format!("{}:{}", prefix, name),
+        _ => name.to_owned(),
+    }
+}
+
+pub fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
@@ -64,12 +64,11 @@ impl Codegen for Annotation {
         result.push('\n');
         result.push_str(&format!("@{} ", self.qualified_name));
         if let Some(ref params_list) = self.params_list {
-            result.push('(');
-            //this is wrong
-            for param in params_list {
-                result.push_str(&format!("{} = {}", param.0, param.1))
-            }
-            result.push(')');
+            let params: Vec<String> = params_list
+                .iter()
+                .map(|(name, value)| format!("{} = {}", name, value))
+                .collect();
+            result.push_str(&format!("({})", params.join(", ")));
         }
         result
     }
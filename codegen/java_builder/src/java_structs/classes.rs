@@ -1,4 +1,4 @@
-use std::{collections::HashSet, rc::Rc};
+use std::collections::HashSet;
 
 use super::{
     annotations::Annotation,
@@ -32,20 +32,14 @@ impl Codegen for JavaClass {
         result.push_str(&format!("package {};\n", self.package));
         result.push_str("\n");
 
-        if self.imports.is_empty() {
-            println!("No imports found you might have forgotten them");
-        }
         result.push_str(self.imports.generate_code().as_str());
 
         result.push_str("\n");
 
-        if self.class_modifiers.is_empty() {
-            println!("No class modifiers you might want to make your class public");
-        }
         result.push_str(&self.class_annotations.generate_code());
         result.push_str(&self.class_modifiers.generate_code());
 
-        result.push_str(&format!("class {}", self.class_name));
+        result.push_str(&format!("class {} ", self.class_name));
         result.push_str(&self.generic_params.generate_code());
 
         if let Some(ref superclass) = self.superclass {
@@ -79,7 +73,6 @@ impl JavaClass {
     }
 
     pub fn generic_param(mut self, generic: String) -> Self {
-        assert!(!generic.is_empty(), "Empty  Params are not allowed");
         self.generic_params.generics.push(generic);
         self
     }
@@ -114,10 +107,7 @@ impl JavaClass {
     }
     pub fn new(class_name: String, package: String) -> JavaClass {
         //package can be empty as it might change for the codegen process
-        assert!(
-            !class_name.is_empty(),
-            "You forgot to include the class name"
-        );
+        //an empty class name is reported by the Linter
         JavaClass {
             imports: vec![],
             class_name,
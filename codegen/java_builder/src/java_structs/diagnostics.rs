@@ -0,0 +1,55 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    //short kebab-case id of the check e.g. duplicate-method
+    pub rule: &'static str,
+    //the element the diagnostic is about e.g. CustomerController::createCustomer
+    pub element: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn warning(rule: &'static str, element: String, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            rule,
+            element,
+            message,
+        }
+    }
+
+    pub fn error(rule: &'static str, element: String, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            rule,
+            element,
+            message,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            severity, self.rule, self.element, self.message
+        )
+    }
+}
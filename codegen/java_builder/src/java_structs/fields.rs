@@ -31,7 +31,9 @@ impl Into<Field> for JavaClass {
 
 impl Into<Field> for VariableParam {
     fn into(self) -> Field {
-        todo!()
+        let mut f = Field::n(self.name, self.type_);
+        f.annotation = self.annotation;
+        f
     }
 }
 
@@ -107,11 +109,11 @@ impl Codegen for Field {
             result.push_str(&format!("{} ", <AccessModifiers as Into<String>>::into(m)));
         }
         result.push_str(&format!("{} ", self.type_.generate_code()));
-        result.push_str(&format!("{};\n", self.name));
-
+        result.push_str(&self.name);
         if let Some(ref init) = self.initializer {
-            result.push_str(&format!("= {}", init));
+            result.push_str(&format!(" = {}", init));
         }
+        result.push_str(";\n");
         result
     }
 }
@@ -1,6 +1,6 @@
 use super::Codegen;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Import {
     //import org.codegen.package.class_name
     pub class_name: String,
@@ -13,7 +13,8 @@ pub struct Interface {
     pub annotations: Vec<Annotation>,
     pub package: String,
     pub imports: Vec<Import>,
-    pub superclass: Option<TypeName>,
+    //an interface can extend several others
+    pub extends: Vec<TypeName>,
     pub name: String,
     pub methods: Vec<Method>,
     pub modifier: AccessModifiers,
@@ -47,10 +48,6 @@ impl Into<JavaClass> for Interface {
 }
 impl Codegen for Interface {
     fn generate_code(&self) -> String {
-        assert!(
-            &self.methods.iter().all(|m| m.code.is_empty()),
-            "Interface methods should have an empty body"
-        );
         let mut result = "".to_owned();
         result.push_str(&format!("package {};\n", self.package));
         result.push_str(&self.imports.generate_code());
@@ -58,17 +55,26 @@ impl Codegen for Interface {
         result.push_str(&(vec![self.modifier].generate_code()));
         result.push_str(&format!("interface {} ", self.name));
 
-        if let Some(ref superclass) = self.superclass {
-            result.push_str(&format!("extends {}", superclass.name));
-            if let Some(ref generics) = superclass.generic_params {
-                result.push_str(&generics.generate_code());
-            }
+        if !self.extends.is_empty() {
+            let extended: Vec<String> = self
+                .extends
+                .iter()
+                .map(|sup| sup.generate_code().trim_end().to_owned())
+                .collect();
+            result.push_str(&format!("extends {}", extended.join(", ")));
             result.push(' ');
         }
         result.push('{');
         result.push('\n');
 
+        //bodies are reported by the Linter, only the signatures are emitted
         for m in &self.methods {
+            //spring data reads @Query and the like from the signature
+            for ann in &m.annotations {
+                result.push('\t');
+                result.push_str(ann.generate_code().trim());
+                result.push('\n');
+            }
             result.push('\t');
             result.push_str(&m.modifiers.generate_code());
             if !(&m.generics.generics.is_empty()) {
@@ -80,6 +86,9 @@ impl Codegen for Interface {
             result.push(' ');
             result.push_str(&m.name);
             result.push_str(&m.parameters.generate_code());
+            if !m.throws.is_empty() {
+                result.push_str(&format!(" throws {}", m.throws.join(", ")));
+            }
             result.push(';');
             result.push('\n');
         }
@@ -110,7 +119,7 @@ impl Interface {
             generics: GenericParams::new(vec![]),
             modifier: AccessModifiers::Public,
             methods: vec![],
-            superclass: None,
+            extends: vec![],
             imports: vec![],
             annotations: vec![],
         }
@@ -151,7 +160,7 @@ impl Interface {
     }
 
     pub fn extends(mut self, sup: TypeName) -> Self {
-        self.superclass = Some(sup);
+        self.extends.push(sup);
         self
     }
 
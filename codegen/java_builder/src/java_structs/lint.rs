@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    classes::JavaClass,
+    diagnostics::Diagnostic,
+    imports::Import,
+    interfaces::Interface,
+    methods::Method,
+    modifiers::{conflicting_modifiers, AccessModifiers},
+    types::TypeName,
+};
+
+const PRIMITIVES: [&str; 9] = [
+    "int", "long", "short", "byte", "double", "float", "boolean", "char", "void",
+];
+
+//java.lang is imported implicitly, only the commonly used types are listed
+const JAVA_LANG: [&str; 30] = [
+    "String",
+    "Object",
+    "Integer",
+    "Long",
+    "Short",
+    "Byte",
+    "Double",
+    "Float",
+    "Boolean",
+    "Character",
+    "Number",
+    "Void",
+    "Math",
+    "System",
+    "Class",
+    "Enum",
+    "Record",
+    "Iterable",
+    "Comparable",
+    "Runnable",
+    "Thread",
+    "CharSequence",
+    "StringBuilder",
+    "Throwable",
+    "Exception",
+    "RuntimeException",
+    "Error",
+    "IllegalArgumentException",
+    "IllegalStateException",
+    "Override",
+];
+
+//checks over the in-memory model, things that parse fine but are still wrong
+#[derive(Default)]
+pub struct Linter {
+    //types that are generated in the same codebase, they might not need an import
+    known_types: HashSet<String>,
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn known_type(mut self, name: String) -> Self {
+        self.known_types.insert(name);
+        self
+    }
+
+    pub fn known_types(mut self, names: Vec<String>) -> Self {
+        self.known_types.extend(names);
+        self
+    }
+
+    pub fn lint_class(&self, cls: &JavaClass, file_stem: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let name = &cls.class_name;
+        if name.is_empty() {
+            diagnostics.push(Diagnostic::error(
+                "empty-name",
+                file_stem.to_owned(),
+                "Class name is empty".to_owned(),
+            ));
+        }
+        if cls.generic_params.generics.iter().any(|g| g.is_empty()) {
+            diagnostics.push(Diagnostic::error(
+                "empty-name",
+                name.to_owned(),
+                "Empty generic params are not allowed".to_owned(),
+            ));
+        }
+        let is_public = cls.class_modifiers.contains(&AccessModifiers::Public);
+        check_top_level_type(name, is_public, file_stem, &mut diagnostics);
+        check_modifiers(name, &cls.class_modifiers, &mut diagnostics);
+        for m in cls.methods.iter() {
+            check_modifiers(
+                &format!("{}::{}", name, m.name),
+                &m.modifiers,
+                &mut diagnostics,
+            );
+        }
+
+        let is_abstract = cls.class_modifiers.contains(&AccessModifiers::Abstract);
+        for m in cls.methods.iter() {
+            if !m.modifiers.contains(&AccessModifiers::Abstract) {
+                continue;
+            }
+            if !is_abstract {
+                diagnostics.push(Diagnostic::error(
+                    "abstract-method-in-concrete-class",
+                    format!("{}::{}", name, m.name),
+                    "Abstract methods are only allowed in abstract classes".to_owned(),
+                ));
+            }
+            if !m.code.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    "abstract-method-with-body",
+                    format!("{}::{}", name, m.name),
+                    "Abstract methods should not have a body".to_owned(),
+                ));
+            }
+        }
+        check_duplicate_methods(name, &cls.methods, &mut diagnostics);
+
+        let generics: Vec<&String> = cls.generic_params.generics.iter().collect();
+        let mut fields: Vec<_> = cls.fields.iter().collect();
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        for f in fields {
+            check_modifiers(
+                &format!("{}::{}", name, f.name),
+                &f.modifiers,
+                &mut diagnostics,
+            );
+            for type_ in self.unresolved_types(&f.type_, &cls.imports, &generics) {
+                diagnostics.push(Diagnostic::warning(
+                    "unknown-type",
+                    format!("{}::{}", name, f.name),
+                    format!("{} is neither imported nor generated", type_),
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    pub fn lint_interface(&self, interface: &Interface, file_stem: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let name = &interface.name;
+        let is_public = interface.modifier == AccessModifiers::Public;
+        check_top_level_type(name, is_public, file_stem, &mut diagnostics);
+        for m in interface.methods.iter().filter(|m| !m.code.is_empty()) {
+            diagnostics.push(Diagnostic::error(
+                "interface-method-with-body",
+                format!("{}::{}", name, m.name),
+                "Interface methods should have an empty body".to_owned(),
+            ));
+        }
+        check_duplicate_methods(name, &interface.methods, &mut diagnostics);
+        diagnostics
+    }
+
+    fn unresolved_types(
+        &self,
+        type_: &TypeName,
+        imports: &[Import],
+        generics: &[&String],
+    ) -> Vec<String> {
+        //a wildcard import might provide anything
+        if imports.iter().any(|i| i.class_name == "*") {
+            return vec![];
+        }
+        let mut names = identifiers_in(&type_.name);
+        if let Some(ref params) = type_.generic_params {
+            for g in params.generics.iter() {
+                names.extend(identifiers_in(g));
+            }
+        }
+        names
+            .into_iter()
+            .filter(|n| !n.contains('.'))
+            .filter(|n| !PRIMITIVES.contains(&n.as_str()) && !JAVA_LANG.contains(&n.as_str()))
+            .filter(|n| !generics.contains(&n))
+            .filter(|n| !imports.iter().any(|i| &i.class_name == n))
+            .filter(|n| !self.known_types.contains(n))
+            .collect()
+    }
+}
+
+fn identifiers_in(type_: &str) -> Vec<String> {
+    type_
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '$'))
+        .filter(|s| !s.is_empty() && *s != "extends" && *s != "super")
+        .map(str::to_owned)
+        .collect()
+}
+
+fn check_top_level_type(
+    name: &str,
+    is_public: bool,
+    file_stem: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if !is_public {
+        diagnostics.push(Diagnostic::warning(
+            "missing-public",
+            name.to_owned(),
+            "Top level type is not public and cannot be used outside its package".to_owned(),
+        ));
+    }
+    if name != file_stem {
+        diagnostics.push(Diagnostic::error(
+            "file-name-mismatch",
+            name.to_owned(),
+            format!("Type {} is written in {}.java", name, file_stem),
+        ));
+    }
+}
+
+fn check_modifiers(
+    element: &str,
+    modifiers: &[AccessModifiers],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (a, b) in conflicting_modifiers(modifiers) {
+        diagnostics.push(Diagnostic::error(
+            "conflicting-modifiers",
+            element.to_owned(),
+            format!("Modifiers {:?} and {:?} should not be used together", a, b),
+        ));
+    }
+}
+
+fn check_duplicate_methods(owner: &str, methods: &[Method], diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for m in methods.iter() {
+        //generics are erased so they do not count towards the signature
+        let params: Vec<&str> = m.parameters.iter().map(|p| p.type_.name.as_str()).collect();
+        let signature = format!("{}({})", m.name, params.join(","));
+        let count = seen.entry(signature.clone()).or_default();
+        *count += 1;
+        if *count == 2 {
+            diagnostics.push(Diagnostic::error(
+                "duplicate-method",
+                format!("{}::{}", owner, m.name),
+                format!("{} is declared more than once", signature),
+            ));
+        }
+    }
+}
@@ -12,7 +12,8 @@ pub struct Method {
     pub return_type: TypeName,
     pub code: String,
     pub name: String,
-    //add throws clause
+    //checked exceptions in the throws clause
+    pub throws: Vec<String>,
 }
 
 #[derive(Clone)]
@@ -50,13 +51,6 @@ impl Codegen for Vec<VariableParam> {
 }
 impl Codegen for Method {
     fn generate_code(&self) -> String {
-        if self.modifiers.contains(&AccessModifiers::Abstract) {
-            assert!(
-                &self.code.is_empty(),
-                "Abstract methods should not have a body"
-            );
-        }
-
         let mut result = "".to_string();
 
         //reminder: it is valid code to not have modifiers
@@ -67,7 +61,11 @@ impl Codegen for Method {
         result.push_str(&format!("{} ", self.return_type.generate_code()));
         result.push_str(&format!("{}", self.name));
         result.push_str(&self.parameters.generate_code());
+        if !self.throws.is_empty() {
+            result.push_str(&format!(" throws {}", self.throws.join(", ")));
+        }
 
+        //a body on an abstract method is reported by the Linter and dropped here
         if self.modifiers.contains(&AccessModifiers::Abstract) {
             result.push(';');
             result.push('\n');
@@ -97,6 +95,7 @@ impl Method {
             parameters: vec![],
             modifiers: vec![],
             code: "".to_owned(),
+            throws: vec![],
         }
     }
 
@@ -154,4 +153,9 @@ impl Method {
         self.parameters.push(v);
         self
     }
+
+    pub fn throws(mut self, exception: String) -> Self {
+        self.throws.push(exception);
+        self
+    }
 }
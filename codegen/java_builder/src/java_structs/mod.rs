@@ -9,6 +9,9 @@ pub mod types;
 pub mod classes;
 pub mod methods;
 pub mod modifiers;
+
+pub mod diagnostics;
+pub mod lint;
 use std::hash::{Hash, Hasher};
 
 use annotations::Annotation;
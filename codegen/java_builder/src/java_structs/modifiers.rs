@@ -23,29 +23,7 @@ impl Codegen for Vec<AccessModifiers> {
         let mut result = "".to_string();
         let mut modifiers = self.clone();
         modifiers.sort_by(|a, b| b.cmp(a));
-        //more rules for modifiers
-        assert!(
-            !(modifiers.contains(&AccessModifiers::Public)
-                && modifiers.contains(&AccessModifiers::Protected)),
-            "Modifiers {:?} and {:?} should not be used together",
-            &AccessModifiers::Public,
-            &AccessModifiers::Protected
-        );
-        assert!(
-            !(modifiers.contains(&AccessModifiers::Protected)
-                && modifiers.contains(&AccessModifiers::Private)),
-            "Modifiers {:?} and {:?} should not be used together",
-            &AccessModifiers::Protected,
-            &AccessModifiers::Private
-        );
-        assert!(
-            !(modifiers.contains(&AccessModifiers::Public)
-                && modifiers.contains(&AccessModifiers::Private)),
-            "Modifiers {:?} and {:?} should not be used together",
-            &AccessModifiers::Public,
-            &AccessModifiers::Private
-        );
-
+        //conflicting modifiers are reported by the Linter
         modifiers.dedup();
         for m in modifiers.iter() {
             result.push_str(&format!("{} ", <AccessModifiers as Into<String>>::into(*m)));
@@ -53,6 +31,22 @@ impl Codegen for Vec<AccessModifiers> {
         result
     }
 }
+//pairs that are not allowed on the same declaration
+pub fn conflicting_modifiers(
+    modifiers: &[AccessModifiers],
+) -> Vec<(AccessModifiers, AccessModifiers)> {
+    let conflicts = [
+        (AccessModifiers::Public, AccessModifiers::Protected),
+        (AccessModifiers::Protected, AccessModifiers::Private),
+        (AccessModifiers::Public, AccessModifiers::Private),
+        (AccessModifiers::Abstract, AccessModifiers::Final),
+    ];
+    conflicts
+        .into_iter()
+        .filter(|(a, b)| modifiers.contains(a) && modifiers.contains(b))
+        .collect()
+}
+
 impl Into<String> for AccessModifiers {
     fn into(self) -> String {
         match self {
@@ -61,9 +61,9 @@ impl Into<VariableParam> for TypeName {
 impl Into<TypeName> for JavaClass {
     fn into(self) -> TypeName {
         if self.generic_params.generics.is_empty() {
-            return TypeName::new(self.class_name);
+            TypeName::new(self.class_name)
         } else {
-            todo!("If a class has generics how can i convert its generics into a typename?");
+            TypeName::new_with_generics(self.class_name, self.generic_params)
         }
     }
 }
@@ -71,9 +71,9 @@ impl Into<TypeName> for JavaClass {
 impl Into<TypeName> for Interface {
     fn into(self) -> TypeName {
         if self.generics.generics.is_empty() {
-            return TypeName::new(self.name);
+            TypeName::new(self.name)
         } else {
-            todo!("If a class has generics how can i convert its generics into a typename?");
+            TypeName::new_with_generics(self.name, self.generics)
         }
     }
 }
@@ -20,7 +20,9 @@ mod integration_tests {
         mvn_code = mvn_code.add_entity(example.clone());
         let addr = mvn_code.get_server_addr();
         let db = mvn_code.get_db_info().to_owned();
-        mvn_code.generate_code();
+        mvn_code
+            .generate_code()
+            .expect("Generated code has syntax errors");
         //FYI this spawns and kills a postgres container
         assert_spring_server_is_up(addr, &db, top_folder);
     }
@@ -37,9 +39,13 @@ mod integration_tests {
         let example = sample_class(&pom_xml);
         let mut mvn_code = MavenCodebase::new(pom_xml, top_folder);
         mvn_code = mvn_code.add_entity(example.clone());
-        mvn_code.generate_code();
+        mvn_code
+            .generate_code()
+            .expect("Generated code has syntax errors");
 
-        let path = mvn_code.extract_to_zip();
+        let path = mvn_code
+            .extract_to_zip()
+            .expect("Generated project could not be zipped");
         assert!(
             path.to_str().unwrap().ends_with("zip"),
             "Seems like the zip dir was not properly generated"
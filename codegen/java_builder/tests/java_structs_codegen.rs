@@ -6,10 +6,12 @@ pub mod java_structs_tests {
     use java_builder::{
         annotations::Annotation,
         classes::JavaClass,
+        diagnostics::{Diagnostic, Severity},
         enums::JavaEnum,
         fields::Field,
         imports::Import,
         interfaces::Interface,
+        lint::Linter,
         methods::Method,
         modifiers::AccessModifiers,
         types::{GenericParams, TypeName},
@@ -19,8 +21,7 @@ pub mod java_structs_tests {
     use crate::common::assert_program_is_syntactically_correct;
 
     #[test]
-    #[should_panic]
-    fn panics_when_interface_method_has_body() {
+    fn lint_flags_interface_method_with_body() {
         let i = Interface::new(
             "org.openapi.tools".to_owned(),
             "WithNonEmptyMethods".to_owned(),
@@ -29,16 +30,17 @@ pub mod java_structs_tests {
             Method::new(TypeName::new("void".to_owned()), "addToList".to_owned())
                 .code(r#"System.out.println(\"Default Impl\")"#.to_owned());
         let i = i.methods(vec![non_empty_body_method]);
-        i.generate_code();
+        let diagnostics = Linter::new().lint_interface(&i, "WithNonEmptyMethods");
+        assert_has_diagnostic(&diagnostics, "interface-method-with-body", Severity::Error);
+        assert_program_is_syntactically_correct(&i.generate_code());
     }
 
     #[test]
-    #[should_panic]
-    fn panics_when_modifiers_protected_and_public() {
-        let modifiers: Vec<AccessModifiers> =
-            vec![AccessModifiers::Protected, AccessModifiers::Public];
-        let _result = modifiers.generate_code();
-        //println!("Result: {}", result);
+    fn lint_flags_modifiers_protected_and_public() {
+        let cls = JavaClass::new("Modifiers".to_owned(), "org.modifiers".to_owned())
+            .class_modifiers(vec![AccessModifiers::Protected, AccessModifiers::Public]);
+        let diagnostics = Linter::new().lint_class(&cls, "Modifiers");
+        assert_has_diagnostic(&diagnostics, "conflicting-modifiers", Severity::Error);
     }
 
     //testing could be per element to decrease the surface
@@ -281,31 +283,87 @@ pub mod java_structs_tests {
     }
 
     #[test]
-    #[should_panic]
-    fn panics_when_modifiers_private_and_public() {
-        let modifiers: Vec<AccessModifiers> =
-            vec![AccessModifiers::Private, AccessModifiers::Public];
-        let result = modifiers.generate_code();
-        //println!("Result: {}", result);
+    fn lint_flags_modifiers_private_and_public() {
+        let cls = JavaClass::new("Modifiers".to_owned(), "org.modifiers".to_owned())
+            .class_modifiers(vec![AccessModifiers::Private, AccessModifiers::Public]);
+        let diagnostics = Linter::new().lint_class(&cls, "Modifiers");
+        assert_has_diagnostic(&diagnostics, "conflicting-modifiers", Severity::Error);
     }
 
     #[test]
-    #[should_panic]
-    fn panics_when_abstract_method_has_body() {
+    fn lint_flags_abstract_method_with_body() {
         let m = Method::new(TypeName::new("void".to_string()), "Greeting".to_string())
             .abstract_()
             .code("System.out.println('Hello World');".to_string());
+        let cls = JavaClass::new("Greeter".to_owned(), "org.greetings".to_owned())
+            .public()
+            .method(m.clone());
 
-        m.generate_code();
+        let diagnostics = Linter::new().lint_class(&cls, "Greeter");
+        assert_has_diagnostic(&diagnostics, "abstract-method-with-body", Severity::Error);
+        assert_has_diagnostic(
+            &diagnostics,
+            "abstract-method-in-concrete-class",
+            Severity::Error,
+        );
+        assert!(!m.generate_code().contains("Hello World"));
     }
 
     #[test]
-    #[should_panic]
-    fn panics_when_modifiers_protected_and_private() {
-        let modifiers: Vec<AccessModifiers> =
-            vec![AccessModifiers::Protected, AccessModifiers::Private];
-        let result = modifiers.generate_code();
-        println!("Result: {}", result);
+    fn lint_flags_model_mistakes() {
+        let greet = Method::new(TypeName::new("void".to_owned()), "greet".to_owned())
+            .param(VariableParam::new("String".into(), "name".into()));
+        let cls = JavaClass::new("Greeter".to_owned(), "org.greetings".to_owned())
+            .import(Import::new("java.util".to_owned(), "List".to_owned()))
+            .field(Field::n(
+                "greetings".to_owned(),
+                TypeName::new_with_generics(
+                    "List".to_owned(),
+                    GenericParams::new(vec!["Greeting".to_owned()]),
+                ),
+            ))
+            .field(Field::n("names".to_owned(), "List".into()))
+            .method(greet.clone())
+            .method(greet);
+
+        let diagnostics = Linter::new().lint_class(&cls, "Greeters");
+        assert_has_diagnostic(&diagnostics, "duplicate-method", Severity::Error);
+        assert_has_diagnostic(&diagnostics, "missing-public", Severity::Warning);
+        assert_has_diagnostic(&diagnostics, "file-name-mismatch", Severity::Error);
+        assert_has_diagnostic(&diagnostics, "unknown-type", Severity::Warning);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .filter(|d| d.rule == "unknown-type")
+                .count(),
+            1,
+            "Only Greeting should be reported as an unknown type"
+        );
+
+        let generated = Linter::new()
+            .known_type("Greeting".to_owned())
+            .lint_class(&cls, "Greeter");
+        assert!(!generated.iter().any(|d| d.rule == "unknown-type"));
+    }
+
+    #[test]
+    fn lint_flags_modifiers_protected_and_private() {
+        let cls = JavaClass::new("Modifiers".to_owned(), "org.modifiers".to_owned())
+            .class_modifiers(vec![AccessModifiers::Protected, AccessModifiers::Private]);
+        let diagnostics = Linter::new().lint_class(&cls, "Modifiers");
+        assert_has_diagnostic(&diagnostics, "conflicting-modifiers", Severity::Error);
+    }
+
+    fn assert_has_diagnostic(diagnostics: &[Diagnostic], rule: &str, severity: Severity) {
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.rule == rule && d.severity == severity),
+            "Expected a {:?} for {} but got {:?}",
+            severity,
+            rule,
+            diagnostics
+        );
     }
 
     fn assert_modifiers_are_generated(java_str: &str, modifiers: Vec<AccessModifiers>) {
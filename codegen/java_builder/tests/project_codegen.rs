@@ -44,7 +44,9 @@ mod java_project_tests {
         let example = sample_class(&pom_xml);
         let mut mvn_code = MavenCodebase::new(pom_xml, top_folder);
         mvn_code = mvn_code.add_entity(example.clone());
-        mvn_code.generate_code();
+        mvn_code
+            .generate_code()
+            .expect("Generated code has syntax errors");
         //there is a file that contains the name of the class  i provided
         assert_a_class_file_exists_in_that(top_folder, |content| {
             content.contains(&example.class_name)
@@ -72,9 +74,262 @@ mod java_project_tests {
         // cleanup_folder(top_folder);
     }
 
+    #[test]
+    fn refuses_to_write_project_with_syntax_errors() {
+        let top_folder = "generated-broken";
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml.java_version("17".to_owned()).spring_boot();
+        let broken = sample_class(&pom_xml).method(
+            Method::new(TypeName::new("void".into()), "broken".into())
+                .public()
+                .code("int x = ;".into()),
+        );
+        let mut mvn_code = MavenCodebase::new(pom_xml, top_folder).add_entity(broken);
+        let Err(CodegenError::InvalidModel(report)) = mvn_code.generate_code() else {
+            panic!("Broken method body was not caught");
+        };
+        assert!(!report.is_ok());
+        assert!(
+            report
+                .syntax_errors
+                .iter()
+                .all(|e| e.element.ends_with("::broken") && e.start.row > 0),
+            "Syntax errors do not point to the broken method {}",
+            report
+        );
+        assert!(
+            !Path::new(top_folder).exists(),
+            "Files were written even though validation failed"
+        );
+    }
+
+    #[test]
+    fn writes_and_reports_syntax_errors_when_asked() {
+        let top_folder = "generated-broken-reported";
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml.java_version("17".to_owned()).spring_boot();
+        let broken = sample_class(&pom_xml).method(
+            Method::new(TypeName::new("void".into()), "broken".into())
+                .public()
+                .code("int x = ;".into()),
+        );
+        let mut mvn_code = MavenCodebase::new(pom_xml, top_folder)
+            .validation(ValidationMode::WriteAndReport)
+            .add_entity(broken);
+        let report = mvn_code
+            .generate_code()
+            .expect("Validation should only report errors");
+        assert!(!report.is_ok());
+        assert!(report.files_checked > 1);
+        assert_a_class_file_exists_in_that(top_folder, |content| content.contains("broken"));
+        cleanup_folder(top_folder);
+    }
+
+    #[test]
+    fn generated_crud_model_has_no_lint_errors() {
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml.java_version("17".to_owned()).spring_boot();
+        let example = sample_class(&pom_xml);
+        let mut mvn_code = MavenCodebase::new(pom_xml, "generated-lint").add_entity(example);
+        let diagnostics = mvn_code.lint();
+        assert!(
+            !diagnostics.iter().any(|d| d.is_error()),
+            "Generated model has lint errors {:?}",
+            diagnostics
+        );
+        assert!(
+            !diagnostics.iter().any(|d| d.rule == "missing-public"),
+            "All generated types should be public {:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn reports_io_errors_instead_of_panicking() {
+        let top_folder = "generated-file-in-the-way";
+        std::fs::write(top_folder, "not a folder").unwrap();
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml.java_version("17".to_owned()).spring_boot();
+        let example = sample_class(&pom_xml);
+        let mut mvn_code = MavenCodebase::new(pom_xml, top_folder).add_entity(example);
+        let result = mvn_code.generate_code();
+        std::fs::remove_file(top_folder).unwrap();
+        assert!(
+            matches!(result, Err(CodegenError::Io { .. })),
+            "Folder creation should fail with an io error"
+        );
+    }
+
+    #[test]
+    fn can_generate_gradle_project() {
+        let top_folder = "generated-gradle";
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql()
+            .spring_boot_starter_test();
+        let example = sample_class(&pom_xml);
+        let mut gradle_code = GradleCodebase::new(pom_xml, top_folder).add_entity(example);
+        gradle_code
+            .generate_code()
+            .expect("Generated code has syntax errors");
+
+        let build = std::fs::read_to_string(Path::new(top_folder).join("build.gradle.kts"))
+            .expect("build.gradle.kts was not written");
+        assert!(build.contains(r#"id("org.springframework.boot") version "3.4.1""#));
+        assert!(
+            build.contains(r#"implementation("org.springframework.boot:spring-boot-starter-web")"#)
+        );
+        assert!(build.contains(r#"compileOnly("org.projectlombok:lombok")"#));
+        assert!(build.contains(r#"runtimeOnly("org.postgresql:postgresql")"#));
+        assert!(build.contains(
+            r#"testImplementation("org.springframework.boot:spring-boot-starter-test")"#
+        ));
+        assert!(build.contains("JavaLanguageVersion.of(17)"));
+        assert!(build.contains("tasks.named<Jar>(\"jar\") {\n    enabled = false\n}"));
+        let settings = std::fs::read_to_string(Path::new(top_folder).join("settings.gradle.kts"))
+            .expect("settings.gradle.kts was not written");
+        assert!(settings.contains(r#"rootProject.name = "rvtool""#));
+        assert!(Path::new(top_folder)
+            .join("gradle/wrapper/gradle-wrapper.properties")
+            .exists());
+        assert!(!Path::new(top_folder).join("pom.xml").exists());
+        assert_dir_exists(&(top_folder.to_owned() + "/src/main/java/org/javacodegen/rvtool"));
+        cleanup_folder(top_folder);
+    }
+
+    #[test]
+    fn can_render_groovy_build_script() {
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml.java_version("17".to_owned()).spring_boot();
+        let gradle = GradleBuild::new(GradleDsl::Groovy);
+        let build = gradle.build_script(&pom_xml);
+        assert!(build.contains("id 'org.springframework.boot' version '3.4.1'"));
+        assert!(build
+            .contains("implementation 'org.springdoc:springdoc-openapi-starter-webmvc-ui:2.7.0'"));
+        assert!(build.contains("annotationProcessor 'org.projectlombok:lombok'"));
+        assert!(build.contains("tasks.named('jar') {\n    enabled = false\n}"));
+        assert_eq!(gradle.build_file_name(), "build.gradle");
+        assert_eq!(
+            gradle.settings_script(&pom_xml),
+            "rootProject.name = 'rvtool'\n"
+        );
+    }
+
+    #[test]
+    fn can_generate_multi_module_project() {
+        let top_folder = "generated-modules";
+        let mut pom_xml = PomXml::new(sample_project_info());
+        pom_xml = pom_xml.java_version("17".to_owned()).spring_boot();
+        let example = sample_class(&pom_xml);
+        let invoice = JavaClass::new("Invoice".into(), "".into())
+            .public()
+            .field(Field::n("total".into(), TypeName::new("int".into())));
+        let mut mvn_code = MavenCodebase::new(pom_xml, top_folder)
+            .modules(ModuleLayout::default())
+            .add_entity(example)
+            .add_entity_with(
+                invoice,
+                CrudOptions::new().dto(DtoConfig::new().mapper(MapperStyle::MapStruct)),
+            );
+        mvn_code
+            .generate_code()
+            .expect("Generated code has syntax errors");
+
+        let root = Path::new(top_folder);
+        let aggregator = std::fs::read_to_string(root.join("pom.xml")).unwrap();
+        assert_xml_structure_with_xsd(&aggregator);
+        assert!(aggregator.contains("<packaging>pom</packaging>"));
+        for module in ["api", "domain", "persistence", "web"] {
+            assert!(aggregator.contains(&format!("<module>{}</module>", module)));
+            assert!(aggregator.contains(&format!("<artifactId>rvtool-{}</artifactId>", module)));
+        }
+        assert!(aggregator.contains("<dependencyManagement>"));
+        assert!(
+            !aggregator.contains("spring-boot-starter-web"),
+            "The aggregator should not declare dependencies of its own"
+        );
+
+        let web = std::fs::read_to_string(root.join("web").join("pom.xml")).unwrap();
+        assert_xml_structure_with_xsd(&web);
+        assert!(web.contains("<artifactId>rvtool-api</artifactId>"));
+        assert!(web.contains("<artifactId>rvtool-persistence</artifactId>"));
+        assert!(web.contains("spring-boot-starter-web"));
+        let domain = std::fs::read_to_string(root.join("domain").join("pom.xml")).unwrap();
+        assert!(!domain.contains("spring-boot-starter-web"));
+        assert!(!domain.contains("2.7.0"));
+        assert!(!domain.contains("mapstruct"));
+        //the mapper is compiled with the dtos
+        let api = std::fs::read_to_string(root.join("api").join("pom.xml")).unwrap();
+        assert_xml_structure_with_xsd(&api);
+        for library in [
+            "mapstruct",
+            "mapstruct-processor",
+            "lombok-mapstruct-binding",
+        ] {
+            assert!(
+                api.contains(&format!("<artifactId>{}</artifactId>", library)),
+                "{} is missing:\n{}",
+                library,
+                api
+            );
+        }
+        assert!(web.contains("spring-boot-maven-plugin"));
+        assert!(!aggregator.contains("spring-boot-maven-plugin"));
+        assert!(!domain.contains("spring-boot-maven-plugin"));
+
+        assert_a_class_file_exists_in_that(root.join("domain"), |content| {
+            content.contains("@Entity")
+        });
+        assert_a_class_file_exists_in_that(root.join("persistence"), |content| {
+            content.contains("extends JpaRepository")
+        });
+        assert_a_class_file_exists_in_that(root.join("web"), |content| {
+            content.contains("@RestController")
+        });
+        assert_a_class_file_exists_in_that(root.join("api"), |content| content.contains("@Mapper"));
+        assert_a_class_file_exists_in_that(root.join("web"), |content| {
+            content.contains("@SpringBootApplication")
+        });
+        assert!(root
+            .join("web/src/main/resources/application.properties")
+            .exists());
+        cleanup_folder(top_folder);
+    }
+
     use std::path::Path;
 
     use crate::common::{self, sample_class, sample_project_info};
+    use java_builder::{
+        catalog::{self, Catalog, VersionRange},
+        ci::{CiConfig, CiProvider},
+        classes::JavaClass,
+        config::{ConfigFormat, SpringProfile},
+        crud_builder::{CrudOptions, DtoConfig, FilterStrategy, MapperStyle, SearchConfig},
+        database::DatabaseKind,
+        diagnostics::Diagnostic,
+        docker::DockerConfig,
+        error::CodegenError,
+        exception_handling::ErrorHandling,
+        fields::Field,
+        gradle::{GradleBuild, GradleDsl},
+        gradle_builder::GradleCodebase,
+        ids::IdStrategy,
+        imports::Import,
+        local_repository::LocalRepository,
+        maven_builder::BuildSystem,
+        methods::Method,
+        migrations::{self, Column, MigrationTool, SchemaChange},
+        modules::ModuleLayout,
+        output::{Layer, OutputConfig, Packaging},
+        pom_xml::{Library, Plugin, PluginExecution, Profile, ProjectInfo, Repository, Scope},
+        queries::{QueryIntent, QueryResult},
+        relationships::{Cascade, Relationship},
+        security::{AuthMode, SecurityConfig},
+        types::TypeName,
+        validation::ValidationMode,
+    };
     #[test]
     fn can_create_maven_folders() {
         let top_folder = "generated2";
@@ -84,7 +339,9 @@ mod java_project_tests {
         pom_xml = pom_xml.java_version(java_version.clone());
         pom_xml = pom_xml.spring_boot();
         let mut mvn_codebase = MavenCodebase::new(pom_xml, &top_folder);
-        mvn_codebase.write_initial_files();
+        mvn_codebase
+            .write_initial_files()
+            .expect("Initial files could not be written");
         let code_folder = &(top_folder.to_owned() + &"/src/main/java/org/javacodegen/rvtool");
         assert_dir_exists(code_folder);
 
@@ -181,4 +438,2081 @@ mod java_project_tests {
         assert!(result.contains("org.projectlombok"));
         assert!(result.contains("spring-boot-devtools"));
     }
+
+    #[test]
+    fn can_render_complete_pom_model() {
+        let mut info = sample_project_info();
+        info.version = "1.2.0".to_owned();
+        info.description = "Tools & <stuff>".to_owned();
+        let pom_xml = PomXml::new(info)
+            .java_version("21".to_owned())
+            .spring_boot()
+            .spring_boot_starter_test()
+            .property("mapstruct.version".to_owned(), "1.6.3".to_owned())
+            .bom(
+                "org.springframework.cloud".to_owned(),
+                "spring-cloud-dependencies".to_owned(),
+                "2024.0.0".to_owned(),
+            )
+            .dependency(
+                Library::new_with_version(
+                    "org.mapstruct".to_owned(),
+                    "mapstruct".to_owned(),
+                    "${mapstruct.version}".to_owned(),
+                )
+                .classifier("sources".to_owned())
+                .exclusion("commons-logging".to_owned(), "commons-logging".to_owned()),
+            )
+            .repository(
+                Repository::new(
+                    "spring-milestones".to_owned(),
+                    "https://repo.spring.io/milestone".to_owned(),
+                )
+                .name("Spring Milestones".to_owned()),
+            )
+            .plugin_repository(Repository::new(
+                "spring-milestones".to_owned(),
+                "https://repo.spring.io/milestone".to_owned(),
+            ))
+            .profile(
+                Profile::new("native".to_owned())
+                    .property("skipTests".to_owned(), "true".to_owned())
+                    .plugin(
+                        Plugin::new(
+                            "org.graalvm.buildtools".to_owned(),
+                            "native-maven-plugin".to_owned(),
+                        )
+                        .execution(
+                            PluginExecution::new(vec!["compile-no-fork".to_owned()])
+                                .id("build-native".to_owned())
+                                .phase("package".to_owned()),
+                        ),
+                    ),
+            );
+
+        let result = pom_xml.generate();
+        assert_xml_structure_with_xsd(&result);
+        assert!(result.contains("<version>1.2.0</version>"));
+        assert!(result.contains("<description>Tools &amp; &lt;stuff&gt;</description>"));
+        assert!(result.contains("<relativePath/>"));
+        assert!(result.contains("        <mapstruct.version>1.6.3</mapstruct.version>"));
+        assert!(result.contains("<artifactId>spring-boot-maven-plugin</artifactId>"));
+        assert!(result.contains(
+            "<artifactId>spring-boot-starter-test</artifactId>\n            <scope>test</scope>"
+        ));
+        assert!(result.contains("<type>pom</type>\n                <scope>import</scope>"));
+        assert!(result.contains("<classifier>sources</classifier>"));
+        assert!(result.contains("<exclusion>"));
+        assert!(result.contains("<optional>true</optional>"));
+        assert!(result.contains("<pluginRepositories>"));
+        assert!(result.contains("<goal>compile-no-fork</goal>"));
+    }
+
+    const EXISTING_POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+         xsi:schemaLocation="http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd">
+    <modelVersion>4.0.0</modelVersion>
+    <parent>
+        <groupId>org.springframework.boot</groupId>
+        <artifactId>spring-boot-starter-parent</artifactId>
+        <version>3.4.1</version>
+        <relativePath/> <!-- lookup parent from repository -->
+    </parent>
+    <groupId>com.example</groupId>
+    <artifactId>demo</artifactId>
+    <name>demo</name>
+    <description>Demo project for Spring Boot</description>
+    <url>https://example.com</url>
+    <licenses>
+        <license><name>Apache-2.0</name></license>
+    </licenses>
+    <scm>
+        <url>https://github.com/example/demo</url>
+    </scm>
+    <!-- keep in sync with the platform bom -->
+    <properties>
+        <java.version>21</java.version>
+        <mapstruct.version>1.6.3</mapstruct.version>
+    </properties>
+    <dependencies>
+        <dependency>
+            <groupId>org.springframework.boot</groupId>
+            <artifactId>spring-boot-starter-web</artifactId>
+        </dependency>
+        <dependency>
+            <groupId>org.springframework.boot</groupId>
+            <artifactId>spring-boot-starter-test</artifactId>
+            <scope>test</scope>
+        </dependency>
+    </dependencies>
+    <build>
+        <finalName>app</finalName>
+        <plugins>
+            <plugin>
+                <groupId>org.springframework.boot</groupId>
+                <artifactId>spring-boot-maven-plugin</artifactId>
+                <configuration>
+                    <layers><enabled>true</enabled></layers>
+                </configuration>
+            </plugin>
+        </plugins>
+    </build>
+</project>
+"#;
+
+    const POM_WITH_PROFILES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+         xsi:schemaLocation="http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd">
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>com.example</groupId>
+    <artifactId>demo</artifactId>
+    <version>1.0.0</version>
+    <dependencies>
+        <!-- web -->
+        <dependency>
+            <groupId>org.springframework.boot</groupId>
+            <artifactId>spring-boot-starter-web</artifactId>
+            <!-- managed by the parent -->
+        </dependency>
+    </dependencies>
+    <repositories>
+        <repository>
+            <id>internal</id>
+            <url>https://repo.example.com</url>
+            <layout>default</layout>
+            <releases>
+                <enabled>true</enabled>
+                <updatePolicy>daily</updatePolicy>
+            </releases>
+        </repository>
+    </repositories>
+    <build>
+        <plugins>
+            <plugin>
+                <groupId>org.example</groupId>
+                <artifactId>packaging-plugin</artifactId>
+                <extensions>true</extensions>
+                <inherited>false</inherited>
+            </plugin>
+        </plugins>
+    </build>
+    <profiles>
+        <profile>
+            <id>legacy</id>
+            <activation>
+                <jdk>[17,21)</jdk>
+                <property><name>legacy</name></property>
+            </activation>
+            <modules>
+                <module>legacy-module</module>
+            </modules>
+            <dependencyManagement>
+                <dependencies>
+                    <dependency>
+                        <groupId>org.example</groupId>
+                        <artifactId>legacy-bom</artifactId>
+                        <version>1.0</version>
+                    </dependency>
+                </dependencies>
+            </dependencyManagement>
+            <build>
+                <finalName>legacy</finalName>
+                <!-- only for the old servers -->
+                <plugins>
+                    <plugin>
+                        <artifactId>maven-war-plugin</artifactId>
+                    </plugin>
+                </plugins>
+            </build>
+        </profile>
+    </profiles>
+</project>
+"#;
+
+    #[test]
+    fn can_parse_and_round_trip_pom_xml() {
+        let pom_xml = PomXml::parse(EXISTING_POM).expect("pom.xml could not be parsed");
+        assert_eq!(pom_xml.project_info.group_id, "com.example");
+        assert_eq!(pom_xml.project_info.version, "3.4.1");
+        assert_eq!(pom_xml.java, "21");
+        assert_eq!(pom_xml.parent_pom.version(), Some("3.4.1"));
+        assert!(pom_xml
+            .has_dependency_that(|d| d.artifact_id == "spring-boot-starter-test" && d.is_test()));
+        assert!(pom_xml.has_plugin("spring-boot-maven-plugin"));
+
+        let pom_xml = pom_xml.postgresql();
+        let result = pom_xml.generate();
+        assert_xml_structure_with_xsd(&result);
+        assert!(result.contains("<!-- keep in sync with the platform bom -->"));
+        assert!(result.contains("<url>https://example.com</url>"));
+        assert!(result.contains("<finalName>app</finalName>"));
+        assert!(result.contains("<enabled>true</enabled>"));
+        assert!(result.contains("<artifactId>postgresql</artifactId>"));
+        assert!(result.contains("<relativePath/> <!-- lookup parent from repository -->\n"));
+        let order: Vec<usize> = [
+            "<description>",
+            "<url>",
+            "<licenses>",
+            "<scm>",
+            "<!-- keep in sync with the platform bom -->",
+            "<properties>",
+            "<dependencies>",
+            "<build>",
+        ]
+        .iter()
+        .map(|e| result.find(e).unwrap_or_else(|| panic!("{} was lost", e)))
+        .collect();
+        assert!(
+            order.windows(2).all(|w| w[0] < w[1]),
+            "The elements were reordered:\n{}",
+            result
+        );
+
+        let reparsed = PomXml::parse(&result).expect("generated pom.xml could not be parsed");
+        assert_eq!(reparsed.generate(), result);
+
+        let result = PomXml::parse(POM_WITH_PROFILES)
+            .expect("pom.xml could not be parsed")
+            .generate();
+        assert_xml_structure_with_xsd(&result);
+        for kept in [
+            "<!-- web -->",
+            "<!-- managed by the parent -->",
+            "<layout>default</layout>",
+            "<updatePolicy>daily</updatePolicy>",
+            "<extensions>true</extensions>",
+            "<inherited>false</inherited>",
+            "<jdk>[17,21)</jdk>",
+            "<name>legacy</name>",
+            "<module>legacy-module</module>",
+            "<artifactId>legacy-bom</artifactId>",
+            "<finalName>legacy</finalName>",
+            "<!-- only for the old servers -->",
+        ] {
+            assert!(result.contains(kept), "{} was lost:\n{}", kept, result);
+        }
+        assert!(
+            result.find("<!-- web -->") < result.find("<artifactId>spring-boot-starter-web"),
+            "The comment moved away from its dependency"
+        );
+        let reparsed = PomXml::parse(&result).expect("generated pom.xml could not be parsed");
+        assert_eq!(reparsed.generate(), result);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_poms() {
+        assert!(matches!(
+            PomXml::parse("<settings></settings>"),
+            Err(CodegenError::Parse(_))
+        ));
+        assert!(matches!(
+            PomXml::parse("<project>"),
+            Err(CodegenError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn can_add_entities_to_an_existing_project() {
+        let top_folder = "generated-existing";
+        let main_class =
+            Path::new(top_folder).join("src/main/java/com/example/demo/DemoApplication.java");
+        std::fs::create_dir_all(main_class.parent().unwrap()).unwrap();
+        std::fs::write(Path::new(top_folder).join("pom.xml"), EXISTING_POM).unwrap();
+        std::fs::write(&main_class, "package com.example.demo;\n").unwrap();
+
+        let codebase =
+            MavenCodebase::open(top_folder).expect("Existing project could not be opened");
+        let example = sample_class(codebase.get_pom_xml());
+        let mut codebase = codebase
+            .configure_pom(|pom| pom.spring_boot_starter_data_jpa())
+            .add_entity(example);
+        codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        assert!(pom.contains("spring-boot-starter-data-jpa"));
+        assert!(pom.contains("<finalName>app</finalName>"));
+        assert_eq!(
+            std::fs::read_to_string(&main_class).unwrap(),
+            "package com.example.demo;\n",
+            "The main class of the existing project was overwritten"
+        );
+        assert_a_class_file_exists_in_that(
+            Path::new(top_folder).join("src/main/java/com/example/demo"),
+            |content| content.contains("@Entity"),
+        );
+        cleanup_folder(top_folder);
+    }
+
+    fn has_rule(diagnostics: &[Diagnostic], rule: &str, element: &str) -> bool {
+        diagnostics
+            .iter()
+            .any(|d| d.rule == rule && d.element.contains(element))
+    }
+
+    #[test]
+    fn spring_boot_project_passes_the_dependency_catalog() {
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql()
+            .spring_boot_starter_test();
+        let diagnostics = pom_xml.check_dependencies(catalog::bundled());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(
+            pom_xml.spring_boot_version(),
+            Some(catalog::bundled().latest_spring_boot())
+        );
+    }
+
+    #[test]
+    fn catalog_resolves_versions_for_the_spring_boot_version() {
+        let catalog = catalog::bundled();
+        let pom_xml = PomXml::new(sample_project_info())
+            .parent(Library::new_with_version(
+                "org.springframework.boot".to_owned(),
+                "spring-boot-starter-parent".to_owned(),
+                "3.3.7".to_owned(),
+            ))
+            .add_library(
+                "org.springdoc".to_owned(),
+                "springdoc-openapi-starter-webmvc-ui".to_owned(),
+            )
+            .lombok();
+        let diagnostics = pom_xml.check_dependencies(catalog);
+        assert!(has_rule(
+            &diagnostics,
+            "unmanaged-version",
+            "springdoc-openapi-starter-webmvc-ui"
+        ));
+        assert!(
+            diagnostics.iter().all(|d| !d.is_error()),
+            "{:?}",
+            diagnostics
+        );
+
+        let resolved = catalog.resolve_missing_versions(pom_xml);
+        let springdoc = resolved
+            .dependencies
+            .iter()
+            .find(|d| d.artifact_id == "springdoc-openapi-starter-webmvc-ui")
+            .unwrap();
+        assert_eq!(springdoc.version(), Some("2.6.0"));
+        let lombok = resolved
+            .dependencies
+            .iter()
+            .find(|d| d.artifact_id == "lombok")
+            .unwrap();
+        assert_eq!(
+            lombok.version(),
+            None,
+            "lombok is managed by the spring boot bom"
+        );
+    }
+
+    #[test]
+    fn catalog_reports_duplicates_conflicts_and_unknown_versions() {
+        let pom_xml = PomXml::new(sample_project_info())
+            .parent(Library::new_with_version(
+                "org.springframework.boot".to_owned(),
+                "spring-boot-starter-parent".to_owned(),
+                "3.0.13".to_owned(),
+            ))
+            .spring_boot_starter_web()
+            .spring_boot_starter_web()
+            .add_library(
+                "org.springframework.boot".to_owned(),
+                "spring-boot-starter-webflux".to_owned(),
+            )
+            .postgresql()
+            .dependency(
+                Library::new("org.postgresql".to_owned(), "postgresql".to_owned())
+                    .scope(Scope::Test),
+            )
+            .spring_boot_docker_compose()
+            .add_library("com.example".to_owned(), "internal-sdk".to_owned());
+        let diagnostics = pom_xml.check_dependencies(catalog::bundled());
+        assert!(has_rule(
+            &diagnostics,
+            "unknown-spring-boot-version",
+            "spring-boot-starter-parent"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "duplicate-dependency",
+            "spring-boot-starter-web"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "conflicting-dependency",
+            "org.postgresql:postgresql"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "conflicting-libraries",
+            "spring-boot-starter-webflux"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "incompatible-version",
+            "spring-boot-docker-compose"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "unknown-artifact",
+            "com.example:internal-sdk"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "missing-version",
+            "com.example:internal-sdk"
+        ));
+    }
+
+    #[test]
+    fn can_load_a_custom_catalog() {
+        let catalog = Catalog::from_json(
+            r#"{
+                "spring_boot": { "latest": "3.5.0", "versions": ["3.5.0"], "managed_groups": [] },
+                "artifacts": [{
+                    "group_id": "org.springdoc",
+                    "artifact_id": "springdoc-openapi-starter-webmvc-ui",
+                    "versions": [{ "boot": "[3.5,)", "version": "2.8.9" }]
+                }]
+            }"#,
+        )
+        .expect("catalog could not be parsed");
+        assert_eq!(
+            catalog.version_for(
+                "org.springdoc",
+                "springdoc-openapi-starter-webmvc-ui",
+                "3.5.0"
+            ),
+            Some("2.8.9")
+        );
+        assert_eq!(
+            catalog.version_for(
+                "org.springdoc",
+                "springdoc-openapi-starter-webmvc-ui",
+                "3.4.1"
+            ),
+            None
+        );
+        assert!(Catalog::from_json("{}").is_err());
+        assert!(VersionRange::parse("[3.4,3.5)").contains("3.4.1"));
+        assert!(!VersionRange::parse("[3.4,3.5)").contains("3.5.0"));
+        assert!(!VersionRange::parse("[3.1,)").contains("3.1.0-M1"));
+        assert!(VersionRange::parse("[").contains("3.4.1"));
+        assert!(!VersionRange::parse("[3.4,é").contains("3.3.0"));
+    }
+
+    //writes groupId:artifactId:version into a fake local repository
+    fn install(repository: &LocalRepository, pom_xml: PomXml) {
+        let info = &pom_xml.project_info;
+        let path = repository.pom_path(&info.group_id, &info.artifact_id, &info.version);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, pom_xml.generate()).unwrap();
+    }
+
+    fn artifact(group_id: &str, artifact_id: &str, version: &str) -> PomXml {
+        PomXml::new(ProjectInfo {
+            group_id: group_id.to_owned(),
+            artifact_id: artifact_id.to_owned(),
+            version: version.to_owned(),
+            name: "".to_owned(),
+            description: "".to_owned(),
+        })
+    }
+
+    #[test]
+    fn can_check_dependencies_against_a_local_repository() {
+        let top_folder = "generated-m2";
+        let repository = LocalRepository::new(top_folder);
+        let boot = "org.springframework.boot";
+        install(
+            &repository,
+            artifact(boot, "spring-boot-dependencies", "3.4.1")
+                .packaging("pom".to_owned())
+                .property("lombok.version".to_owned(), "1.18.36".to_owned())
+                .managed_dependency(Library::new_with_version(
+                    "org.projectlombok".to_owned(),
+                    "lombok".to_owned(),
+                    "${lombok.version}".to_owned(),
+                ))
+                .managed_dependency(Library::new_with_version(
+                    boot.to_owned(),
+                    "spring-boot-starter-web".to_owned(),
+                    "3.4.1".to_owned(),
+                )),
+        );
+        install(
+            &repository,
+            artifact(boot, "spring-boot-starter-parent", "3.4.1")
+                .packaging("pom".to_owned())
+                .parent(Library::new_with_version(
+                    boot.to_owned(),
+                    "spring-boot-dependencies".to_owned(),
+                    "3.4.1".to_owned(),
+                )),
+        );
+        install(
+            &repository,
+            artifact(boot, "spring-boot-starter-web", "3.4.1"),
+        );
+        install(
+            &repository,
+            artifact("org.projectlombok", "lombok", "1.18.36"),
+        );
+        for version in ["1.0", "2.0"] {
+            install(&repository, artifact("com.example", "shared", version));
+        }
+        for (name, shared_version) in [("a", "1.0"), ("b", "2.0")] {
+            install(
+                &repository,
+                artifact("com.example", name, "1.0").dependency(Library::new_with_version(
+                    "com.example".to_owned(),
+                    "shared".to_owned(),
+                    shared_version.to_owned(),
+                )),
+            );
+        }
+
+        let pom_xml = PomXml::new(sample_project_info())
+            .parent(Library::new_with_version(
+                boot.to_owned(),
+                "spring-boot-starter-parent".to_owned(),
+                "3.4.1".to_owned(),
+            ))
+            .spring_boot_starter_web()
+            .lombok()
+            .add_library_with_version("a".to_owned(), "com.example".to_owned(), "1.0".to_owned())
+            .add_library_with_version("b".to_owned(), "com.example".to_owned(), "1.0".to_owned())
+            .add_library_with_version(
+                "ghost".to_owned(),
+                "com.example".to_owned(),
+                "1.0".to_owned(),
+            )
+            .add_library("com.example".to_owned(), "unversioned".to_owned());
+        let diagnostics = repository.check(&pom_xml);
+        cleanup_folder(top_folder);
+
+        assert!(has_rule(
+            &diagnostics,
+            "missing-artifact",
+            "com.example:ghost:1.0"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "unresolved-version",
+            "com.example:unversioned"
+        ));
+        assert!(has_rule(
+            &diagnostics,
+            "transitive-conflict",
+            "com.example:shared"
+        ));
+        assert!(
+            !has_rule(&diagnostics, "missing-artifact", "lombok")
+                && !has_rule(&diagnostics, "missing-artifact", "spring-boot-starter-web"),
+            "versions managed by the parent bom were not resolved: {:?}",
+            diagnostics
+        );
+        assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+    }
+
+    fn read_generated(top_folder: &str, file: &str) -> String {
+        let path = Path::new(top_folder)
+            .join("src/main/java/org/javacodegen/rvtool")
+            .join(file);
+        std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("{} was not generated", file))
+    }
+
+    #[test]
+    fn can_generate_session_security() {
+        let top_folder = "generated-security";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let example = sample_class(&pom_xml);
+        let mut codebase = MavenCodebase::new(pom_xml, top_folder)
+            .add_entity(example)
+            .security(SecurityConfig::new(AuthMode::Session));
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        assert!(pom.contains("spring-boot-starter-security"));
+        assert!(pom.contains("spring-security-test"));
+        assert!(!pom.contains("oauth2-resource-server"));
+
+        let configuration = read_generated(top_folder, "security/SecurityConfiguration.java");
+        assert!(configuration.contains(
+            "SecurityFilterChain securityFilterChain(HttpSecurity http) throws Exception"
+        ));
+        assert!(configuration.contains(".requestMatchers(\"/login\", \"/error\", \"/api-docs/**\""));
+        assert!(configuration.contains("@EnableMethodSecurity"));
+        assert!(configuration.contains("HttpSessionSecurityContextRepository"));
+        let controller = read_generated(top_folder, "controllers/CustomerController.java");
+        assert!(controller.contains("@PreAuthorize (value = \"hasRole('ADMIN')\")"));
+        assert!(controller.contains("@PreAuthorize (value = \"hasAnyRole('USER', 'ADMIN')\")"));
+        let login = read_generated(top_folder, "security/AuthController.java");
+        assert!(login.contains("securityContextRepository.saveContext"));
+        assert_a_class_file_exists_in_that(top_folder, |content| {
+            content.contains("class User") && content.contains("@Table (name = \"users\")")
+        });
+        assert_a_class_file_exists_in_that(top_folder, |content| {
+            content.contains("findByUsername(String username)")
+        });
+        let properties = std::fs::read_to_string(
+            Path::new(top_folder).join("src/main/resources/application.properties"),
+        )
+        .unwrap();
+        assert!(properties.contains("security.admin.password=${ADMIN_PASSWORD:}"));
+        assert!(!properties.contains("security.jwt"));
+        cleanup_folder(top_folder);
+    }
+
+    #[test]
+    fn security_mode_follows_openapi_security_schemes() {
+        let bearer = r#"{"components": {"securitySchemes": {"token": {"type": "http", "scheme": "bearer", "bearerFormat": "JWT"}}}}"#;
+        let basic = r#"{"components": {"securitySchemes": {"basic": {"type": "http", "scheme": "basic"}}}}"#;
+        let oidc = r#"{"components": {"securitySchemes": {"oidc": {"type": "openIdConnect", "openIdConnectUrl": "https://example.com"}}}}"#;
+        let mode = |spec: &str| SecurityConfig::from_openapi(spec).unwrap().map(|s| s.mode);
+        assert_eq!(mode(bearer), Some(AuthMode::Jwt));
+        assert_eq!(mode(oidc), Some(AuthMode::Jwt));
+        assert_eq!(mode(basic), Some(AuthMode::Session));
+        assert_eq!(mode(r#"{"openapi": "3.0.1", "paths": {}}"#), None);
+        assert!(matches!(
+            SecurityConfig::from_openapi("not json"),
+            Err(CodegenError::Parse(_))
+        ));
+
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let example = sample_class(&pom_xml);
+        let security = SecurityConfig::from_openapi(bearer).unwrap().unwrap();
+        let mut codebase = MavenCodebase::new(pom_xml, "generated-jwt")
+            .security(security.write_roles(vec!["EDITOR".to_owned()]))
+            .add_entity(example);
+        assert!(
+            codebase.get_pom_xml().has_dependency_that(
+                |d| d.artifact_id == "spring-boot-starter-oauth2-resource-server"
+            )
+        );
+        let properties = codebase.create_application_properties();
+        assert!(properties.contains("security.jwt.secret=${JWT_SECRET}\n"));
+        let dev_config = codebase.profile_config(SpringProfile::Dev);
+        assert!(dev_config
+            .get("security.jwt.secret")
+            .is_some_and(|s| s.starts_with("${JWT_SECRET:")));
+        assert!(codebase
+            .profile_config(SpringProfile::Prod)
+            .get("security.jwt.secret")
+            .is_none());
+        let report = codebase.validate();
+        assert!(report.is_ok(), "{}", report);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn can_generate_docker_files() {
+        let top_folder = "generated-docker";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("21".to_owned())
+            .spring_boot()
+            .postgresql()
+            .spring_boot_docker_compose();
+        let mut codebase = MavenCodebase::new(pom_xml, top_folder).docker(DockerConfig::default());
+        let db = codebase.get_db_info();
+        codebase
+            .write_initial_files()
+            .expect("Initial files could not be written");
+        let read = |file: &str| std::fs::read_to_string(Path::new(top_folder).join(file)).unwrap();
+
+        let dockerfile = read("Dockerfile");
+        assert!(dockerfile.contains("FROM maven:3.9-eclipse-temurin-21 AS build"));
+        assert!(dockerfile.contains("FROM eclipse-temurin:21-jre"));
+        assert!(dockerfile.contains("COPY --from=build /workspace/target/*.jar app.jar"));
+        assert!(dockerfile.contains("EXPOSE 8082"));
+        assert!(read(".dockerignore").contains("target/"));
+
+        let compose = read("compose.yaml");
+        assert!(compose.contains("image: rvtool:latest"));
+        assert!(compose.contains(&format!("POSTGRES_DB: {}", db.db)));
+        assert!(compose.contains(&format!("POSTGRES_USER: {}", db.username)));
+        assert!(compose.contains(&format!("- \"{}:5432\"", db.db_port)));
+        assert!(compose.contains(&format!(
+            "SPRING_DATASOURCE_URL: jdbc:postgresql://db:5432/{}",
+            db.db
+        )));
+        assert!(compose.contains("condition: service_healthy"));
+        //the database is started by spring boot, the app only with --profile app
+        assert!(compose.contains("profiles: [\"app\"]"));
+        assert!(read("src/main/resources/application.properties")
+            .contains("spring.docker.compose.file=compose.yaml"));
+        cleanup_folder(top_folder);
+
+        let mut gradle = MavenCodebase::new(
+            PomXml::new(sample_project_info())
+                .java_version("17".to_owned())
+                .spring_boot(),
+            "generated-docker-gradle",
+        )
+        .build_system(BuildSystem::Gradle(GradleBuild::new(GradleDsl::Kotlin)))
+        .docker(DockerConfig::default());
+        gradle
+            .write_initial_files()
+            .expect("Initial files could not be written");
+        let dockerfile =
+            std::fs::read_to_string(Path::new("generated-docker-gradle").join("Dockerfile"))
+                .unwrap();
+        let compose =
+            std::fs::read_to_string(Path::new("generated-docker-gradle").join("compose.yaml"))
+                .unwrap();
+        cleanup_folder("generated-docker-gradle");
+        assert!(dockerfile.contains("FROM gradle:8.12-jdk17 AS build"));
+        assert!(dockerfile.contains("/workspace/build/libs/*.jar"));
+        assert!(!compose.contains("db:"), "{}", compose);
+    }
+
+    #[test]
+    fn can_generate_ci_pipelines() {
+        let top_folder = "generated-ci";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml, top_folder)
+            .docker(DockerConfig::default())
+            .ci(CiConfig::new(vec![
+                CiProvider::GitHubActions,
+                CiProvider::GitLab,
+            ]));
+        let db = codebase.get_db_info();
+        codebase
+            .write_initial_files()
+            .expect("Initial files could not be written");
+        let read = |file: &str| std::fs::read_to_string(Path::new(top_folder).join(file)).unwrap();
+        let github = read(".github/workflows/build.yml");
+        let gitlab = read(".gitlab-ci.yml");
+        cleanup_folder(top_folder);
+
+        assert!(github.contains("name: TempContRvTool"));
+        assert!(github.contains("java-version: '17'"));
+        assert!(github.contains("run: mvn -B verify"));
+        //without testcontainers the tests need a database next to them
+        assert!(github.contains(&format!("POSTGRES_DB: {}", db.db)));
+        assert!(github.contains("uses: docker/build-push-action@v6"));
+        assert!(github.contains("ghcr.io/${{ github.repository_owner }}/rvtool:latest"));
+        serde_yaml::from_str::<serde_yaml::Value>(&github).expect("the workflow is not valid yaml");
+        assert!(gitlab.contains("image: maven:3.9-eclipse-temurin-17"));
+        assert!(gitlab.contains("SPRING_DATASOURCE_URL: jdbc:postgresql://db:5432/"));
+        assert!(gitlab.contains("docker push --all-tags \"$CI_REGISTRY_IMAGE\""));
+        let pipeline: serde_yaml::Value =
+            serde_yaml::from_str(&gitlab).expect(".gitlab-ci.yml is not valid yaml");
+        //image is a global keyword, a job with that name is read as the default image
+        assert!(pipeline.get("image").is_none(), "{}", gitlab);
+        assert_eq!(pipeline["test"]["stage"].as_str(), Some("test"));
+        assert_eq!(pipeline["package-image"]["stage"].as_str(), Some("package"));
+        assert_eq!(
+            pipeline["package-image"]["image"].as_str(),
+            Some("docker:27")
+        );
+
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("21".to_owned())
+            .spring_boot()
+            .postgresql()
+            .testcontainers_postgresql();
+        let codebase = MavenCodebase::new(pom_xml, "generated-ci-gradle")
+            .build_system(BuildSystem::Gradle(GradleBuild::new(GradleDsl::Kotlin)));
+        let ci = CiConfig::new(vec![CiProvider::GitLab, CiProvider::GitHubActions])
+            .registry("registry.example.com".to_owned());
+        let files = ci.files(&codebase.container_target(), None);
+        assert_eq!(files[0].0, ".gitlab-ci.yml");
+        let (gitlab, github) = (&files[0].1, &files[1].1);
+        assert!(
+            gitlab.contains("docker:27-dind") && gitlab.contains("TESTCONTAINERS_HOST_OVERRIDE")
+        );
+        assert!(gitlab.contains("gradle build --no-daemon"));
+        assert!(
+            !gitlab.contains("stage: package"),
+            "no Dockerfile, no image"
+        );
+        let pipeline: serde_yaml::Value =
+            serde_yaml::from_str(gitlab).expect(".gitlab-ci.yml is not valid yaml");
+        assert_eq!(pipeline["test"]["stage"].as_str(), Some("test"));
+        assert!(pipeline.get("package-image").is_none());
+        assert!(github.contains("gradle-version: '8.12'"));
+        assert!(
+            !github.contains("POSTGRES_DB"),
+            "testcontainers starts the database"
+        );
+        assert!(!github.contains("build-push-action"));
+    }
+
+    #[test]
+    fn database_kind_configures_driver_properties_and_services() {
+        let pom_xml = PomXml::new(sample_project_info())
+            .spring_boot()
+            .postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml, "generated-database")
+            .database(DatabaseKind::MySql)
+            .docker(DockerConfig::default());
+        let pom = codebase.get_pom_xml();
+        assert!(pom.has_dependency_that(|d| d.artifact_id == "mysql-connector-j"));
+        assert!(
+            !pom.has_dependency_that(|d| d.group_id() == "org.postgresql"),
+            "the previous driver is replaced"
+        );
+        let db = codebase.get_db_info();
+        assert_eq!(db.kind, DatabaseKind::MySql);
+        assert_eq!(db.db_port, 3306);
+
+        let properties = codebase.create_application_properties();
+        assert!(properties.contains(&format!(
+            "spring.datasource.url=jdbc:mysql://localhost:3306/{}",
+            db.db
+        )));
+        assert!(properties.contains("spring.datasource.driver-class-name=com.mysql.cj.jdbc.Driver"));
+        assert!(
+            properties.contains("spring.jpa.database-platform=org.hibernate.dialect.MySQLDialect")
+        );
+
+        let target = codebase.container_target();
+        let compose = DockerConfig::default().compose(&target);
+        assert!(compose.contains("image: mysql:8.4"));
+        assert!(compose.contains(&format!("MYSQL_DATABASE: {}", db.db)));
+        assert!(compose.contains("- \"3306:3306\""));
+        assert!(compose.contains(&format!(
+            "SPRING_DATASOURCE_URL: jdbc:mysql://db:3306/{}",
+            db.db
+        )));
+        let github = CiConfig::default().github_workflow(&target, None);
+        assert!(github.contains("image: mysql:8.4"));
+
+        //an in memory database needs no container
+        let mut h2 = MavenCodebase::new(
+            PomXml::new(sample_project_info()).spring_boot(),
+            "generated-database-h2",
+        )
+        .database(DatabaseKind::H2);
+        let properties = h2.create_application_properties();
+        assert!(properties.contains("spring.datasource.url=jdbc:h2:mem:"));
+        assert!(properties.contains("spring.jpa.database-platform=org.hibernate.dialect.H2Dialect"));
+        let compose = DockerConfig::default().compose(&h2.container_target());
+        assert!(!compose.contains("db:"), "{}", compose);
+        assert!(!compose.contains("SPRING_DATASOURCE_URL"));
+
+        //the kind is detected from the driver an existing pom declares
+        let sql_server = MavenCodebase::new(
+            PomXml::new(sample_project_info()).dependency(DatabaseKind::SqlServer.driver()),
+            "generated-database-mssql",
+        );
+        let db = sql_server.get_db_info();
+        assert_eq!(db.kind, DatabaseKind::SqlServer);
+        assert_eq!(db.db_port, 1433);
+        assert!(DockerConfig::default()
+            .compose(&sql_server.container_target())
+            .contains("ACCEPT_EULA: Y"));
+    }
+
+    fn order_class() -> JavaClass {
+        JavaClass::new("PurchaseOrder".into(), "".into())
+            .public()
+            .field(Field::n(
+                "customer".into(),
+                TypeName::new("Customer".into()),
+            ))
+            .field(Field::n("paid".into(), TypeName::new("boolean".into())))
+    }
+
+    #[test]
+    fn can_generate_incremental_flyway_migrations() {
+        let top_folder = "generated-flyway";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .add_entity(sample_class(&pom_xml))
+            .add_entity(order_class());
+        codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let migrations = Path::new(top_folder).join("src/main/resources/db/migration");
+        let initial = std::fs::read_to_string(migrations.join("V1__create_schema.sql")).unwrap();
+        let properties = std::fs::read_to_string(
+            Path::new(top_folder).join("src/main/resources/application.properties"),
+        )
+        .unwrap();
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+
+        let customer =
+            sample_class(&pom_xml).field(Field::n("phone".into(), TypeName::new("String".into())));
+        MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .add_entity(customer)
+            .add_entity(order_class())
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let update = std::fs::read_to_string(migrations.join("V2__update_schema.sql")).unwrap();
+        //nothing changed, nothing to migrate
+        MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .generate_code()
+            .unwrap();
+        let dropped = std::fs::read_to_string(migrations.join("V3__update_schema.sql")).unwrap();
+        cleanup_folder(top_folder);
+
+        assert!(pom.contains("<artifactId>flyway-core</artifactId>"));
+        assert!(pom.contains("<artifactId>flyway-database-postgresql</artifactId>"));
+        assert!(properties.contains("spring.jpa.hibernate.ddl-auto=validate"));
+        assert!(initial.contains(
+            "CREATE TABLE customer (\n    id BIGINT GENERATED BY DEFAULT AS IDENTITY NOT NULL,\n    age INTEGER NOT NULL,\n    email VARCHAR(255),\n    first_name VARCHAR(255),\n    last_name VARCHAR(255),\n    PRIMARY KEY (id)\n);"
+        ), "{}", initial);
+        assert!(initial.contains("customer_id BIGINT,"));
+        assert!(initial.contains(
+            "ALTER TABLE purchase_order ADD CONSTRAINT fk_purchase_order_customer_id FOREIGN KEY (customer_id) REFERENCES customer (id);"
+        ));
+        assert_eq!(
+            update.trim(),
+            "ALTER TABLE customer ADD phone VARCHAR(255);"
+        );
+        //the foreign key goes before the tables
+        assert!(
+            dropped.find("DROP CONSTRAINT fk_purchase_order_customer_id")
+                < dropped.find("DROP TABLE customer;"),
+            "{}",
+            dropped
+        );
+    }
+
+    #[test]
+    fn migrations_wait_for_a_database() {
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let codebase =
+            MavenCodebase::new(pom_xml, "generated-no-database").migrations(MigrationTool::Flyway);
+        assert!(!codebase
+            .get_pom_xml()
+            .has_dependency_that(|d| d.artifact_id.starts_with("flyway")));
+
+        let codebase = codebase.configure_pom(|p| p.postgresql());
+        assert!(codebase
+            .get_pom_xml()
+            .has_dependency_that(|d| d.artifact_id == "flyway-core"));
+        assert!(codebase
+            .get_pom_xml()
+            .has_dependency_that(|d| d.artifact_id == "flyway-database-postgresql"));
+    }
+
+    #[test]
+    fn can_generate_liquibase_changelogs() {
+        let top_folder = "generated-liquibase";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Liquibase)
+            .database(DatabaseKind::MySql)
+            .add_entity(sample_class(&pom_xml))
+            .security(SecurityConfig::new(AuthMode::Session));
+        codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let changelog = Path::new(top_folder).join("src/main/resources/db/changelog");
+        let master = std::fs::read_to_string(changelog.join("db.changelog-master.xml")).unwrap();
+        let changes =
+            std::fs::read_to_string(changelog.join("changes/001-create-schema.xml")).unwrap();
+        let properties = codebase.create_application_properties();
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        cleanup_folder(top_folder);
+
+        assert!(pom.contains("<artifactId>liquibase-core</artifactId>"));
+        assert!(!pom.contains("flyway"));
+        assert!(properties.contains(
+            "spring.liquibase.change-log=classpath:db/changelog/db.changelog-master.xml"
+        ));
+        assert!(master.contains("<includeAll path=\"changes/\" relativeToChangelogFile=\"true\"/>"));
+        assert!(changes.contains("<createTable tableName=\"customer\">"));
+        assert!(changes.contains("<column name=\"id\" type=\"BIGINT\" autoIncrement=\"true\">"));
+        assert!(changes.contains("<createTable tableName=\"users\">"));
+        assert!(changes.contains(
+            "<constraints nullable=\"false\" unique=\"true\" uniqueConstraintName=\"uk_users_username\"/>"
+        ));
+        //the roles of a user are an element collection
+        assert!(changes.contains("<createTable tableName=\"user_roles\">"));
+        assert!(changes.contains("referencedTableName=\"users\""));
+    }
+
+    #[test]
+    fn can_write_yaml_config_with_profiles() {
+        let top_folder = "generated-profiles";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml, top_folder)
+            .config_format(ConfigFormat::Yaml)
+            .profiles(SpringProfile::ALL.to_vec())
+            .property("server.port".to_owned(), "9090".to_owned())
+            .property("app.greeting".to_owned(), "hello: world".to_owned())
+            .property("app.api-version".to_owned(), "1.10".to_owned())
+            .property("app.answer".to_owned(), "yes".to_owned())
+            .property("app.missing".to_owned(), "null".to_owned())
+            .property("app.mode".to_owned(), "Off".to_owned())
+            .property("app.retries".to_owned(), "3".to_owned())
+            .property("app.timeout".to_owned(), "10s".to_owned())
+            .profile_property(
+                SpringProfile::Prod,
+                "logging.level.root".to_owned(),
+                "warn".to_owned(),
+            );
+        let db = codebase.get_db_info();
+        assert_eq!(
+            codebase.create_application_properties(),
+            codebase.create_application_properties(),
+            "the keys come out in the same order every time"
+        );
+        codebase
+            .write_initial_files()
+            .expect("Initial files could not be written");
+        let read = |file: &str| {
+            std::fs::read_to_string(Path::new(top_folder).join("src/main/resources").join(file))
+                .unwrap_or_else(|_| panic!("{} was not written", file))
+        };
+        let (shared, dev, test, prod) = (
+            read("application.yml"),
+            read("application-dev.yml"),
+            read("application-test.yml"),
+            read("application-prod.yml"),
+        );
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        let properties = Path::new(top_folder).join("src/main/resources/application.properties");
+        assert!(!properties.exists());
+        cleanup_folder(top_folder);
+
+        assert!(
+            shared.starts_with(
+                "app:\n  answer: \"yes\"\n  api-version: \"1.10\"\n  greeting: \"hello: world\"\n  missing: \"null\"\n  mode: \"Off\"\n  retries: 3\n  timeout: 10s\nserver:\n  address: localhost\n  port: 9090\n"
+            ),
+            "{}",
+            shared
+        );
+        assert!(shared.contains("  profiles:\n    default: dev\n"));
+        assert!(
+            !shared.contains("datasource"),
+            "the profiles bring the database"
+        );
+        assert!(dev.contains(&format!(
+            "    url: jdbc:postgresql://localhost:5432/{}",
+            db.db
+        )));
+        assert!(test.contains("url: jdbc:h2:mem:testdb;MODE=PostgreSQL;DB_CLOSE_DELAY=-1"));
+        assert!(test.contains("ddl-auto: create-drop"));
+        assert!(prod.contains("password: ${DATABASE_PASSWORD}"));
+        assert!(prod.contains("ddl-auto: validate"));
+        assert!(prod.contains("logging:\n  level:\n    root: warn\n"));
+        assert!(
+            pom.contains("<artifactId>h2</artifactId>\n            <scope>test</scope>"),
+            "{}",
+            pom
+        );
+        assert!(pom.contains("<artifactId>postgresql</artifactId>"));
+    }
+
+    #[test]
+    fn crud_endpoints_are_implemented() {
+        let top_folder = "generated-crud-endpoints";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity(sample_class(&pom_xml))
+            .add_entity(order_class());
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let service = read_generated(top_folder, "services/CustomerService.java");
+        let controller = read_generated(top_folder, "controllers/CustomerController.java");
+        let mapper = read_generated(top_folder, "dto/PurchaseOrderMapper.java");
+        let entity = read_generated(top_folder, "models/Customer.java");
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(service.contains("return repository.save(entity);"));
+        assert!(service.contains("return repository.findAll(pageable);"));
+        assert!(entity.starts_with("package org.javacodegen.rvtool.models;"));
+        assert!(controller.starts_with("package org.javacodegen.rvtool.controllers;"));
+        assert!(service.contains("import org.javacodegen.rvtool.models.Customer;"));
+        assert!(service.contains("changes.accept(entity);"));
+        assert!(service.contains("repository.deleteById(id);"));
+        //the service does not copy the fields of the entity
+        assert!(!service.contains("firstName"), "{}", service);
+
+        assert!(controller.contains("@RequestBody CustomerRequest request"));
+        //the constraints of the request are checked without an exception handler too
+        assert!(controller.contains("@Valid"), "{}", controller);
+        assert!(controller.contains("import jakarta.validation.Valid;"));
+        assert!(codebase
+            .get_pom_xml()
+            .has_dependency_that(|d| d.artifact_id == "spring-boot-starter-validation"));
+        assert!(controller.contains("ResponseEntity.status(HttpStatus.CREATED)"));
+        assert!(controller.contains("ResponseEntity.notFound().build()"));
+        assert!(controller.contains("ResponseEntity.noContent().build()"));
+        assert!(controller.contains("service.findAll(pageable).map(mapper::toResponse)"));
+        assert!(controller.contains("this.service = service;"));
+
+        assert!(
+            mapper.contains("entity.setPaid(request.isPaid());"),
+            "{}",
+            mapper
+        );
+        assert!(mapper.contains("entity.setCustomer(request.getCustomer());"));
+        assert!(!mapper.contains("entity.setId"));
+    }
+
+    #[test]
+    fn list_endpoints_page_sort_and_filter() {
+        let top_folder = "generated-search";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let search = SearchConfig::new()
+            .searchable(vec!["lastName".into(), "age".into()])
+            .sortable(vec!["id".into(), "lastName".into()]);
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity_with(
+                sample_class(&pom_xml),
+                CrudOptions::new().search(search.clone()),
+            )
+            .add_entity_with(
+                order_class(),
+                CrudOptions::new().search(
+                    SearchConfig::new()
+                        .searchable(vec!["paid".into()])
+                        .filter(FilterStrategy::DerivedQueries),
+                ),
+            );
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let controller = read_generated(top_folder, "controllers/CustomerController.java");
+        let service = read_generated(top_folder, "services/CustomerService.java");
+        let repository = read_generated(top_folder, "repositories/CustomerRepository.java");
+        let order_service = read_generated(top_folder, "services/PurchaseOrderService.java");
+        let order_repository =
+            read_generated(top_folder, "repositories/PurchaseOrderRepository.java");
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(controller.contains("ResponseEntity<Page<CustomerResponse>>"));
+        assert!(controller.contains(
+            "@RequestParam (required = false)String lastName,\n@RequestParam (required = false)Integer age,Pageable pageable"
+        ), "{}", controller);
+        assert!(
+            controller.contains("service.findAll(lastName, age, pageable).map(mapper::toResponse)")
+        );
+        assert!(
+            repository.contains(
+                "extends JpaRepository<Customer,Long>, JpaSpecificationExecutor<Customer> {"
+            ),
+            "{}",
+            repository
+        );
+        assert!(service.contains("Set<String> sortable = Set.of(\"id\", \"lastName\");"));
+        assert!(service.contains(
+            "cb.like(cb.lower(root.get(\"lastName\")), \"%\" + lastName.toLowerCase() + \"%\")"
+        ));
+        assert!(service.contains("cb.equal(root.get(\"age\"), age)"));
+        assert!(service.contains("return repository.findAll(spec, pageable);"));
+
+        assert!(
+            order_repository
+                .contains("Page<PurchaseOrder>  findByPaid(Boolean paid,Pageable pageable);"),
+            "{}",
+            order_repository
+        );
+        assert!(order_service.contains("return repository.findByPaid(paid, pageable);"));
+        assert!(!order_service.contains("Specification"));
+        assert!(!order_service.contains("Set<String>"));
+    }
+
+    #[test]
+    fn repositories_declare_query_intents() {
+        let top_folder = "generated-queries";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let options = CrudOptions::new()
+            .query(QueryIntent::find_by(vec!["email".into()]))
+            .query(QueryIntent::exists_by(vec![
+                "firstName".into(),
+                "lastName".into(),
+            ]))
+            .query(QueryIntent::count_by(vec!["age".into()]))
+            .query(QueryIntent::top(5, "id".into()).by(vec!["lastName".into()]))
+            .query(QueryIntent::find_all_by(vec!["age".into()]).paged())
+            //not a field of the customer
+            .query(QueryIntent::find_by(vec!["status".into()]))
+            .query(
+                QueryIntent::jpql(
+                    "findAdults".into(),
+                    "select c from Customer c where c.age >= :age".into(),
+                    QueryResult::List,
+                )
+                .param("int".into(), "age".into()),
+            )
+            .query(QueryIntent::native(
+                "countWithEmail".into(),
+                "SELECT COUNT(*) FROM customer WHERE email IS NOT NULL".into(),
+                QueryResult::Count,
+            ));
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity_with(sample_class(&pom_xml), options);
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let repository = read_generated(top_folder, "repositories/CustomerRepository.java");
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        for method in [
+            "Optional<Customer>  findByEmail(String email);",
+            "boolean existsByFirstNameAndLastName(String firstName,String lastName);",
+            "long countByAge(int age);",
+            "List<Customer>  findTop5ByLastNameOrderByIdDesc(String lastName);",
+            "Page<Customer>  findByAge(int age,Pageable pageable);",
+            "@Query (value = \"select c from Customer c where c.age >= :age\")\n\tList<Customer>  findAdults(\n@Param (value = \"age\")int age);",
+            "@Query (value = \"SELECT COUNT(*) FROM customer WHERE email IS NOT NULL\", nativeQuery = true)",
+        ] {
+            assert!(repository.contains(method), "{} in {}", method, repository);
+        }
+        assert!(!repository.contains("findByStatus"));
+        //JpaRepository has it already
+        assert!(!repository.contains("findById"));
+        assert_eq!(repository.matches("import java.util.Optional;").count(), 1);
+    }
+
+    #[test]
+    fn dtos_carry_the_selected_fields() {
+        let top_folder = "generated-dtos";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let customer = JavaClass::new("Customer".into(), "".into())
+            .public()
+            .import(Import::new(
+                "jakarta.validation.constraints".into(),
+                "NotBlank".into(),
+            ))
+            .field(
+                Field::n("firstName".into(), TypeName::new("String".into()))
+                    .annotation("NotBlank".into()),
+            )
+            .field(Field::n("lastName".into(), TypeName::new("String".into())))
+            .field(Field::n("email".into(), TypeName::new("String".into())))
+            .field(Field::n("age".into(), TypeName::new("int".into())));
+        let mapstruct = DtoConfig::new().mapper(MapperStyle::MapStruct);
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity_with(
+                customer,
+                CrudOptions::new().dto(
+                    mapstruct
+                        .clone()
+                        .response_fields(vec!["id".into(), "firstName".into(), "lastName".into()])
+                        .read_only(vec!["email".into()]),
+                ),
+            )
+            .add_entity_with(order_class(), CrudOptions::new().dto(mapstruct))
+            .relationship(Relationship::many_to_one(
+                "PurchaseOrder".into(),
+                "customer".into(),
+                "Customer".into(),
+            ));
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let response = read_generated(top_folder, "dto/CustomerResponse.java");
+        let request = read_generated(top_folder, "dto/CustomerRequest.java");
+        let mapper = read_generated(top_folder, "dto/CustomerMapper.java");
+        let order_mapper = read_generated(top_folder, "dto/PurchaseOrderMapper.java");
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(response.contains("private Long id;"), "{}", response);
+        assert!(response.contains("private String firstName;"));
+        assert!(!response.contains("email"));
+        assert!(!response.contains("int age;"));
+        assert!(!response.contains("jakarta"));
+
+        assert!(
+            request.contains("@NotBlank     private String firstName;"),
+            "{}",
+            request
+        );
+        assert!(request.contains("import jakarta.validation.constraints.NotBlank;"));
+        assert!(request.contains("private int age;"));
+        assert!(!request.contains("email"));
+        assert!(!request.contains(" id;"));
+
+        assert!(
+            mapper.contains("@Mapper (componentModel = \"spring\")"),
+            "{}",
+            mapper
+        );
+        assert!(mapper.contains("abstract class CustomerMapper"));
+        assert!(mapper.contains("@Mapping (target = \"email\", ignore = true)"));
+        assert!(mapper.contains("@Mapping (target = \"id\", ignore = true)"));
+        assert!(mapper.contains("@MappingTarget Customer entity);"));
+
+        assert!(
+            order_mapper.contains("@Mapping (target = \"customerId\", source = \"customer.id\")"),
+            "{}",
+            order_mapper
+        );
+        assert!(order_mapper.contains("@Mapping (target = \"customer\", ignore = true)"));
+        assert!(!order_mapper.contains("customerReference"));
+
+        assert!(pom.contains("<artifactId>mapstruct-processor</artifactId>"));
+        assert!(pom.contains("<artifactId>lombok-mapstruct-binding</artifactId>"));
+    }
+
+    #[test]
+    fn classes_can_be_packaged_by_feature() {
+        let top_folder = "generated-by-feature";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        //the config also applies to the entities added before it
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity(sample_class(&pom_xml))
+            .add_entity(order_class())
+            .relationship(Relationship::one_to_many(
+                "Customer".into(),
+                "orders".into(),
+                "PurchaseOrder".into(),
+                "customer".into(),
+            ))
+            .output(
+                OutputConfig::new()
+                    .packaging(Packaging::ByFeature)
+                    .controller_suffix("Resource".into())
+                    .repository_suffix("Repo".into()),
+            )
+            .security(SecurityConfig::new(AuthMode::Session));
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let controller = read_generated(top_folder, "customer/CustomerResource.java");
+        let service = read_generated(top_folder, "customer/CustomerService.java");
+        let order = read_generated(top_folder, "purchaseorder/PurchaseOrder.java");
+        let repository = read_generated(top_folder, "purchaseorder/PurchaseOrderRepo.java");
+        let user_details = read_generated(top_folder, "security/DatabaseUserDetailsService.java");
+        let admin = read_generated(top_folder, "security/AdminInitializer.java");
+        let layer_folder = Path::new(top_folder)
+            .join("src/main/java/org/javacodegen/rvtool/controllers")
+            .exists();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+        assert!(!layer_folder);
+
+        assert!(
+            controller.starts_with("package org.javacodegen.rvtool.customer;"),
+            "{}",
+            controller
+        );
+        assert!(controller.contains("public class CustomerResource"));
+        assert!(controller
+            .contains("import org.javacodegen.rvtool.purchaseorder.PurchaseOrderService;"));
+        assert!(
+            controller.contains("import org.javacodegen.rvtool.purchaseorder.PurchaseOrderMapper;")
+        );
+        //same package, nothing to import
+        assert!(!controller.contains("import org.javacodegen.rvtool.customer."));
+        assert!(service.contains("private final CustomerRepo repository;"));
+        assert!(order.contains("import org.javacodegen.rvtool.customer.Customer;"));
+        assert!(repository.contains("interface PurchaseOrderRepo"));
+        for security_class in [&user_details, &admin] {
+            assert!(
+                security_class.contains("import org.javacodegen.rvtool.user.UserRepo;"),
+                "{}",
+                security_class
+            );
+            assert!(security_class.contains("UserRepo repository;"));
+            assert!(security_class.contains("(UserRepo repository"));
+            assert!(!security_class.contains("UserRepository"));
+        }
+
+        let output = OutputConfig::new().packaging(Packaging::ByFeature);
+        let package_of = |entity: &str| output.package_of("org.example", Layer::Models, entity);
+        assert_eq!(package_of("Package"), "org.example.package_");
+        assert_eq!(package_of("Default"), "org.example.default_");
+        assert_eq!(package_of("Case"), "org.example.case_");
+        assert_eq!(package_of("Record"), "org.example.record");
+    }
+
+    #[test]
+    fn controllers_answer_with_problem_details() {
+        let top_folder = "generated-problem-details";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity(sample_class(&pom_xml))
+            .add_entity(order_class())
+            .relationship(Relationship::one_to_many(
+                "Customer".into(),
+                "orders".into(),
+                "PurchaseOrder".into(),
+                "customer".into(),
+            ))
+            .security(SecurityConfig::new(AuthMode::Session))
+            .error_handling(ErrorHandling::new().type_base("https://example.com/problems/".into()));
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let advice = read_generated(top_folder, "errors/GlobalExceptionHandler.java");
+        let exception = read_generated(top_folder, "errors/NotFoundException.java");
+        let service = read_generated(top_folder, "services/CustomerService.java");
+        let controller = read_generated(top_folder, "controllers/CustomerController.java");
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(advice.contains("@RestControllerAdvice"), "{}", advice);
+        assert!(advice.contains("ProblemDetail handleNotFound"));
+        assert!(advice.contains("HttpStatus.NOT_FOUND"));
+        assert!(advice.contains("ProblemDetail handleInvalidBody"));
+        assert!(advice.contains("problem.setProperty(\"errors\", errors);"));
+        assert!(advice.contains("ProblemDetail handleConstraintViolation"));
+        assert!(advice.contains("URI.create(\"https://example.com/problems/not-found\")"));
+        //spring security answers 401 and 403 itself
+        assert!(advice.contains("throw exception;"));
+        assert!(advice.contains("\"An unexpected error occurred\""));
+        assert!(exception.contains("public class NotFoundException extends RuntimeException"));
+
+        assert!(
+            service.contains("import org.javacodegen.rvtool.errors.NotFoundException;"),
+            "{}",
+            service
+        );
+        assert!(service.contains("orElseThrow(() -> new NotFoundException(\"Customer\", id))"));
+        assert!(!service.contains("Optional"));
+        assert!(controller.contains("@Valid"), "{}", controller);
+        assert!(controller.contains("ResponseEntity.ok(mapper.toResponse(service.findById(id)))"));
+        assert!(!controller.contains("notFound()"));
+        assert!(pom.contains("spring-boot-starter-validation"));
+    }
+
+    #[test]
+    fn entities_can_be_audited_and_soft_deleted() {
+        let top_folder = "generated-audited";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let options = CrudOptions::new()
+            .auditing()
+            .created_by()
+            .soft_delete()
+            .versioned();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .security(SecurityConfig::new(AuthMode::Session))
+            .add_entity_with(sample_class(&pom_xml), options)
+            .add_entity(order_class());
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let customer = read_generated(top_folder, "models/Customer.java");
+        let order = read_generated(top_folder, "models/PurchaseOrder.java");
+        let main_class = read_generated(top_folder, "TempContRvTool.java");
+        let request = read_generated(top_folder, "dto/CustomerRequest.java");
+        let response = read_generated(top_folder, "dto/CustomerResponse.java");
+        let migration = std::fs::read_to_string(
+            Path::new(top_folder).join("src/main/resources/db/migration/V1__create_schema.sql"),
+        )
+        .unwrap();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(
+            customer.contains("@EntityListeners (value = AuditingEntityListener.class)"),
+            "{}",
+            customer
+        );
+        assert!(customer.contains("private Instant createdAt;"));
+        assert!(customer.contains("@LastModifiedDate"));
+        assert!(customer.contains("@CreatedBy"));
+        assert!(customer.contains("@Version"));
+        assert!(customer.contains(
+            "@SQLDelete (sql = \"UPDATE customer SET deleted = true WHERE id = ? AND version = ?\")"
+        ));
+        assert!(customer.contains("@SQLRestriction (value = \"deleted = false\")"));
+        assert!(customer.contains("private boolean deleted;"));
+        //only the entities that ask for it
+        assert!(!order.contains("deleted"));
+
+        assert!(main_class.contains("@EnableJpaAuditing"), "{}", main_class);
+        assert!(main_class.contains("public AuditorAware<String>"));
+        assert!(main_class.contains("SecurityContextHolder"));
+
+        //the audit fields are read only, the deleted flag is never exposed
+        assert!(
+            response.contains("private Instant createdAt;"),
+            "{}",
+            response
+        );
+        assert!(response.contains("private Long version;"));
+        assert!(!response.contains("deleted"));
+        assert!(!request.contains("createdAt"), "{}", request);
+        assert!(!request.contains("version"));
+        assert!(!request.contains("deleted"));
+
+        assert!(
+            migration.contains("deleted BOOLEAN DEFAULT false NOT NULL"),
+            "{}",
+            migration
+        );
+        assert!(migration.contains("version BIGINT DEFAULT 0"));
+        assert!(migration.contains("created_at TIMESTAMP WITH TIME ZONE"));
+        assert!(migration.contains("created_by VARCHAR(255)"));
+    }
+
+    #[test]
+    fn changed_column_defaults_are_migrated() {
+        let column = |default: Option<&str>| Column {
+            name: "deleted".into(),
+            sql_type: "BOOLEAN".into(),
+            nullable: false,
+            unique: false,
+            identity: false,
+            default: default.map(|d| d.to_owned()),
+        };
+        let changes = |old: Option<&str>, new: Option<&str>| {
+            vec![SchemaChange::AlterColumn(
+                "customer".into(),
+                column(old),
+                column(new),
+            )]
+        };
+        let sql = |old, new, kind| migrations::sql(&changes(old, new), kind);
+
+        assert_eq!(
+            sql(None, Some("false"), DatabaseKind::PostgreSql).trim(),
+            "ALTER TABLE customer ALTER COLUMN deleted SET DEFAULT false;"
+        );
+        assert_eq!(
+            sql(Some("false"), None, DatabaseKind::MySql).trim(),
+            "ALTER TABLE customer ALTER COLUMN deleted DROP DEFAULT;"
+        );
+        assert_eq!(
+            sql(Some("false"), Some("true"), DatabaseKind::Oracle).trim(),
+            "ALTER TABLE customer MODIFY (deleted DEFAULT 1);"
+        );
+        let sql_server = sql(Some("false"), Some("true"), DatabaseKind::SqlServer);
+        assert!(sql_server.contains("DROP CONSTRAINT IF EXISTS df_customer_deleted;"));
+        assert!(sql_server.contains("ADD CONSTRAINT df_customer_deleted DEFAULT 1 FOR deleted;"));
+
+        let changelog = migrations::changelog(&changes(Some("false"), Some("true")), "002");
+        assert!(
+            changelog.find("<dropDefaultValue") < changelog.find("<addDefaultValue"),
+            "{}",
+            changelog
+        );
+        assert!(changelog.contains("defaultValueBoolean=\"true\""));
+        let changelog = migrations::changelog(&changes(Some("false"), None), "003");
+        assert!(
+            changelog.contains("<dropDefaultValue tableName=\"customer\" columnName=\"deleted\"")
+        );
+        assert!(!changelog.contains("<addDefaultValue"));
+    }
+
+    #[test]
+    fn nested_routes_go_through_the_id_of_the_parent() {
+        let top_folder = "generated-nested-own-id";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot();
+        let tag = JavaClass::new("Tag".into(), "".into())
+            .public()
+            .field(Field::n("code".into(), TypeName::new("String".into())).annotation("Id".into()))
+            .field(Field::n("label".into(), TypeName::new("String".into())));
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .add_entity(sample_class(&pom_xml))
+            .add_entity(order_class())
+            .add_entity(tag)
+            .relationship(Relationship::many_to_one(
+                "PurchaseOrder".into(),
+                "tag".into(),
+                "Tag".into(),
+            ))
+            .relationship(Relationship::many_to_many(
+                "Tag".into(),
+                "customers".into(),
+                "Customer".into(),
+            ));
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let order_repository =
+            read_generated(top_folder, "repositories/PurchaseOrderRepository.java");
+        let customer_repository =
+            read_generated(top_folder, "repositories/CustomerRepository.java");
+        let tag_controller = read_generated(top_folder, "controllers/TagController.java");
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(
+            order_repository.contains("findByTagCode(String tagCode,Pageable pageable)"),
+            "{}",
+            order_repository
+        );
+        assert!(!order_repository.contains("TagId"));
+        assert!(
+            customer_repository.contains("join e.customers t where e.code = :id"),
+            "{}",
+            customer_repository
+        );
+        assert!(customer_repository.contains("String id,Pageable pageable"));
+        assert!(
+            tag_controller.contains("purchaseOrderService.findByTagCode(id, pageable)"),
+            "{}",
+            tag_controller
+        );
+    }
+
+    #[test]
+    fn entities_can_choose_their_id_strategy() {
+        let top_folder = "generated-ids";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let line = JavaClass::new("OrderLine".into(), "".into())
+            .public()
+            .field(Field::n("quantity".into(), TypeName::new("Integer".into())));
+        //an input class that brings its own id keeps it
+        let tag = JavaClass::new("Tag".into(), "".into())
+            .public()
+            .field(Field::n("code".into(), TypeName::new("String".into())).annotation("Id".into()))
+            .field(Field::n("label".into(), TypeName::new("String".into())));
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .add_entity_with(
+                sample_class(&pom_xml),
+                CrudOptions::new().id(IdStrategy::uuid()),
+            )
+            .add_entity_with(
+                order_class(),
+                CrudOptions::new().id(IdStrategy::sequence("order_seq".into())),
+            )
+            .add_entity_with(
+                line,
+                CrudOptions::new().id(IdStrategy::composite(
+                    "OrderLineKey".into(),
+                    vec![
+                        ("orderId".into(), "Long".into()),
+                        ("lineNo".into(), "Integer".into()),
+                    ],
+                )),
+            )
+            .add_entity(tag);
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let customer = read_generated(top_folder, "models/Customer.java");
+        let customer_repository =
+            read_generated(top_folder, "repositories/CustomerRepository.java");
+        let customer_controller = read_generated(top_folder, "controllers/CustomerController.java");
+        let order = read_generated(top_folder, "models/PurchaseOrder.java");
+        let key = read_generated(top_folder, "models/OrderLineKey.java");
+        let line_controller = read_generated(top_folder, "controllers/OrderLineController.java");
+        let tag_repository = read_generated(top_folder, "repositories/TagRepository.java");
+        let tag_request = read_generated(top_folder, "dto/TagRequest.java");
+        let tag = read_generated(top_folder, "models/Tag.java");
+        let migration = std::fs::read_to_string(
+            Path::new(top_folder).join("src/main/resources/db/migration/V1__create_schema.sql"),
+        )
+        .unwrap();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(
+            customer.contains("@GeneratedValue (strategy = GenerationType.UUID)"),
+            "{}",
+            customer
+        );
+        assert!(customer.contains("private UUID id;"));
+        assert!(customer.contains("import java.util.UUID;"));
+        assert!(
+            customer_repository.contains("JpaRepository<Customer,UUID"),
+            "{}",
+            customer_repository
+        );
+        assert!(
+            customer_controller.contains("@PathVariable UUID id"),
+            "{}",
+            customer_controller
+        );
+
+        assert!(
+            order.contains("@SequenceGenerator (name = \"order_seq\", sequenceName = \"order_seq\", allocationSize = 50)"),
+            "{}",
+            order
+        );
+        assert!(
+            migration.contains("CREATE SEQUENCE order_seq START WITH 1 INCREMENT BY 50;"),
+            "{}",
+            migration
+        );
+        //the reference to a uuid entity
+        assert!(migration.contains("customer_id UUID"));
+
+        assert!(key.contains("implements Serializable"), "{}", key);
+        assert!(key.contains("private Integer lineNo;"));
+        assert!(
+            line_controller.contains("/{lineNo}/{orderId}"),
+            "{}",
+            line_controller
+        );
+        assert!(line_controller.contains("id.setOrderId(orderId);"));
+        assert!(migration.contains("PRIMARY KEY (line_no, order_id)"));
+
+        assert!(
+            tag_repository.contains("JpaRepository<Tag,String"),
+            "{}",
+            tag_repository
+        );
+        assert!(tag.contains("import jakarta.persistence.Id;"), "{}", tag);
+        //assigned ids come with the request
+        assert!(
+            tag_request.contains("private String code;"),
+            "{}",
+            tag_request
+        );
+    }
+
+    fn read_generated_test(top_folder: &str, file: &str) -> String {
+        let path = Path::new(top_folder)
+            .join("src/test/java/org/javacodegen/rvtool")
+            .join(file);
+        std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("{} was not generated", file))
+    }
+
+    #[test]
+    fn generates_test_suites_for_the_crud_classes() {
+        let top_folder = "generated-test-suites";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql()
+            .testcontainers_postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .security(SecurityConfig::new(AuthMode::Session))
+            .error_handling(ErrorHandling::new())
+            .add_entity_with(sample_class(&pom_xml), CrudOptions::new().auditing())
+            .add_entity(order_class())
+            .relationship(Relationship::one_to_many(
+                "Customer".into(),
+                "orders".into(),
+                "PurchaseOrder".into(),
+                "customer".into(),
+            ));
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let controller_test =
+            read_generated_test(top_folder, "controllers/CustomerControllerTest.java");
+        let repository_test =
+            read_generated_test(top_folder, "repositories/CustomerRepositoryTest.java");
+        let integration_test =
+            read_generated_test(top_folder, "controllers/CustomerIntegrationTest.java");
+        let containers = read_generated_test(top_folder, "TestcontainersConfiguration.java");
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(
+            controller_test.contains("@WebMvcTest (controllers = CustomerController.class)"),
+            "{}",
+            controller_test
+        );
+        assert!(controller_test.contains("@AutoConfigureMockMvc (addFilters = false)"));
+        assert!(controller_test.contains("@MockitoBean"));
+        //the children of the nested route are looked up too
+        assert!(controller_test.contains("private PurchaseOrderService purchaseOrderService;"));
+        //@EnableJpaAuditing of the main class needs it in the web slice
+        assert!(controller_test.contains("private JpaMetamodelMappingContext"));
+        assert!(controller_test.contains("willThrow(new NotFoundException(\"Customer\", id))"));
+        assert!(controller_test.contains("status().isCreated()"));
+
+        assert!(
+            repository_test.contains(
+                "@AutoConfigureTestDatabase (replace = AutoConfigureTestDatabase.Replace.NONE)"
+            ),
+            "{}",
+            repository_test
+        );
+        assert!(repository_test.contains("@Import (value = TestcontainersConfiguration.class)"));
+        assert!(repository_test.contains("customer.setEmail(\"sample\");"));
+
+        assert!(
+            integration_test.contains("@SpringBootTest"),
+            "{}",
+            integration_test
+        );
+        assert!(integration_test.contains("@WithMockUser"));
+        assert!(containers.contains("DockerImageName.parse(\"postgres:16-alpine\")"));
+
+        for artifact in [
+            "spring-boot-starter-test",
+            "spring-boot-testcontainers",
+            "spring-security-test",
+        ] {
+            assert!(pom.contains(&format!("<artifactId>{}</artifactId>", artifact)));
+        }
+    }
+
+    #[test]
+    fn repository_tests_run_on_h2_without_a_container() {
+        let top_folder = "generated-test-suites-h2";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .add_entity(sample_class(&pom_xml));
+        codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let repository_test =
+            read_generated_test(top_folder, "repositories/CustomerRepositoryTest.java");
+        let has_integration_test = Path::new(top_folder)
+            .join("src/test/java/org/javacodegen/rvtool/controllers/CustomerIntegrationTest.java")
+            .exists();
+        let pom = std::fs::read_to_string(Path::new(top_folder).join("pom.xml")).unwrap();
+        cleanup_folder(top_folder);
+
+        assert!(
+            repository_test.contains("\"spring.flyway.enabled=false\""),
+            "{}",
+            repository_test
+        );
+        assert!(!has_integration_test);
+        assert!(pom.contains("<artifactId>h2</artifactId>"), "{}", pom);
+
+        //nothing is written when the project keeps its own tests
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .without_tests()
+            .add_entity(sample_class(&pom_xml));
+        codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let has_tests = Path::new(top_folder)
+            .join("src/test/java/org/javacodegen/rvtool/controllers")
+            .exists();
+        cleanup_folder(top_folder);
+        assert!(!has_tests);
+    }
+
+    fn named_class(name: &str, field: &str) -> JavaClass {
+        JavaClass::new(name.into(), "".into())
+            .public()
+            .field(Field::n(field.into(), TypeName::new("String".into())))
+    }
+
+    #[test]
+    fn can_map_relationships_between_entities() {
+        let top_folder = "generated-relationships";
+        let pom_xml = PomXml::new(sample_project_info())
+            .java_version("17".to_owned())
+            .spring_boot()
+            .postgresql();
+        //the order is added after the relationship, it still gets its side
+        let mut codebase = MavenCodebase::new(pom_xml.clone(), top_folder)
+            .migrations(MigrationTool::Flyway)
+            .add_entity(sample_class(&pom_xml))
+            .relationship(
+                Relationship::one_to_many(
+                    "Customer".into(),
+                    "orders".into(),
+                    "PurchaseOrder".into(),
+                    "customer".into(),
+                )
+                .cascade(vec![Cascade::All]),
+            )
+            .add_entity(order_class())
+            .add_entity(named_class("Student", "name"))
+            .add_entity(named_class("Course", "title"))
+            .relationship(
+                Relationship::many_to_many("Student".into(), "courses".into(), "Course".into())
+                    .inverse("students".into()),
+            );
+        let report = codebase
+            .generate_code()
+            .expect("Generated code has syntax errors");
+        let customer = read_generated(top_folder, "models/Customer.java");
+        let order = read_generated(top_folder, "models/PurchaseOrder.java");
+        let order_dto = read_generated(top_folder, "dto/PurchaseOrderResponse.java");
+        let order_mapper = read_generated(top_folder, "dto/PurchaseOrderMapper.java");
+        let order_service = read_generated(top_folder, "services/PurchaseOrderService.java");
+        let order_controller =
+            read_generated(top_folder, "controllers/PurchaseOrderController.java");
+        let customer_dto = read_generated(top_folder, "dto/CustomerResponse.java");
+        let customer_controller = read_generated(top_folder, "controllers/CustomerController.java");
+        let order_repository =
+            read_generated(top_folder, "repositories/PurchaseOrderRepository.java");
+        let student = read_generated(top_folder, "models/Student.java");
+        let course = read_generated(top_folder, "models/Course.java");
+        let course_repository = read_generated(top_folder, "repositories/CourseRepository.java");
+        let migration = std::fs::read_to_string(
+            Path::new(top_folder).join("src/main/resources/db/migration/V1__create_schema.sql"),
+        )
+        .unwrap();
+        cleanup_folder(top_folder);
+        assert!(
+            !report.diagnostics.iter().any(|d| d.is_error()),
+            "{}",
+            report
+        );
+
+        assert!(
+            customer.contains("@OneToMany (mappedBy = \"customer\", cascade = CascadeType.ALL)"),
+            "{}",
+            customer
+        );
+        assert!(customer.contains("private List<PurchaseOrder>  orders = new ArrayList<>();"));
+        assert!(customer.contains("@ToString.Exclude"));
+        assert!(customer.contains("import java.util.ArrayList;"));
+        assert!(
+            order.contains("@ManyToOne (fetch = FetchType.LAZY)"),
+            "{}",
+            order
+        );
+        assert!(order.contains("@JoinColumn (name = \"customer_id\")"));
+        assert_eq!(order.matches("Customer customer;").count(), 1);
+
+        assert!(
+            order_dto.contains("private Long customerId;"),
+            "{}",
+            order_dto
+        );
+        assert!(!order_dto.contains("Customer customer;"));
+        assert!(order_mapper.contains(
+            "response.setCustomerId(entity.getCustomer() == null ? null : entity.getCustomer().getId());"
+        ));
+        //the customer is looked up instead of being built from its id
+        assert!(!order_mapper.contains("new Customer()"), "{}", order_mapper);
+        assert!(!order_mapper.contains("entity.setCustomer("));
+        assert!(
+            order_service.contains("public Customer customerReference(Long id)"),
+            "{}",
+            order_service
+        );
+        assert!(order_service.contains("return customerRepository.findById(id)\n"));
+        assert!(order_service.contains(
+            "new ResponseStatusException(HttpStatus.NOT_FOUND, \"Customer \" + id + \" was not found\")"
+        ));
+        assert!(order_service.contains("this.customerRepository = customerRepository;"));
+        assert!(
+            order_controller.contains(
+                "var entity = mapper.toEntity(request);\n\tentity.setCustomer(service.customerReference(request.getCustomerId()));\n\tvar created = service.create(entity);"
+            ),
+            "{}",
+            order_controller
+        );
+        assert!(order_controller.contains(
+            "entity.setCustomer(service.customerReference(request.getCustomerId()));\n\t})"
+        ));
+        assert!(!customer_dto.contains("orders"));
+
+        assert!(customer_controller.contains("@GetMapping (value = \"/{id}/orders\")"));
+        assert!(
+            customer_controller.contains(
+                "purchaseOrderService.findByCustomerId(id, pageable).map(purchaseOrderMapper::toResponse)"
+            ),
+            "{}",
+            customer_controller
+        );
+        assert!(customer_controller.contains("this.purchaseOrderService = purchaseOrderService;"));
+        assert!(order_repository
+            .contains("Page<PurchaseOrder>  findByCustomerId(Long customerId,Pageable pageable);"));
+
+        assert!(student.contains(
+            "@JoinTable (name = \"student_courses\", joinColumns = @JoinColumn(name = \"student_id\"), inverseJoinColumns = @JoinColumn(name = \"course_id\"))"
+        ), "{}", student);
+        assert!(student.contains("private Set<Course>  courses = new HashSet<>();"));
+        assert!(course.contains("@ManyToMany (mappedBy = \"courses\")"));
+        assert!(course_repository.contains(
+            "@Query (value = \"select t from Student e join e.courses t where e.id = :id\")"
+        ));
+        assert!(
+            migration.contains("CREATE TABLE student_courses ("),
+            "{}",
+            migration
+        );
+        assert!(migration.contains("PRIMARY KEY (student_id, course_id)"));
+        assert!(migration.contains("REFERENCES customer (id)"));
+    }
 }